@@ -0,0 +1,356 @@
+//! Baseline snapshot store for visual regression runs.
+//!
+//! A store is a directory of view bundles keyed by scenario name and viewport:
+//!
+//! ```text
+//! <root>/<scenario>/<WxH>/          approved baseline
+//! <root>/<scenario>/<WxH>.pending/  last impl capture, promoted by `approve`
+//! ```
+//!
+//! Each entry is a [view bundle](crate::view_bundle) plus `meta.json`
+//! ([`BaselineMetadata`]), so baselines can also be replayed with `--ref-type view`.
+//! Browser captures also record their engine and device, and a capture rendered with
+//! different ones is refused instead of compared.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::browser::{BrowserEngine, DeviceEmulation};
+use crate::error::{DpcError, Result};
+use crate::output::ResourceDescriptor;
+use crate::types::{NormalizedView, Viewport};
+use crate::view_bundle::{read_view_bundle, write_view_bundle, VIEW_FILE};
+
+/// Default store location, relative to the working directory.
+pub const DEFAULT_BASELINE_DIR: &str = ".dpc/baselines";
+
+const META_FILE: &str = "meta.json";
+const PENDING_SUFFIX: &str = ".pending";
+
+/// Where a stored capture came from.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineMetadata {
+    pub scenario: String,
+    pub viewport: Viewport,
+    pub source: ResourceDescriptor,
+    /// Capture time in seconds since the Unix epoch.
+    pub captured_at: u64,
+    /// Browser settings of a URL or HTML capture; unset for other inputs and for
+    /// baselines recorded before it was stored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub render: Option<BaselineRender>,
+}
+
+/// Engine and device emulation a browser capture was rendered with.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineRender {
+    pub engine: BrowserEngine,
+    pub device: DeviceEmulation,
+}
+
+impl BaselineMetadata {
+    /// Fail when this baseline and a capture rendered with `render` both came from a
+    /// browser but with a different engine or device: the diff would measure that, not
+    /// the change.
+    pub fn check_render(&self, render: Option<&BaselineRender>) -> Result<()> {
+        match (&self.render, render) {
+            (Some(recorded), Some(current)) if recorded != current => {
+                Err(DpcError::Config(format!(
+                    "Baseline '{}' at {} was recorded in {} ({}) but this run renders in {} ({}); pass matching --browser/--device flags or re-record the baseline",
+                    self.scenario,
+                    self.viewport,
+                    recorded.engine.as_str(),
+                    recorded.device.describe(),
+                    current.engine.as_str(),
+                    current.device.describe()
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Directory-backed store of baseline and pending captures.
+#[derive(Debug, Clone)]
+pub struct BaselineStore {
+    root: PathBuf,
+}
+
+impl BaselineStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Bundle directory of the approved baseline for a scenario/viewport.
+    pub fn baseline_dir(&self, scenario: &str, viewport: &Viewport) -> Result<PathBuf> {
+        Ok(self.scenario_dir(scenario)?.join(viewport.to_string()))
+    }
+
+    /// Bundle directory of the last unapproved capture for a scenario/viewport.
+    pub fn pending_dir(&self, scenario: &str, viewport: &Viewport) -> Result<PathBuf> {
+        Ok(self
+            .scenario_dir(scenario)?
+            .join(format!("{viewport}{PENDING_SUFFIX}")))
+    }
+
+    /// Store `view` as the approved baseline, replacing any previous one.
+    pub fn record(&self, meta: &BaselineMetadata, view: &NormalizedView) -> Result<PathBuf> {
+        let dir = self.baseline_dir(&meta.scenario, &meta.viewport)?;
+        write_bundle(&dir, meta, view)?;
+        Ok(dir)
+    }
+
+    /// Store `view` as the pending capture, replacing any previous one.
+    pub fn record_pending(
+        &self,
+        meta: &BaselineMetadata,
+        view: &NormalizedView,
+    ) -> Result<PathBuf> {
+        let dir = self.pending_dir(&meta.scenario, &meta.viewport)?;
+        write_bundle(&dir, meta, view)?;
+        Ok(dir)
+    }
+
+    /// Load the approved baseline; the view's screenshot path points into the store.
+    pub fn load(
+        &self,
+        scenario: &str,
+        viewport: &Viewport,
+    ) -> Result<(BaselineMetadata, NormalizedView)> {
+        let dir = self.baseline_dir(scenario, viewport)?;
+        if !dir.join(VIEW_FILE).is_file() {
+            return Err(DpcError::Config(format!(
+                "No baseline recorded for scenario '{scenario}' at {viewport} in {}; run `dpc baseline record` first",
+                self.root.display()
+            )));
+        }
+        let meta = read_json(&dir.join(META_FILE))?;
+        let view = read_view_bundle(&dir)?;
+        Ok((meta, view))
+    }
+
+    /// Promote the pending capture to the baseline and return the baseline directory.
+    pub fn approve(&self, scenario: &str, viewport: &Viewport) -> Result<PathBuf> {
+        let pending = self.pending_dir(scenario, viewport)?;
+        if !pending.join(VIEW_FILE).is_file() {
+            return Err(DpcError::Config(format!(
+                "No pending capture for scenario '{scenario}' at {viewport}; run `dpc baseline compare` first"
+            )));
+        }
+        let baseline = self.baseline_dir(scenario, viewport)?;
+        if baseline.exists() {
+            fs::remove_dir_all(&baseline)?;
+        }
+        fs::rename(&pending, &baseline)?;
+        Ok(baseline)
+    }
+
+    /// Viewports that have a pending capture for `scenario`, sorted by width then height.
+    pub fn pending_viewports(&self, scenario: &str) -> Result<Vec<Viewport>> {
+        let dir = self.scenario_dir(scenario)?;
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut viewports = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let name = entry?.file_name();
+            let Some(name) = name.to_str() else {
+                continue;
+            };
+            if let Some(vp) = name
+                .strip_suffix(PENDING_SUFFIX)
+                .and_then(|raw| raw.parse::<Viewport>().ok())
+            {
+                viewports.push(vp);
+            }
+        }
+        viewports.sort_by_key(|vp| (vp.width, vp.height));
+        Ok(viewports)
+    }
+
+    fn scenario_dir(&self, scenario: &str) -> Result<PathBuf> {
+        validate_scenario(scenario)?;
+        Ok(self.root.join(scenario))
+    }
+}
+
+/// Scenario names become directory names, so keep them to a portable character set.
+fn validate_scenario(scenario: &str) -> Result<()> {
+    let valid = !scenario.is_empty()
+        && !scenario.starts_with('.')
+        && scenario
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+    if valid {
+        Ok(())
+    } else {
+        Err(DpcError::Config(format!(
+            "Invalid baseline scenario name '{scenario}': use letters, digits, '-', '_' or '.'"
+        )))
+    }
+}
+
+fn write_bundle(dir: &Path, meta: &BaselineMetadata, view: &NormalizedView) -> Result<()> {
+    write_view_bundle(dir, view)?;
+    fs::write(dir.join(META_FILE), serde_json::to_vec_pretty(meta)?)?;
+    Ok(())
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T> {
+    let data = fs::read(path)?;
+    Ok(serde_json::from_slice(&data)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::ResourceKind;
+    use crate::view_bundle::SCREENSHOT_FILE;
+    use image::RgbaImage;
+    use tempfile::TempDir;
+
+    fn capture(dir: &Path, name: &str, color: [u8; 4]) -> NormalizedView {
+        let path = dir.join(name);
+        RgbaImage::from_pixel(4, 4, image::Rgba(color))
+            .save(&path)
+            .expect("write screenshot");
+        NormalizedView {
+            kind: ResourceKind::Url,
+            screenshot_path: path,
+            width: 4,
+            height: 4,
+            dom: None,
+            figma_tree: None,
+            ocr_blocks: None,
+            diagnostics: None,
+        }
+    }
+
+    fn meta(scenario: &str, viewport: Viewport) -> BaselineMetadata {
+        BaselineMetadata {
+            scenario: scenario.to_string(),
+            viewport,
+            source: ResourceDescriptor {
+                kind: ResourceKind::Url,
+                value: "https://example.com".to_string(),
+            },
+            captured_at: 0,
+            render: Some(BaselineRender {
+                engine: BrowserEngine::Chromium,
+                device: DeviceEmulation::default(),
+            }),
+        }
+    }
+
+    #[test]
+    fn record_and_load_round_trip() {
+        let dir = TempDir::new().expect("tempdir");
+        let store = BaselineStore::new(dir.path().join("store"));
+        let viewport = Viewport {
+            width: 375,
+            height: 812,
+        };
+        let view = capture(dir.path(), "shot.png", [1, 2, 3, 255]);
+
+        let recorded = store
+            .record(&meta("home", viewport), &view)
+            .expect("record");
+        assert_eq!(
+            recorded,
+            dir.path().join("store").join("home").join("375x812")
+        );
+
+        let (loaded_meta, loaded) = store.load("home", &viewport).expect("load");
+        assert_eq!(loaded_meta.source.value, "https://example.com");
+        assert_eq!(loaded.screenshot_path, recorded.join(SCREENSHOT_FILE));
+        assert_eq!((loaded.width, loaded.height), (4, 4));
+    }
+
+    #[test]
+    fn check_render_refuses_other_engines_and_devices() {
+        let dir = TempDir::new().expect("tempdir");
+        let store = BaselineStore::new(dir.path());
+        let view = capture(dir.path(), "shot.png", [1, 2, 3, 255]);
+        store
+            .record(&meta("home", Viewport::default()), &view)
+            .expect("record");
+        let (recorded, _) = store.load("home", &Viewport::default()).expect("load");
+
+        let same = recorded.render.clone().expect("render recorded");
+        assert!(recorded.check_render(Some(&same)).is_ok());
+        // Image inputs and old baselines carry no render settings to compare.
+        assert!(recorded.check_render(None).is_ok());
+        assert!(BaselineMetadata {
+            render: None,
+            ..recorded.clone()
+        }
+        .check_render(Some(&same))
+        .is_ok());
+
+        let firefox = BaselineRender {
+            engine: BrowserEngine::Firefox,
+            ..same.clone()
+        };
+        let err = recorded.check_render(Some(&firefox)).unwrap_err();
+        assert!(err.to_string().contains("recorded in chromium"), "{err}");
+        let retina = BaselineRender {
+            device: DeviceEmulation {
+                device_scale_factor: 2.0,
+                ..DeviceEmulation::default()
+            },
+            ..same
+        };
+        assert!(recorded.check_render(Some(&retina)).is_err());
+    }
+
+    #[test]
+    fn load_reports_missing_baseline() {
+        let dir = TempDir::new().expect("tempdir");
+        let store = BaselineStore::new(dir.path());
+        let err = store
+            .load("home", &Viewport::default())
+            .expect_err("missing baseline");
+        assert!(err.to_string().contains("dpc baseline record"));
+    }
+
+    #[test]
+    fn approve_promotes_pending_capture() {
+        let dir = TempDir::new().expect("tempdir");
+        let store = BaselineStore::new(dir.path().join("store"));
+        let viewport = Viewport::default();
+        let old = capture(dir.path(), "old.png", [0, 0, 0, 255]);
+        let new = capture(dir.path(), "new.png", [255, 255, 255, 255]);
+        store.record(&meta("home", viewport), &old).expect("record");
+        store
+            .record_pending(&meta("home", viewport), &new)
+            .expect("pending");
+        assert_eq!(store.pending_viewports("home").unwrap(), vec![viewport]);
+
+        store.approve("home", &viewport).expect("approve");
+
+        let (_, loaded) = store.load("home", &viewport).expect("load");
+        let pixel = image::open(&loaded.screenshot_path).unwrap().to_rgba8();
+        assert_eq!(pixel.get_pixel(0, 0).0, [255, 255, 255, 255]);
+        assert!(store.pending_viewports("home").unwrap().is_empty());
+        assert!(store.approve("home", &viewport).is_err());
+    }
+
+    #[test]
+    fn rejects_scenario_names_that_escape_the_store() {
+        let store = BaselineStore::new("/tmp/store");
+        for name in ["", "../home", "a/b", ".hidden"] {
+            assert!(
+                store.baseline_dir(name, &Viewport::default()).is_err(),
+                "{name} should be rejected"
+            );
+        }
+    }
+}
@@ -0,0 +1,331 @@
+//! Credentials applied to the browser context before navigation.
+//!
+//! Secret values (header values, basic-auth passwords) may be written as `env:NAME` to
+//! read them from an environment variable, so they stay out of config files and logs.
+
+use crate::{DpcError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Prefix marking a secret value that should be read from an environment variable.
+pub const ENV_SECRET_PREFIX: &str = "env:";
+
+/// Headers, cookies, HTTP basic auth and Playwright storage state for URL renders.
+///
+/// `Debug` only prints header and cookie names, never their values.
+#[derive(Clone, Default, PartialEq)]
+pub struct AuthOptions {
+    /// Extra HTTP headers sent with every request.
+    pub headers: BTreeMap<String, String>,
+    /// Cookies added to the context before navigation.
+    pub cookies: Vec<Cookie>,
+    /// Credentials answered to HTTP basic-auth challenges.
+    pub basic_auth: Option<BasicAuth>,
+    /// Playwright `storageState` JSON (cookies + localStorage), e.g. saved after a login.
+    pub storage_state: Option<PathBuf>,
+}
+
+/// HTTP basic-auth credentials.
+#[derive(Clone, PartialEq, Eq, Serialize)]
+pub struct BasicAuth {
+    pub username: String,
+    pub password: String,
+}
+
+/// A cookie in Playwright's `addCookies` shape.
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Cookie {
+    pub name: String,
+    pub value: String,
+    pub domain: String,
+    #[serde(default = "default_cookie_path")]
+    pub path: String,
+    /// Unix time in seconds; `-1` for a session cookie.
+    #[serde(default = "session_expiry", alias = "expirationDate")]
+    pub expires: f64,
+    #[serde(default)]
+    pub http_only: bool,
+    #[serde(default)]
+    pub secure: bool,
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_same_site"
+    )]
+    pub same_site: Option<String>,
+}
+
+fn default_cookie_path() -> String {
+    "/".to_string()
+}
+
+fn session_expiry() -> f64 {
+    -1.0
+}
+
+/// Accept Playwright (`Lax`), Chrome DevTools (`lax`) and browser-extension
+/// (`no_restriction`, `unspecified`) spellings.
+fn deserialize_same_site<'de, D>(deserializer: D) -> std::result::Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let raw: Option<String> = Option::deserialize(deserializer)?;
+    Ok(
+        raw.and_then(|value| match value.to_ascii_lowercase().as_str() {
+            "strict" => Some("Strict".to_string()),
+            "lax" => Some("Lax".to_string()),
+            "none" | "no_restriction" => Some("None".to_string()),
+            _ => None,
+        }),
+    )
+}
+
+impl AuthOptions {
+    /// True when nothing needs to be applied to the browser context.
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+            && self.cookies.is_empty()
+            && self.basic_auth.is_none()
+            && self.storage_state.is_none()
+    }
+
+    /// One-line description safe for logs: names and paths only, never secret values.
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if !self.headers.is_empty() {
+            let names: Vec<&str> = self.headers.keys().map(String::as_str).collect();
+            parts.push(format!("headers [{}]", names.join(", ")));
+        }
+        if !self.cookies.is_empty() {
+            parts.push(format!("{} cookie(s)", self.cookies.len()));
+        }
+        if let Some(basic) = &self.basic_auth {
+            parts.push(format!("basic auth as {}", basic.username));
+        }
+        if let Some(path) = &self.storage_state {
+            parts.push(format!("storage state {}", path.display()));
+        }
+        if parts.is_empty() {
+            "none".to_string()
+        } else {
+            parts.join(", ")
+        }
+    }
+
+    /// Browser-context settings for the Playwright scripts: `extraHTTPHeaders`,
+    /// `httpCredentials` and `storageState` go to `newContext`, `cookies` to `addCookies`.
+    pub(crate) fn to_script_value(&self) -> serde_json::Value {
+        let mut value = serde_json::json!({});
+        if !self.headers.is_empty() {
+            value["extraHTTPHeaders"] = serde_json::json!(self.headers);
+        }
+        if !self.cookies.is_empty() {
+            value["cookies"] = serde_json::json!(self.cookies);
+        }
+        if let Some(basic) = &self.basic_auth {
+            value["httpCredentials"] = serde_json::json!(basic);
+        }
+        if let Some(path) = &self.storage_state {
+            value["storageState"] = serde_json::json!(path.to_string_lossy());
+        }
+        value
+    }
+}
+
+impl fmt::Debug for AuthOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuthOptions")
+            .field("headers", &self.headers.keys().collect::<Vec<_>>())
+            .field(
+                "cookies",
+                &self.cookies.iter().map(|c| &c.name).collect::<Vec<_>>(),
+            )
+            .field("basic_auth", &self.basic_auth)
+            .field("storage_state", &self.storage_state)
+            .finish()
+    }
+}
+
+impl fmt::Debug for BasicAuth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BasicAuth")
+            .field("username", &self.username)
+            .field("password", &"<redacted>")
+            .finish()
+    }
+}
+
+impl fmt::Debug for Cookie {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Cookie")
+            .field("name", &self.name)
+            .field("domain", &self.domain)
+            .field("path", &self.path)
+            .finish_non_exhaustive()
+    }
+}
+
+impl BasicAuth {
+    /// Parse `USER:PASSWORD`; the password may be `env:NAME`.
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (username, password) = spec.split_once(':').ok_or_else(|| {
+            DpcError::Config(
+                "Basic auth must be USER:PASSWORD (password may be env:NAME)".to_string(),
+            )
+        })?;
+        Ok(Self {
+            username: username.to_string(),
+            password: resolve_secret(password, "basic auth password")?,
+        })
+    }
+}
+
+/// Resolve a possibly `env:NAME` value; `what` names the setting in error messages.
+pub fn resolve_secret(value: &str, what: &str) -> Result<String> {
+    match value.strip_prefix(ENV_SECRET_PREFIX) {
+        Some(name) => std::env::var(name).map_err(|_| {
+            DpcError::Config(format!(
+                "Environment variable {name} (used for {what}) is not set"
+            ))
+        }),
+        None => Ok(value.to_string()),
+    }
+}
+
+/// Parse a `Name: value` header; the value may be `env:NAME`.
+pub fn parse_header(spec: &str) -> Result<(String, String)> {
+    let (name, value) = spec
+        .split_once(':')
+        .map(|(name, value)| (name.trim(), value.trim()))
+        .filter(|(name, _)| !name.is_empty())
+        .ok_or_else(|| {
+            DpcError::Config(format!(
+                "Invalid header {spec:?}; expected \"Name: value\" (value may be env:NAME)"
+            ))
+        })?;
+    let value = resolve_secret(value, &format!("header {name}"))?;
+    Ok((name.to_string(), value))
+}
+
+/// Load cookies from a Netscape `cookies.txt` or a JSON export (an array of cookies, or
+/// an object with a `cookies` array such as a Playwright storage state).
+pub fn load_cookie_file(path: &Path) -> Result<Vec<Cookie>> {
+    let data = fs::read_to_string(path).map_err(|err| {
+        DpcError::Config(format!(
+            "Failed to read cookie file {}: {err}",
+            path.display()
+        ))
+    })?;
+    let invalid = |detail: String| {
+        DpcError::Config(format!("Invalid cookie file {}: {detail}", path.display()))
+    };
+
+    let trimmed = data.trim_start();
+    if trimmed.starts_with('[') || trimmed.starts_with('{') {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum CookieJson {
+            List(Vec<Cookie>),
+            Wrapped { cookies: Vec<Cookie> },
+        }
+        return match serde_json::from_str(trimmed).map_err(|e| invalid(e.to_string()))? {
+            CookieJson::List(cookies) | CookieJson::Wrapped { cookies } => Ok(cookies),
+        };
+    }
+
+    let mut cookies = Vec::new();
+    for (index, line) in data.lines().enumerate() {
+        let (line, http_only) = match line.strip_prefix("#HttpOnly_") {
+            Some(rest) => (rest, true),
+            None => (line, false),
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let fields: Vec<&str> = line.split('\t').collect();
+        let [domain, _include_subdomains, path, secure, expires, name, value] = fields[..] else {
+            return Err(invalid(format!(
+                "line {} is not a Netscape cookie (7 tab-separated fields)",
+                index + 1
+            )));
+        };
+        let expires: f64 = expires
+            .parse()
+            .map_err(|_| invalid(format!("line {} has a non-numeric expiry", index + 1)))?;
+        cookies.push(Cookie {
+            name: name.to_string(),
+            value: value.to_string(),
+            domain: domain.to_string(),
+            path: path.to_string(),
+            // Netscape files use 0 for session cookies.
+            expires: if expires > 0.0 { expires } else { -1.0 },
+            http_only,
+            secure: secure.eq_ignore_ascii_case("TRUE"),
+            same_site: None,
+        });
+    }
+    Ok(cookies)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn loads_netscape_and_json_cookie_files() {
+        let mut netscape = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            netscape,
+            "# Netscape HTTP Cookie File\n\
+             .example.com\tTRUE\t/\tTRUE\t0\tsession\tabc\n\
+             #HttpOnly_app.example.com\tFALSE\t/app\tFALSE\t1900000000\tauth\txyz\n"
+        )
+        .unwrap();
+        let cookies = load_cookie_file(netscape.path()).unwrap();
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[0].expires, -1.0);
+        assert!(cookies[0].secure && !cookies[0].http_only);
+        assert_eq!(cookies[1].domain, "app.example.com");
+        assert_eq!(cookies[1].path, "/app");
+        assert!(cookies[1].http_only);
+
+        let mut json = tempfile::NamedTempFile::new().unwrap();
+        write!(
+            json,
+            r#"{{"cookies": [{{"name": "sid", "value": "1", "domain": "example.com",
+                "expirationDate": 1900000000, "sameSite": "no_restriction"}}]}}"#
+        )
+        .unwrap();
+        let cookies = load_cookie_file(json.path()).unwrap();
+        assert_eq!(cookies[0].path, "/");
+        assert_eq!(cookies[0].expires, 1_900_000_000.0);
+        assert_eq!(cookies[0].same_site.as_deref(), Some("None"));
+    }
+
+    #[test]
+    fn secrets_resolve_from_env_and_stay_out_of_debug() {
+        std::env::set_var("DPC_TEST_AUTH_SECRET", "s3cret");
+        let (name, value) = parse_header("Authorization: env:DPC_TEST_AUTH_SECRET").unwrap();
+        let basic = BasicAuth::parse("admin:env:DPC_TEST_AUTH_SECRET").unwrap();
+        assert_eq!((name.as_str(), value.as_str()), ("Authorization", "s3cret"));
+        assert_eq!(basic.password, "s3cret");
+
+        let auth = AuthOptions {
+            headers: BTreeMap::from([(name, value)]),
+            basic_auth: Some(basic),
+            ..AuthOptions::default()
+        };
+        assert!(!format!("{auth:?}").contains("s3cret"));
+        assert!(!auth.describe().contains("s3cret"));
+        assert!(auth.describe().contains("Authorization"));
+
+        let err = parse_header("X-Token: env:DPC_TEST_AUTH_UNSET").unwrap_err();
+        assert!(err.to_string().contains("DPC_TEST_AUTH_UNSET"));
+        assert!(parse_header("no-colon").is_err());
+    }
+}
@@ -0,0 +1,599 @@
+//! Native Chrome DevTools Protocol backend (`playwright` cargo feature).
+//!
+//! Drives a local Chrome/Chromium through `chromiumoxide` instead of spawning Node.
+//! It captures the same screenshot and runs the same DOM traversal as the Playwright
+//! script, so the resulting [`NormalizedView`] is interchangeable.
+
+use super::auth::AuthOptions;
+use super::diagnostics::{reported, DIAGNOSTICS_COLLECT_FN, DIAGNOSTICS_INIT_FN};
+use super::dom::{convert_raw_dom, RawDomSnapshot};
+use super::manager::{captured_size, log_progress, UrlToViewOptions};
+use super::playwright::DOM_SNAPSHOT_FN;
+use super::scroll::AUTO_SCROLL_FN;
+use super::stabilize::{SETTLE_PAGE_FN, STABILIZE_INIT_FN};
+use super::state::{InteractiveState, SETTLE_STATE_FN};
+use super::steps::{step_error, InteractionStep};
+use crate::types::{NormalizedView, PageDiagnostics, ResourceKind};
+use crate::{DpcError, Result};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use futures::StreamExt;
+use playwright::browser::{Browser, BrowserConfig};
+use playwright::cdp::browser_protocol::emulation::{
+    MediaFeature, SetDeviceMetricsOverrideParams, SetEmulatedMediaParams,
+    SetTouchEmulationEnabledParams, SetUserAgentOverrideParams,
+};
+use playwright::cdp::browser_protocol::input::{
+    DispatchKeyEventParams, DispatchKeyEventType, DispatchMouseEventParams, DispatchMouseEventType,
+    MouseButton,
+};
+use playwright::cdp::browser_protocol::network::{
+    CookieParam, Headers, SetExtraHttpHeadersParams, TimeSinceEpoch,
+};
+use playwright::cdp::browser_protocol::page::{
+    CaptureScreenshotFormat, CaptureScreenshotParams, Viewport,
+};
+use playwright::Page;
+use std::fs;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+use tokio::time::{sleep, timeout};
+
+/// How long the resource count must stay unchanged to count as network idle.
+const IDLE_QUIET_PERIOD: Duration = Duration::from_millis(500);
+
+/// A launched browser plus the task pumping its CDP connection.
+pub(crate) struct CdpBrowser {
+    browser: Browser,
+    handler: JoinHandle<()>,
+}
+
+impl CdpBrowser {
+    /// Launch Chrome/Chromium with the viewport and headless mode from `options`.
+    pub(crate) async fn launch(options: &UrlToViewOptions) -> Result<Self> {
+        if options.auth.storage_state.is_some() {
+            return Err(DpcError::Config(
+                "storage state is only supported by the Node backend".to_string(),
+            ));
+        }
+        if options.har.is_some() {
+            return Err(DpcError::Config(
+                "HAR record/replay is only supported by the Node backend".to_string(),
+            ));
+        }
+        let mut builder =
+            BrowserConfig::builder().window_size(options.viewport.width, options.viewport.height);
+        if !options.headless {
+            builder = builder.with_head();
+        }
+        if let Some(path) = &options.chrome_path {
+            builder = builder.chrome_executable(path);
+        }
+        let config = builder.build().map_err(DpcError::Config)?;
+        let (browser, mut handler) = Browser::launch(config)
+            .await
+            .map_err(|err| DpcError::Config(format!("Failed to launch Chrome: {err}")))?;
+        let handler = tokio::spawn(async move { while handler.next().await.is_some() {} });
+        Ok(Self { browser, handler })
+    }
+
+    /// Render `url`, write the screenshot and return the normalized view.
+    ///
+    /// The browser is closed afterwards, whether or not the capture succeeded.
+    pub(crate) async fn render(
+        self,
+        url: &str,
+        screenshot_path: &Path,
+        options: &UrlToViewOptions,
+    ) -> Result<NormalizedView> {
+        let Self { browser, handler } = self;
+        let start = Instant::now();
+        let result = timeout(
+            options.process_timeout,
+            capture(&browser, url, screenshot_path, options),
+        )
+        .await
+        .unwrap_or_else(|_| {
+            Err(DpcError::Config(format!(
+                "Chrome (CDP) timed out after {:?}",
+                options.process_timeout
+            )))
+        });
+        // Dropping the browser kills the Chrome process it launched.
+        drop(browser);
+        handler.abort();
+
+        let (dom, diagnostics) = result?;
+        let (width, height) = captured_size(screenshot_path, options)?;
+        log_progress(
+            &options.progress,
+            &format!("Capture finished in {:.1}s", start.elapsed().as_secs_f32()),
+        );
+        Ok(NormalizedView {
+            kind: ResourceKind::Url,
+            screenshot_path: screenshot_path.to_path_buf(),
+            width,
+            height,
+            dom: Some(convert_raw_dom(dom)),
+            figma_tree: None,
+            ocr_blocks: None,
+            diagnostics: reported(Some(diagnostics)),
+        })
+    }
+}
+
+async fn capture(
+    browser: &Browser,
+    url: &str,
+    screenshot_path: &Path,
+    options: &UrlToViewOptions,
+) -> Result<(RawDomSnapshot, PageDiagnostics)> {
+    let page = browser.new_page("about:blank").await.map_err(cdp_error)?;
+    apply_device(&page, options).await?;
+    apply_auth(&page, &options.auth).await?;
+    page.evaluate_on_new_document(format!("({})()", DIAGNOSTICS_INIT_FN))
+        .await
+        .map_err(cdp_error)?;
+    if options.deterministic {
+        page.evaluate_on_new_document(format!("({})()", STABILIZE_INIT_FN))
+            .await
+            .map_err(cdp_error)?;
+    }
+
+    log_progress(
+        &options.progress,
+        "Navigating and waiting for network idle (CDP)…",
+    );
+    match timeout(options.navigation_timeout, page.goto(url)).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(err)) => {
+            return Err(DpcError::Config(format!(
+                "Navigation failed: {err}. Check the URL is reachable."
+            )))
+        }
+        Err(_) => {
+            return Err(DpcError::Config(format!(
+                "Navigation timed out after {:?}. Increase --nav-timeout or check the URL is reachable.",
+                options.navigation_timeout
+            )))
+        }
+    }
+    wait_for_network_idle(&page, options.network_idle_timeout).await;
+
+    for (index, step) in options.steps.iter().enumerate() {
+        run_step(&page, step, options.navigation_timeout)
+            .await
+            .map_err(|message| step_error(&options.steps, index, url, message))?;
+    }
+    if let Some(auto_scroll) = options.auto_scroll {
+        page.evaluate(format!(
+            "({})({})",
+            AUTO_SCROLL_FN,
+            auto_scroll.to_script_value()
+        ))
+        .await
+        .map_err(cdp_error)?;
+        wait_for_network_idle(&page, options.network_idle_timeout).await;
+    }
+
+    if let Some(parent) = screenshot_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| DpcError::Config(format!("Failed to create screenshot dir: {}", e)))?;
+    }
+    if options.deterministic {
+        page.evaluate(format!("({})()", SETTLE_PAGE_FN))
+            .await
+            .map_err(cdp_error)?;
+    }
+    if let Some(state) = options.state {
+        apply_state(
+            &page,
+            options.selector.as_deref(),
+            state,
+            options.navigation_timeout,
+        )
+        .await?;
+    }
+    let mut root = String::new();
+    let png = if let Some(selector) = &options.selector {
+        let clip = element_clip(&page, selector, options.navigation_timeout).await?;
+        root = format!("document.querySelector({})", js_string(selector));
+        screenshot_clip(&page, clip, options).await?
+    } else if options.full_page {
+        screenshot_clip(&page, document_clip(&page).await?, options).await?
+    } else {
+        let params = CaptureScreenshotParams::builder()
+            .format(CaptureScreenshotFormat::Png)
+            .build();
+        page.screenshot(params).await.map_err(cdp_error)?
+    };
+    fs::write(screenshot_path, png)?;
+
+    let json: String = page
+        .evaluate(format!("JSON.stringify(({})({root}))", DOM_SNAPSHOT_FN))
+        .await
+        .map_err(cdp_error)?
+        .into_value()
+        .map_err(|err| DpcError::Config(format!("DOM snapshot was not a string: {err}")))?;
+    let dom = serde_json::from_str(&json)
+        .map_err(|err| DpcError::Config(format!("Failed to parse CDP DOM snapshot: {err}")))?;
+
+    let json: String = page
+        .evaluate(format!("JSON.stringify(({})())", DIAGNOSTICS_COLLECT_FN))
+        .await
+        .map_err(cdp_error)?
+        .into_value()
+        .map_err(|err| DpcError::Config(format!("Page diagnostics were not a string: {err}")))?;
+    let diagnostics = serde_json::from_str(&json)
+        .map_err(|err| DpcError::Config(format!("Failed to parse page diagnostics: {err}")))?;
+    Ok((dom, diagnostics))
+}
+
+/// Viewport and scale factor, then touch, user agent and media-feature overrides.
+async fn apply_device(page: &Page, options: &UrlToViewOptions) -> Result<()> {
+    let device = &options.device;
+    set_viewport_height(page, options.viewport.height, options).await?;
+    if device.has_touch {
+        page.execute(SetTouchEmulationEnabledParams::new(true))
+            .await
+            .map_err(cdp_error)?;
+    }
+    if let Some(agent) = &device.user_agent {
+        page.execute(SetUserAgentOverrideParams::new(agent.clone()))
+            .await
+            .map_err(cdp_error)?;
+    }
+    let mut features = Vec::new();
+    if let Some(scheme) = device.color_scheme {
+        features.push(MediaFeature::new("prefers-color-scheme", scheme.as_str()));
+    }
+    if let Some(motion) = device.reduced_motion {
+        features.push(MediaFeature::new("prefers-reduced-motion", motion.as_str()));
+    }
+    if !features.is_empty() {
+        page.execute(SetEmulatedMediaParams::builder().features(features).build())
+            .await
+            .map_err(cdp_error)?;
+    }
+    Ok(())
+}
+
+/// Emulate the configured viewport width and device at `height` CSS pixels.
+async fn set_viewport_height(page: &Page, height: u32, options: &UrlToViewOptions) -> Result<()> {
+    page.execute(SetDeviceMetricsOverrideParams::new(
+        options.viewport.width as i64,
+        height as i64,
+        options.device.device_scale_factor,
+        options.device.is_mobile,
+    ))
+    .await
+    .map_err(cdp_error)?;
+    Ok(())
+}
+
+/// Screenshot `clip` (document coordinates). Chrome only paints inside the viewport, so
+/// when the clip reaches below it the page is scrolled to the top and the viewport made
+/// tall enough for the capture, then set back. The width stays put so the layout does not reflow sideways.
+async fn screenshot_clip(
+    page: &Page,
+    clip: Viewport,
+    options: &UrlToViewOptions,
+) -> Result<Vec<u8>> {
+    let bottom = (clip.y + clip.height).ceil() as u32;
+    let grow = bottom > options.viewport.height;
+    if grow {
+        set_viewport_height(page, bottom, options).await?;
+        page.evaluate("window.scrollTo(0, 0)")
+            .await
+            .map_err(cdp_error)?;
+    }
+    let params = CaptureScreenshotParams::builder()
+        .format(CaptureScreenshotFormat::Png)
+        .clip(clip)
+        .build();
+    let png = page.screenshot(params).await.map_err(cdp_error);
+    if grow {
+        set_viewport_height(page, options.viewport.height, options).await?;
+    }
+    png
+}
+
+/// Send extra headers (basic auth becomes an `Authorization` header) and set cookies.
+async fn apply_auth(page: &Page, auth: &AuthOptions) -> Result<()> {
+    let mut headers = serde_json::Map::new();
+    for (name, value) in &auth.headers {
+        headers.insert(name.clone(), value.clone().into());
+    }
+    if let Some(basic) = &auth.basic_auth {
+        let credentials = STANDARD.encode(format!("{}:{}", basic.username, basic.password));
+        headers.insert(
+            "Authorization".to_string(),
+            format!("Basic {credentials}").into(),
+        );
+    }
+    if !headers.is_empty() {
+        page.execute(SetExtraHttpHeadersParams::new(Headers::new(
+            serde_json::Value::Object(headers),
+        )))
+        .await
+        .map_err(cdp_error)?;
+    }
+
+    if !auth.cookies.is_empty() {
+        let mut params = Vec::with_capacity(auth.cookies.len());
+        for cookie in &auth.cookies {
+            let mut builder = CookieParam::builder()
+                .name(cookie.name.clone())
+                .value(cookie.value.clone())
+                .domain(cookie.domain.clone())
+                .path(cookie.path.clone())
+                .secure(cookie.secure)
+                .http_only(cookie.http_only);
+            if cookie.expires > 0.0 {
+                builder = builder.expires(TimeSinceEpoch::new(cookie.expires));
+            }
+            params.push(builder.build().map_err(DpcError::Config)?);
+        }
+        page.set_cookies(params).await.map_err(cdp_error)?;
+    }
+    Ok(())
+}
+
+/// Run one interaction step, mirroring the Playwright script's `runSteps`. Pointer and
+/// key steps go through CDP input events so `:hover` and key handlers behave as with
+/// Playwright; errors come back as plain messages for [`step_error`].
+async fn run_step(
+    page: &Page,
+    step: &InteractionStep,
+    limit: Duration,
+) -> std::result::Result<(), String> {
+    match step {
+        InteractionStep::Click(selector) => {
+            let (x, y) = wait_for_element(page, selector, limit).await?;
+            for kind in [
+                DispatchMouseEventType::MouseMoved,
+                DispatchMouseEventType::MousePressed,
+                DispatchMouseEventType::MouseReleased,
+            ] {
+                mouse_event(page, kind, x, y).await?;
+            }
+        }
+        InteractionStep::Hover(selector) => {
+            let (x, y) = wait_for_element(page, selector, limit).await?;
+            mouse_event(page, DispatchMouseEventType::MouseMoved, x, y).await?;
+        }
+        InteractionStep::Fill { selector, value } => {
+            wait_for_element(page, selector, limit).await?;
+            let script = format!(
+                "(() => {{ const el = document.querySelector({}); el.focus(); el.value = {}; \
+                 el.dispatchEvent(new Event('input', {{ bubbles: true }})); \
+                 el.dispatchEvent(new Event('change', {{ bubbles: true }})); }})()",
+                js_string(selector),
+                js_string(value)
+            );
+            page.evaluate(script).await.map_err(|err| err.to_string())?;
+        }
+        InteractionStep::Press(key) => {
+            for kind in [DispatchKeyEventType::KeyDown, DispatchKeyEventType::KeyUp] {
+                let params = DispatchKeyEventParams::builder()
+                    .r#type(kind)
+                    .key(key.clone())
+                    .build()?;
+                page.execute(params).await.map_err(|err| err.to_string())?;
+            }
+        }
+        InteractionStep::WaitForSelector(selector) => {
+            wait_for_element(page, selector, limit).await?;
+        }
+        InteractionStep::WaitMs(ms) => sleep(Duration::from_millis(*ms)).await,
+        InteractionStep::ScrollTo(selector) => {
+            wait_for_element(page, selector, limit).await?;
+        }
+    }
+    Ok(())
+}
+
+/// Put the capture element into `state`, mirroring the Playwright scripts' `applyState`:
+/// pointer moves and presses and a Shift key press go through CDP input events, then the
+/// element's transitions get up to 1s to finish.
+async fn apply_state(
+    page: &Page,
+    selector: Option<&str>,
+    state: InteractiveState,
+    limit: Duration,
+) -> Result<()> {
+    if state == InteractiveState::Rest {
+        return Ok(());
+    }
+    let Some(selector) = selector else {
+        return Err(DpcError::Config(format!(
+            "The {state} state needs a capture selector"
+        )));
+    };
+    let (x, y) = wait_for_element(page, selector, limit)
+        .await
+        .map_err(|err| {
+            DpcError::Config(format!(
+                "No visible element matches capture selector {selector} ({err})"
+            ))
+        })?;
+    let failed =
+        |err: String| DpcError::Config(format!("Failed to apply the {state} state: {err}"));
+    match state {
+        InteractiveState::Rest => {}
+        InteractiveState::Hover => {
+            mouse_event(page, DispatchMouseEventType::MouseMoved, x, y)
+                .await
+                .map_err(failed)?;
+        }
+        InteractiveState::Focus => {
+            for kind in [DispatchKeyEventType::KeyDown, DispatchKeyEventType::KeyUp] {
+                let params = DispatchKeyEventParams::builder()
+                    .r#type(kind)
+                    .key("Shift")
+                    .build()
+                    .map_err(failed)?;
+                page.execute(params).await.map_err(cdp_error)?;
+            }
+            page.evaluate(format!(
+                "document.querySelector({}).focus()",
+                js_string(selector)
+            ))
+            .await
+            .map_err(cdp_error)?;
+        }
+        InteractiveState::Active => {
+            for kind in [
+                DispatchMouseEventType::MouseMoved,
+                DispatchMouseEventType::MousePressed,
+            ] {
+                mouse_event(page, kind, x, y).await.map_err(failed)?;
+            }
+        }
+    }
+    page.evaluate(format!(
+        "({})(document.querySelector({}))",
+        SETTLE_STATE_FN,
+        js_string(selector)
+    ))
+    .await
+    .map_err(cdp_error)?;
+    Ok(())
+}
+
+/// Poll until `selector` matches a visible element, scroll it into view and return the
+/// viewport coordinates of its centre.
+async fn wait_for_element(
+    page: &Page,
+    selector: &str,
+    limit: Duration,
+) -> std::result::Result<(f64, f64), String> {
+    let script = format!(
+        "(() => {{ const el = document.querySelector({}); if (!el) return null; \
+         el.scrollIntoView({{ block: 'center', inline: 'center' }}); \
+         const r = el.getBoundingClientRect(); \
+         if (r.width === 0 && r.height === 0) return null; \
+         return [r.x + r.width / 2, r.y + r.height / 2]; }})()",
+        js_string(selector)
+    );
+    let deadline = Instant::now() + limit;
+    loop {
+        let point = page
+            .evaluate(script.as_str())
+            .await
+            .map_err(|err| err.to_string())?
+            .into_value::<Option<(f64, f64)>>()
+            .map_err(|err| err.to_string())?;
+        if let Some(point) = point {
+            return Ok(point);
+        }
+        if Instant::now() >= deadline {
+            return Err(format!(
+                "Timeout {}ms exceeded waiting for {selector} to be visible",
+                limit.as_millis()
+            ));
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+}
+
+/// Page-coordinate clip rectangle of the element a capture is scoped to.
+async fn element_clip(page: &Page, selector: &str, limit: Duration) -> Result<Viewport> {
+    wait_for_element(page, selector, limit)
+        .await
+        .map_err(|err| {
+            DpcError::Config(format!(
+                "No visible element matches capture selector {selector} ({err})"
+            ))
+        })?;
+    let script = format!(
+        "(() => {{ const r = document.querySelector({}).getBoundingClientRect(); \
+         return [r.x + window.scrollX, r.y + window.scrollY, r.width, r.height]; }})()",
+        js_string(selector)
+    );
+    let (x, y, width, height): (f64, f64, f64, f64) = page
+        .evaluate(script)
+        .await
+        .map_err(cdp_error)?
+        .into_value()
+        .map_err(|err| DpcError::Config(format!("Element bounds were not numbers: {err}")))?;
+    Viewport::builder()
+        .x(x)
+        .y(y)
+        .width(width)
+        .height(height)
+        .scale(1.0)
+        .build()
+        .map_err(DpcError::Config)
+}
+
+/// Clip covering the whole document, scrolled to the top so the DOM snapshot's viewport
+/// boxes line up with it.
+async fn document_clip(page: &Page) -> Result<Viewport> {
+    let (width, height): (f64, f64) = page
+        .evaluate(
+            "(() => { window.scrollTo(0, 0); const e = document.scrollingElement || document.documentElement; \
+             return [e.scrollWidth, e.scrollHeight]; })()",
+        )
+        .await
+        .map_err(cdp_error)?
+        .into_value()
+        .map_err(|err| DpcError::Config(format!("Document size was not numbers: {err}")))?;
+    Viewport::builder()
+        .x(0.0)
+        .y(0.0)
+        .width(width)
+        .height(height)
+        .scale(1.0)
+        .build()
+        .map_err(DpcError::Config)
+}
+
+async fn mouse_event(
+    page: &Page,
+    kind: DispatchMouseEventType,
+    x: f64,
+    y: f64,
+) -> std::result::Result<(), String> {
+    let params = DispatchMouseEventParams::builder()
+        .r#type(kind)
+        .x(x)
+        .y(y)
+        .button(MouseButton::Left)
+        .click_count(1)
+        .build()?;
+    page.execute(params).await.map_err(|err| err.to_string())?;
+    Ok(())
+}
+
+/// Quote `value` as a JS string literal.
+fn js_string(value: &str) -> String {
+    serde_json::Value::from(value).to_string()
+}
+
+/// Approximates Playwright's `networkidle`: wait until the number of loaded resources
+/// stops changing for [`IDLE_QUIET_PERIOD`], giving up silently after `limit`.
+async fn wait_for_network_idle(page: &Page, limit: Duration) {
+    let deadline = Instant::now() + limit;
+    let mut last = None;
+    let mut stable_since = Instant::now();
+    while Instant::now() < deadline {
+        let count = page
+            .evaluate("performance.getEntriesByType('resource').length")
+            .await
+            .ok()
+            .and_then(|value| value.into_value::<u64>().ok());
+        if count != last {
+            last = count;
+            stable_since = Instant::now();
+        } else if stable_since.elapsed() >= IDLE_QUIET_PERIOD {
+            return;
+        }
+        sleep(Duration::from_millis(100)).await;
+    }
+}
+
+fn cdp_error(err: playwright::error::CdpError) -> DpcError {
+    DpcError::Config(format!("Chrome DevTools Protocol error: {err}"))
+}
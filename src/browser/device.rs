@@ -0,0 +1,302 @@
+//! Device emulation for URL renders: named presets and the browser-context settings
+//! (scale factor, mobile mode, touch, user agent, media features) they stand for.
+//!
+//! Viewports stay in CSS pixels; with a scale factor above 1 the screenshot is taken at
+//! device pixels while DOM bounding boxes keep CSS pixel coordinates.
+
+use super::manager::BrowserEngine;
+use crate::Viewport;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// `prefers-color-scheme` to emulate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ColorScheme {
+    Light,
+    Dark,
+    NoPreference,
+}
+
+impl ColorScheme {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ColorScheme::Light => "light",
+            ColorScheme::Dark => "dark",
+            ColorScheme::NoPreference => "no-preference",
+        }
+    }
+}
+
+/// `prefers-reduced-motion` to emulate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ReducedMotion {
+    Reduce,
+    NoPreference,
+}
+
+impl ReducedMotion {
+    pub(crate) fn as_str(self) -> &'static str {
+        match self {
+            ReducedMotion::Reduce => "reduce",
+            ReducedMotion::NoPreference => "no-preference",
+        }
+    }
+}
+
+/// Browser-context emulation beyond the viewport size.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DeviceEmulation {
+    /// Device pixels per CSS pixel; screenshots are this many times the viewport size.
+    pub device_scale_factor: f64,
+    /// Honor `<meta name="viewport">` and mobile layout rules.
+    pub is_mobile: bool,
+    /// Report touch support (`ontouchstart`, `pointer: coarse`).
+    pub has_touch: bool,
+    /// User agent override; the browser's own when unset.
+    pub user_agent: Option<String>,
+    pub color_scheme: Option<ColorScheme>,
+    pub reduced_motion: Option<ReducedMotion>,
+}
+
+impl Default for DeviceEmulation {
+    fn default() -> Self {
+        Self {
+            device_scale_factor: 1.0,
+            is_mobile: false,
+            has_touch: false,
+            user_agent: None,
+            color_scheme: None,
+            reduced_motion: None,
+        }
+    }
+}
+
+impl DeviceEmulation {
+    /// True for a plain 1x desktop context.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// One-line description for verbose logs.
+    pub fn describe(&self) -> String {
+        let mut parts = vec![format!("{}x scale", self.device_scale_factor)];
+        if self.is_mobile {
+            parts.push("mobile".to_string());
+        }
+        if self.has_touch {
+            parts.push("touch".to_string());
+        }
+        if let Some(scheme) = self.color_scheme {
+            parts.push(format!("color scheme {}", scheme.as_str()));
+        }
+        if let Some(motion) = self.reduced_motion {
+            parts.push(format!("reduced motion {}", motion.as_str()));
+        }
+        if self.user_agent.is_some() {
+            parts.push("custom user agent".to_string());
+        }
+        parts.join(", ")
+    }
+
+    /// `newContext` options for the Playwright scripts; unset overrides are left out.
+    /// Firefox rejects `isMobile`, so it is never sent there.
+    pub(crate) fn to_script_value(&self, engine: BrowserEngine) -> Value {
+        let mut context = serde_json::Map::new();
+        context.insert(
+            "deviceScaleFactor".to_string(),
+            self.device_scale_factor.into(),
+        );
+        if engine != BrowserEngine::Firefox {
+            context.insert("isMobile".to_string(), self.is_mobile.into());
+        }
+        context.insert("hasTouch".to_string(), self.has_touch.into());
+        if let Some(agent) = &self.user_agent {
+            context.insert("userAgent".to_string(), agent.clone().into());
+        }
+        if let Some(scheme) = self.color_scheme {
+            context.insert("colorScheme".to_string(), scheme.as_str().into());
+        }
+        if let Some(motion) = self.reduced_motion {
+            context.insert("reducedMotion".to_string(), motion.as_str().into());
+        }
+        Value::Object(context)
+    }
+}
+
+/// A named device: its viewport (CSS pixels) plus emulation settings.
+#[derive(Debug, Clone, Copy)]
+pub struct DevicePreset {
+    pub name: &'static str,
+    pub viewport: Viewport,
+    pub device_scale_factor: f64,
+    pub is_mobile: bool,
+    pub has_touch: bool,
+    pub user_agent: Option<&'static str>,
+}
+
+impl DevicePreset {
+    /// The preset's emulation settings, with no media-feature overrides.
+    pub fn emulation(&self) -> DeviceEmulation {
+        DeviceEmulation {
+            device_scale_factor: self.device_scale_factor,
+            is_mobile: self.is_mobile,
+            has_touch: self.has_touch,
+            user_agent: self.user_agent.map(str::to_string),
+            color_scheme: None,
+            reduced_motion: None,
+        }
+    }
+}
+
+const IOS_SAFARI_UA: &str = "Mozilla/5.0 (iPhone; CPU iPhone OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+const IPADOS_SAFARI_UA: &str = "Mozilla/5.0 (iPad; CPU OS 17_0 like Mac OS X) AppleWebKit/605.1.15 (KHTML, like Gecko) Version/17.0 Mobile/15E148 Safari/604.1";
+const ANDROID_CHROME_UA: &str = "Mozilla/5.0 (Linux; Android 14; Pixel 7) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Mobile Safari/537.36";
+
+/// Built-in presets, selectable with `--device` or `[device] preset`.
+pub const DEVICE_PRESETS: &[DevicePreset] = &[
+    DevicePreset {
+        name: "iphone-se",
+        viewport: Viewport {
+            width: 375,
+            height: 667,
+        },
+        device_scale_factor: 2.0,
+        is_mobile: true,
+        has_touch: true,
+        user_agent: Some(IOS_SAFARI_UA),
+    },
+    DevicePreset {
+        name: "iphone-14",
+        viewport: Viewport {
+            width: 390,
+            height: 844,
+        },
+        device_scale_factor: 3.0,
+        is_mobile: true,
+        has_touch: true,
+        user_agent: Some(IOS_SAFARI_UA),
+    },
+    DevicePreset {
+        name: "iphone-14-pro-max",
+        viewport: Viewport {
+            width: 430,
+            height: 932,
+        },
+        device_scale_factor: 3.0,
+        is_mobile: true,
+        has_touch: true,
+        user_agent: Some(IOS_SAFARI_UA),
+    },
+    DevicePreset {
+        name: "pixel-7",
+        viewport: Viewport {
+            width: 412,
+            height: 915,
+        },
+        device_scale_factor: 2.625,
+        is_mobile: true,
+        has_touch: true,
+        user_agent: Some(ANDROID_CHROME_UA),
+    },
+    DevicePreset {
+        name: "ipad",
+        viewport: Viewport {
+            width: 820,
+            height: 1180,
+        },
+        device_scale_factor: 2.0,
+        is_mobile: true,
+        has_touch: true,
+        user_agent: Some(IPADOS_SAFARI_UA),
+    },
+    DevicePreset {
+        name: "desktop",
+        viewport: Viewport {
+            width: 1440,
+            height: 900,
+        },
+        device_scale_factor: 1.0,
+        is_mobile: false,
+        has_touch: false,
+        user_agent: None,
+    },
+    DevicePreset {
+        name: "desktop-hd",
+        viewport: Viewport {
+            width: 1920,
+            height: 1080,
+        },
+        device_scale_factor: 1.0,
+        is_mobile: false,
+        has_touch: false,
+        user_agent: None,
+    },
+    DevicePreset {
+        name: "desktop-retina",
+        viewport: Viewport {
+            width: 1440,
+            height: 900,
+        },
+        device_scale_factor: 2.0,
+        is_mobile: false,
+        has_touch: false,
+        user_agent: None,
+    },
+];
+
+/// Look up a preset by name (case-insensitive).
+pub fn device_preset(name: &str) -> Option<&'static DevicePreset> {
+    DEVICE_PRESETS
+        .iter()
+        .find(|preset| preset.name.eq_ignore_ascii_case(name.trim()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_resolve_by_name() {
+        let phone = device_preset("iPhone-14").expect("preset");
+        assert_eq!(
+            phone.viewport,
+            Viewport {
+                width: 390,
+                height: 844
+            }
+        );
+        let emulation = phone.emulation();
+        assert_eq!(emulation.device_scale_factor, 3.0);
+        assert!(emulation.is_mobile && emulation.has_touch);
+        assert!(device_preset("nokia-3310").is_none());
+        assert!(device_preset("desktop").unwrap().emulation().is_default());
+    }
+
+    #[test]
+    fn script_value_leaves_out_unset_overrides() {
+        let value = DeviceEmulation {
+            color_scheme: Some(ColorScheme::Dark),
+            ..DeviceEmulation::default()
+        }
+        .to_script_value(BrowserEngine::Chromium);
+        assert_eq!(value["deviceScaleFactor"], 1.0);
+        assert_eq!(value["colorScheme"], "dark");
+        assert!(value.get("userAgent").is_none());
+        assert!(value.get("reducedMotion").is_none());
+    }
+
+    #[test]
+    fn firefox_script_value_has_no_mobile_flag() {
+        let phone = device_preset("pixel-7").unwrap().emulation();
+        assert_eq!(
+            phone.to_script_value(BrowserEngine::Webkit)["isMobile"],
+            true
+        );
+        let value = phone.to_script_value(BrowserEngine::Firefox);
+        assert!(value.get("isMobile").is_none());
+        assert_eq!(value["hasTouch"], true);
+    }
+}
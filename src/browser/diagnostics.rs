@@ -0,0 +1,136 @@
+//! Page diagnostics: console errors, uncaught exceptions, failed requests and broken
+//! images collected while a URL renders (see [`PageDiagnostics`]).
+//!
+//! The Node backend listens to Playwright's page events; the CDP backend hooks
+//! `console.error` and the error events from an init script and reads HTTP statuses from
+//! the resource timing entries. Each list keeps at most 50 entries.
+
+use crate::types::PageDiagnostics;
+
+/// JS `function watchPage(page)`: subscribes to the page's console, error and network
+/// events and returns the object they fill, `{ consoleErrors, pageErrors, failedRequests }`.
+///
+/// Chromium and WebKit log every failed load as a console error too; those duplicates of
+/// `failedRequests` are dropped.
+macro_rules! watch_page_fn {
+    () => {
+        r#"
+function watchPage(page) {
+  const found = { consoleErrors: [], pageErrors: [], failedRequests: [] };
+  const push = (list, item) => {
+    if (list.length < 50) list.push(item);
+  };
+  page.on('console', (msg) => {
+    if (msg.type() === 'error' && !msg.text().startsWith('Failed to load resource')) {
+      push(found.consoleErrors, msg.text());
+    }
+  });
+  page.on('pageerror', (err) => push(found.pageErrors, err && err.message ? err.message : String(err)));
+  page.on('requestfailed', (req) => {
+    const failure = req.failure();
+    push(found.failedRequests, {
+      url: req.url(),
+      resourceType: req.resourceType(),
+      error: failure ? failure.errorText : null
+    });
+  });
+  page.on('response', (res) => {
+    if (res.status() >= 400) {
+      push(found.failedRequests, {
+        url: res.url(),
+        resourceType: res.request().resourceType(),
+        status: res.status()
+      });
+    }
+  });
+  return found;
+}
+"#
+    };
+}
+pub(crate) use watch_page_fn;
+
+/// JS arrow function returning `[{ src, alt }]` for every `<img>` that finished loading
+/// with no image data. Images still pending (e.g. lazy ones never scrolled to) are skipped.
+macro_rules! broken_images_fn {
+    () => {
+        r#"() => Array.from(document.images)
+  .filter((img) => img.complete && img.naturalWidth === 0 && (img.currentSrc || img.src))
+  .slice(0, 50)
+  .map((img) => ({ src: img.currentSrc || img.src, alt: img.alt || null }))"#
+    };
+}
+pub(crate) use broken_images_fn;
+
+/// Init script for the CDP backend: records `console.error` calls, uncaught errors and
+/// unhandled rejections on `window.__dpcDiagnostics`.
+#[cfg_attr(not(feature = "playwright"), allow(dead_code))]
+pub(crate) const DIAGNOSTICS_INIT_FN: &str = r#"() => {
+  const found = { consoleErrors: [], pageErrors: [] };
+  Object.defineProperty(window, '__dpcDiagnostics', { value: found });
+  const push = (list, item) => {
+    if (list.length < 50) list.push(item);
+  };
+  const describe = (value) => (value && value.message ? value.message : String(value));
+  const consoleError = console.error;
+  console.error = function (...args) {
+    push(found.consoleErrors, args.map(describe).join(' '));
+    return consoleError.apply(this, args);
+  };
+  window.addEventListener('error', (event) => push(found.pageErrors, event.message || describe(event.error)));
+  window.addEventListener('unhandledrejection', (event) => push(found.pageErrors, describe(event.reason)));
+}"#;
+
+/// Collects the CDP backend's diagnostics: what [`DIAGNOSTICS_INIT_FN`] recorded, resource
+/// timing entries with a 4xx/5xx `responseStatus`, and broken images.
+#[cfg_attr(not(feature = "playwright"), allow(dead_code))]
+pub(crate) const DIAGNOSTICS_COLLECT_FN: &str = concat!(
+    r#"() => {
+  const found = window.__dpcDiagnostics || { consoleErrors: [], pageErrors: [] };
+  const failedRequests = performance
+    .getEntriesByType('navigation')
+    .concat(performance.getEntriesByType('resource'))
+    .filter((entry) => entry.responseStatus >= 400)
+    .slice(0, 50)
+    .map((entry) => ({ url: entry.name, resourceType: entry.initiatorType, status: entry.responseStatus }));
+  return { ...found, failedRequests, brokenImages: ("#,
+    broken_images_fn!(),
+    r#")() };
+}"#
+);
+
+/// Diagnostics worth attaching to a view: `None` when the page reported nothing.
+pub(crate) fn reported(diagnostics: Option<PageDiagnostics>) -> Option<PageDiagnostics> {
+    diagnostics.filter(|diagnostics| !diagnostics.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::FailedRequest;
+
+    #[test]
+    fn script_output_parses_and_empty_reports_are_dropped() {
+        let json = r#"{
+            "consoleErrors": ["boom"],
+            "pageErrors": [],
+            "failedRequests": [
+                { "url": "https://example.com/site.css", "resourceType": "stylesheet", "status": 404 },
+                { "url": "https://fonts.example/a.woff2", "resourceType": "font", "error": "net::ERR_NAME_NOT_RESOLVED" }
+            ],
+            "brokenImages": [{ "src": "https://example.com/hero.png", "alt": null }]
+        }"#;
+        let diagnostics: PageDiagnostics = serde_json::from_str(json).unwrap();
+        let reasons: Vec<String> = diagnostics
+            .failed_requests
+            .iter()
+            .map(FailedRequest::reason)
+            .collect();
+        assert_eq!(reasons, vec!["404", "net::ERR_NAME_NOT_RESOLVED"]);
+        assert_eq!(diagnostics.broken_images[0].alt, None);
+        assert!(reported(Some(diagnostics)).is_some());
+
+        assert!(reported(Some(PageDiagnostics::default())).is_none());
+        assert!(DIAGNOSTICS_COLLECT_FN.contains("img.naturalWidth === 0"));
+    }
+}
@@ -1,6 +1,9 @@
 //! DOM snapshot types and conversion from raw Playwright output.
 
-use crate::types::{BoundingBox, ComputedStyle, DomNode, DomSnapshot};
+use crate::types::{
+    BoundingBox, BoxCorners, BoxSides, ComputedStyle, DomNode, DomSnapshot, PageDiagnostics,
+    PseudoElement,
+};
 use std::collections::HashMap;
 
 /// Raw script result with DOM snapshot from Playwright.
@@ -8,6 +11,8 @@ use std::collections::HashMap;
 pub(crate) struct ScriptResultWithDom {
     pub status: String,
     pub dom: Option<RawDomSnapshot>,
+    #[serde(default)]
+    pub diagnostics: Option<PageDiagnostics>,
 }
 
 /// Raw DOM snapshot as returned by the Playwright script.
@@ -34,6 +39,8 @@ pub(crate) struct RawDomNode {
     pub text: Option<String>,
     pub bounding_box: RawBoundingBox,
     pub computed_style: Option<RawComputedStyle>,
+    pub shadow_host: Option<String>,
+    pub frame: Option<String>,
 }
 
 /// Raw bounding box from Playwright output.
@@ -58,6 +65,18 @@ pub(crate) struct RawComputedStyle {
     pub display: Option<String>,
     pub visibility: Option<String>,
     pub opacity: Option<f32>,
+    pub padding: Option<BoxSides>,
+    pub margin: Option<BoxSides>,
+    pub border_width: Option<BoxSides>,
+    pub border_style: Option<String>,
+    pub border_color: Option<String>,
+    pub border_radius: Option<BoxCorners>,
+    pub box_shadow: Option<String>,
+    pub letter_spacing: Option<f32>,
+    pub text_align: Option<String>,
+    pub text_transform: Option<String>,
+    pub before: Option<PseudoElement>,
+    pub after: Option<PseudoElement>,
 }
 
 /// Converts raw DOM data from Playwright into the application's DomSnapshot type.
@@ -88,7 +107,21 @@ pub(crate) fn convert_raw_dom(dom_data: RawDomSnapshot) -> DomSnapshot {
                 display: s.display,
                 visibility: s.visibility,
                 opacity: s.opacity,
+                padding: s.padding,
+                margin: s.margin,
+                border_width: s.border_width,
+                border_style: s.border_style,
+                border_color: s.border_color,
+                border_radius: s.border_radius,
+                box_shadow: s.box_shadow,
+                letter_spacing: s.letter_spacing,
+                text_align: s.text_align,
+                text_transform: s.text_transform,
+                before: s.before,
+                after: s.after,
             }),
+            shadow_host: raw.shadow_host,
+            frame: raw.frame,
         })
         .collect();
 
@@ -115,6 +148,7 @@ mod tests {
                 "parent": null,
                 "attributes": {"class": "container"},
                 "text": "Hello",
+                "shadowHost": "node-7",
                 "boundingBox": {"x": 0, "y": 0, "width": 100, "height": 50},
                 "computedStyle": {
                     "fontFamily": "Arial",
@@ -125,7 +159,13 @@ mod tests {
                     "backgroundColor": "rgb(255, 255, 255)",
                     "display": "block",
                     "visibility": "visible",
-                    "opacity": 0.5
+                    "opacity": 0.5,
+                    "padding": {"top": 8, "right": 16, "bottom": 8, "left": 16},
+                    "borderRadius": {"topLeft": 4, "topRight": 4, "bottomRight": 0, "bottomLeft": 0},
+                    "boxShadow": null,
+                    "letterSpacing": 0.5,
+                    "textTransform": "uppercase",
+                    "before": {"content": "★", "width": null, "height": null}
                 }
             }]
         }"#;
@@ -143,6 +183,8 @@ mod tests {
         assert_eq!(node.attributes.get("class"), Some(&"container".to_string()));
         assert_eq!(node.text, Some("Hello".to_string()));
         assert_eq!(node.bounding_box.width, 100.0);
+        assert_eq!(node.shadow_host.as_deref(), Some("node-7"));
+        assert!(node.frame.is_none());
 
         let style = node.computed_style.as_ref().unwrap();
         assert_eq!(style.font_family, Some("Arial".to_string()));
@@ -150,6 +192,13 @@ mod tests {
         assert_eq!(style.display.as_deref(), Some("block"));
         assert_eq!(style.visibility.as_deref(), Some("visible"));
         assert_eq!(style.opacity, Some(0.5));
+        assert_eq!(style.padding.unwrap().values(), [8.0, 16.0, 8.0, 16.0]);
+        assert_eq!(style.border_radius.unwrap().top_right, 4.0);
+        assert!(style.margin.is_none() && style.box_shadow.is_none());
+        assert_eq!(style.letter_spacing, Some(0.5));
+        assert_eq!(style.text_transform.as_deref(), Some("uppercase"));
+        assert_eq!(style.before.as_ref().unwrap().content, "★");
+        assert!(style.after.is_none());
     }
 
     #[test]
@@ -199,7 +248,30 @@ mod tests {
                     display: Some("block".into()),
                     visibility: Some("visible".into()),
                     opacity: Some(0.8),
+                    padding: Some(BoxSides {
+                        top: 4.0,
+                        right: 8.0,
+                        bottom: 4.0,
+                        left: 8.0,
+                    }),
+                    margin: None,
+                    border_width: None,
+                    border_style: Some("solid".into()),
+                    border_color: Some("rgb(0, 0, 0)".into()),
+                    border_radius: None,
+                    box_shadow: Some("rgba(0, 0, 0, 0.2) 0px 1px 2px 0px".into()),
+                    letter_spacing: Some(0.0),
+                    text_align: Some("center".into()),
+                    text_transform: None,
+                    before: None,
+                    after: Some(PseudoElement {
+                        content: "→".into(),
+                        width: Some(12.0),
+                        height: Some(12.0),
+                    }),
                 }),
+                shadow_host: None,
+                frame: Some("n0".into()),
             }],
         };
 
@@ -207,10 +279,17 @@ mod tests {
         let node = snapshot.nodes.first().unwrap();
 
         assert_eq!(node.text.as_deref(), Some("hello"));
+        assert_eq!(node.frame.as_deref(), Some("n0"));
+        assert!(node.shadow_host.is_none());
         let style = node.computed_style.as_ref().unwrap();
         assert_eq!(style.font_family.as_deref(), Some("Arial"));
         assert_eq!(style.display.as_deref(), Some("block"));
         assert_eq!(style.visibility.as_deref(), Some("visible"));
         assert_eq!(style.opacity, Some(0.8));
+        assert_eq!(style.padding.unwrap().left, 8.0);
+        assert_eq!(style.border_style.as_deref(), Some("solid"));
+        assert!(style.box_shadow.is_some());
+        assert_eq!(style.text_align.as_deref(), Some("center"));
+        assert_eq!(style.after.as_ref().unwrap().width, Some(12.0));
     }
 }
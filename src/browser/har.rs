@@ -0,0 +1,271 @@
+//! Network recording (`--har-record`) and offline replay (`--har-replay`) for URL renders.
+//!
+//! Recording writes each render's traffic to its own part file next to the target HAR;
+//! [`HarRecorder`] merges the parts, so one run with several URL renders (ref and impl,
+//! breakpoints, batch entries) leaves a single HAR that replays all of them.
+
+use crate::{DpcError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+/// What a replay does with requests the HAR has no entry for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HarUnmatched {
+    /// Abort them and fail the render, listing the missing requests.
+    #[default]
+    Fail,
+    /// Answer them with an empty 404 and carry on.
+    Stub,
+}
+
+impl HarUnmatched {
+    fn as_str(self) -> &'static str {
+        match self {
+            HarUnmatched::Fail => "fail",
+            HarUnmatched::Stub => "stub",
+        }
+    }
+}
+
+/// Record or replay the network traffic of URL renders.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HarMode {
+    /// Save all traffic (response bodies embedded) to this HAR file, replacing it.
+    Record(PathBuf),
+    /// Serve requests from this HAR instead of the network.
+    Replay {
+        path: PathBuf,
+        unmatched: HarUnmatched,
+    },
+}
+
+impl HarMode {
+    /// One-line description for verbose logs.
+    pub fn describe(&self) -> String {
+        match self {
+            HarMode::Record(path) => format!("recording to {}", path.display()),
+            HarMode::Replay { path, unmatched } => format!(
+                "replaying {} (unmatched requests: {})",
+                path.display(),
+                unmatched.as_str()
+            ),
+        }
+    }
+
+    /// Fail early on a replay HAR that is missing or not a HAR, or a record target whose
+    /// directory cannot be created.
+    pub fn check(&self) -> Result<()> {
+        match self {
+            HarMode::Record(path) => match path.parent() {
+                Some(dir) if !dir.as_os_str().is_empty() => {
+                    fs::create_dir_all(dir).map_err(|err| {
+                        DpcError::Config(format!(
+                            "Cannot create directory for HAR {}: {err}",
+                            path.display()
+                        ))
+                    })
+                }
+                _ => Ok(()),
+            },
+            HarMode::Replay { path, .. } => read_har(path).map(|_| ()),
+        }
+    }
+
+    /// The `har` object the Playwright scripts read: `{ mode, path, unmatched }`.
+    pub(crate) fn to_script_value(&self) -> Value {
+        match self {
+            HarMode::Record(path) => json!({
+                "mode": "record",
+                "path": path.to_string_lossy(),
+            }),
+            HarMode::Replay { path, unmatched } => json!({
+                "mode": "replay",
+                "path": path.to_string_lossy(),
+                "unmatched": unmatched.as_str(),
+            }),
+        }
+    }
+}
+
+/// `har` for the scripts, or `null` when neither recording nor replaying.
+pub(crate) fn har_script_value(har: Option<&HarMode>) -> Value {
+    har.map(HarMode::to_script_value).unwrap_or(Value::Null)
+}
+
+fn read_har(path: &Path) -> Result<Value> {
+    let raw = fs::read_to_string(path)
+        .map_err(|err| DpcError::Config(format!("Failed to read HAR {}: {err}", path.display())))?;
+    let har: Value = serde_json::from_str(&raw).map_err(|err| {
+        DpcError::Config(format!("Failed to parse HAR {}: {err}", path.display()))
+    })?;
+    if !har["log"]["entries"].is_array() {
+        return Err(DpcError::Config(format!(
+            "{} is not a HAR file (no log.entries)",
+            path.display()
+        )));
+    }
+    Ok(har)
+}
+
+/// Collects the recordings of every render made through one manager into one HAR.
+///
+/// The first recording replaces the target file; later ones append their pages and
+/// entries to it.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HarRecorder {
+    state: Arc<Mutex<RecorderState>>,
+}
+
+#[derive(Debug, Default)]
+struct RecorderState {
+    parts: u64,
+    wrote_target: bool,
+}
+
+/// A render being recorded: where the script writes, and the HAR it belongs to.
+#[derive(Debug)]
+pub(crate) struct HarPart {
+    target: PathBuf,
+    part: PathBuf,
+}
+
+impl HarRecorder {
+    /// If `har` records, point it at a fresh part file and return the pending part.
+    pub(crate) fn begin(&self, har: &mut Option<HarMode>) -> Option<HarPart> {
+        let Some(HarMode::Record(target)) = har.as_ref() else {
+            return None;
+        };
+        let target = target.clone();
+        let index = {
+            let mut state = self.state.lock().unwrap();
+            state.parts += 1;
+            state.parts
+        };
+        let file_name = target
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| "recording.har".to_string());
+        let part =
+            target.with_file_name(format!("{file_name}.{}-{index}.part", std::process::id()));
+        *har = Some(HarMode::Record(part.clone()));
+        Some(HarPart { target, part })
+    }
+
+    /// Merge a finished part into its target HAR (only if the render succeeded) and
+    /// remove the part file.
+    pub(crate) fn finish(&self, part: Option<HarPart>, succeeded: bool) -> Result<()> {
+        let Some(HarPart { target, part }) = part else {
+            return Ok(());
+        };
+        let result = if succeeded {
+            let mut state = self.state.lock().unwrap();
+            let merged = append_recording(&target, &part, !state.wrote_target);
+            if merged.is_ok() {
+                state.wrote_target = true;
+            }
+            merged
+        } else {
+            Ok(())
+        };
+        let _ = fs::remove_file(&part);
+        result
+    }
+}
+
+/// Copy `recorded` over `target` (`replace`), or append its pages and entries to it.
+fn append_recording(target: &Path, recorded: &Path, replace: bool) -> Result<()> {
+    let recording = read_har(recorded)?;
+    let merged = if replace || !target.exists() {
+        recording
+    } else {
+        let mut har = read_har(target)?;
+        for key in ["pages", "entries"] {
+            if let Some(items) = recording["log"][key].as_array() {
+                match har["log"][key].as_array_mut() {
+                    Some(existing) => existing.extend(items.iter().cloned()),
+                    None => har["log"][key] = Value::Array(items.clone()),
+                }
+            }
+        }
+        har
+    };
+    let body = serde_json::to_string(&merged)
+        .map_err(|err| DpcError::Config(format!("Failed to encode HAR: {err}")))?;
+    fs::write(target, body)
+        .map_err(|err| DpcError::Config(format!("Failed to write HAR {}: {err}", target.display())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write_har(path: &Path, page: &str, url: &str) {
+        let har = json!({
+            "log": {
+                "version": "1.2",
+                "pages": [{ "id": page }],
+                "entries": [{ "pageref": page, "request": { "method": "GET", "url": url } }],
+            }
+        });
+        fs::write(path, har.to_string()).unwrap();
+    }
+
+    #[test]
+    fn recorder_merges_renders_into_one_har() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("site.har");
+        write_har(&target, "stale", "https://old.example/");
+        let recorder = HarRecorder::default();
+
+        for (page, url) in [
+            ("page@1", "https://example.com/"),
+            ("page@2", "https://example.com/b"),
+        ] {
+            let mut har = Some(HarMode::Record(target.clone()));
+            let part = recorder.begin(&mut har).expect("recording");
+            let Some(HarMode::Record(part_path)) = &har else {
+                panic!("expected a record mode");
+            };
+            assert_ne!(part_path, &target);
+            write_har(part_path, page, url);
+            recorder.finish(Some(part), true).unwrap();
+            assert!(!part_path.exists());
+        }
+
+        let merged = read_har(&target).unwrap();
+        let urls: Vec<&str> = merged["log"]["entries"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|entry| entry["request"]["url"].as_str().unwrap())
+            .collect();
+        assert_eq!(urls, ["https://example.com/", "https://example.com/b"]);
+        assert_eq!(merged["log"]["pages"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn replay_requires_a_har_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("missing.har");
+        let replay = HarMode::Replay {
+            path: path.clone(),
+            unmatched: HarUnmatched::Fail,
+        };
+        assert!(replay.check().is_err());
+
+        fs::write(&path, "{\"log\":{}}").unwrap();
+        assert!(replay
+            .check()
+            .unwrap_err()
+            .to_string()
+            .contains("not a HAR"));
+
+        write_har(&path, "page@1", "https://example.com/");
+        assert!(replay.check().is_ok());
+        assert_eq!(replay.to_script_value()["unmatched"], "fail");
+    }
+}
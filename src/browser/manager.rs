@@ -5,22 +5,30 @@
 
 use crate::types::{NormalizedView, ResourceKind};
 use crate::{DpcError, Result, Viewport};
+use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
-use tokio::io::AsyncReadExt;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::process::Command;
 use tokio::sync::Semaphore;
 use tokio::time::timeout;
 
+use super::auth::AuthOptions;
+use super::device::DeviceEmulation;
+use super::diagnostics::reported;
 use super::dom::{convert_raw_dom, ScriptResultWithDom};
+use super::har::{har_script_value, HarMode, HarRecorder};
 use super::playwright::{
     ensure_node_available, ensure_playwright_available, map_playwright_error,
-    map_playwright_status_error, map_spawn_error, ScriptError, ScriptResult, PLAYWRIGHT_SCRIPT,
-    PLAYWRIGHT_SCRIPT_WITH_DOM,
+    map_playwright_status_error, map_spawn_error, ScriptError, PLAYWRIGHT_SCRIPT_WITH_DOM,
 };
+use super::pool::{BrowserPool, RenderRequest};
+use super::scroll::{auto_scroll_script_value, AutoScroll};
+use super::state::InteractiveState;
+use super::steps::{step_error, steps_script_value, InteractionStep};
 
 /// Default timeout for page navigation.
 pub const DEFAULT_NAVIGATION_TIMEOUT: Duration = Duration::from_secs(30);
@@ -31,13 +39,77 @@ pub const DEFAULT_NETWORK_IDLE_TIMEOUT: Duration = Duration::from_secs(10);
 /// Default timeout for the entire Playwright process.
 pub const DEFAULT_PROCESS_TIMEOUT: Duration = Duration::from_secs(45);
 
+/// Callback invoked with human-readable progress messages during rendering.
+pub type ProgressCallback = Arc<dyn Fn(&str) + Send + Sync>;
+
+/// Which driver renders URLs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowserBackend {
+    /// Node.js + the `playwright` npm package (always available).
+    #[default]
+    Node,
+    /// Chrome DevTools Protocol from Rust; needs the `playwright` cargo feature and a local
+    /// Chrome/Chromium. Falls back to `Node` when the browser cannot be launched.
+    Cdp,
+}
+
+/// Browser engine URLs are rendered in; Playwright ships all three.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BrowserEngine {
+    #[default]
+    Chromium,
+    Firefox,
+    /// WebKit, the engine behind Safari.
+    Webkit,
+}
+
+impl BrowserEngine {
+    /// Playwright's name for the engine (`chromium`, `firefox`, `webkit`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BrowserEngine::Chromium => "chromium",
+            BrowserEngine::Firefox => "firefox",
+            BrowserEngine::Webkit => "webkit",
+        }
+    }
+}
+
+impl std::fmt::Display for BrowserEngine {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Configuration options for browser sessions.
 #[derive(Debug, Clone)]
 pub struct BrowserOptions {
+    /// Rendering driver (default: Node + Playwright).
+    pub backend: BrowserBackend,
+    /// Browser engine (default: Chromium). Only Chromium can use the CDP backend.
+    pub engine: BrowserEngine,
+    /// Chrome/Chromium executable for the CDP backend; auto-detected when unset.
+    pub chrome_path: Option<PathBuf>,
     /// The Node.js command to use (default: "node").
     pub node_command: String,
-    /// Viewport dimensions for the browser.
+    /// Headers, cookies, basic auth and storage state applied before navigation.
+    pub auth: AuthOptions,
+    /// Interactions run after the page settles and before the screenshot and DOM capture.
+    pub steps: Vec<InteractionStep>,
+    /// Freeze animations, caret, media, clock and randomness and wait for fonts
+    /// (see [`super::stabilize`]).
+    pub deterministic: bool,
+    /// Record the page's network traffic to a HAR file, or replay it from one.
+    pub har: Option<HarMode>,
+    /// Scroll through the page before capture so lazy-loaded content renders.
+    pub auto_scroll: Option<AutoScroll>,
+    /// Capture the whole document height instead of the viewport (screenshot and DOM).
+    pub full_page: bool,
+    /// Viewport dimensions for the browser, in CSS pixels.
     pub viewport: Viewport,
+    /// Scale factor, mobile/touch, user agent and media features of the browser context.
+    pub device: DeviceEmulation,
     /// Whether to run in headless mode.
     pub headless: bool,
     /// Timeout for page navigation.
@@ -53,8 +125,18 @@ pub struct BrowserOptions {
 impl Default for BrowserOptions {
     fn default() -> Self {
         Self {
+            backend: BrowserBackend::default(),
+            engine: BrowserEngine::default(),
+            chrome_path: None,
             node_command: "node".to_string(),
+            auth: AuthOptions::default(),
+            steps: Vec::new(),
+            deterministic: false,
+            har: None,
+            auto_scroll: None,
+            full_page: false,
             viewport: Viewport::default(),
+            device: DeviceEmulation::default(),
             headless: true,
             navigation_timeout: DEFAULT_NAVIGATION_TIMEOUT,
             network_idle_timeout: DEFAULT_NETWORK_IDLE_TIMEOUT,
@@ -65,10 +147,16 @@ impl Default for BrowserOptions {
 }
 
 /// Manages concurrent browser sessions with semaphore-based limiting.
+///
+/// Node renders share one long-lived browser (started on first use); clones of a manager
+/// share it too. It closes itself once the last clone is dropped, or call
+/// [`BrowserManager::shutdown`] to close it and wait for it to exit.
 #[derive(Debug, Clone)]
 pub struct BrowserManager {
     options: BrowserOptions,
     semaphore: Arc<Semaphore>,
+    pool: Arc<BrowserPool>,
+    har: HarRecorder,
 }
 
 /// Result of rendering a page.
@@ -80,6 +168,40 @@ pub struct PageRenderResult {
     pub viewport: Viewport,
     /// Time taken to render the page.
     pub elapsed: Duration,
+    /// Breakdown of where the render time went.
+    pub timing: RenderTiming,
+}
+
+/// Where the time for one render went.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RenderTiming {
+    /// Browser startup charged to this render; zero when a pooled browser was reused.
+    pub launch: Duration,
+    /// Navigation plus waiting for network idle.
+    pub navigation: Duration,
+    /// Screenshot and DOM extraction.
+    pub capture: Duration,
+    /// Wall time of the whole render, including queueing on the worker.
+    pub total: Duration,
+    /// Whether the render ran in an already running browser.
+    pub reused_browser: bool,
+}
+
+impl std::fmt::Display for RenderTiming {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:.1}s (", self.total.as_secs_f32())?;
+        if self.reused_browser {
+            write!(f, "browser reused")?;
+        } else {
+            write!(f, "launch {:.1}s", self.launch.as_secs_f32())?;
+        }
+        write!(
+            f,
+            ", navigation {:.1}s, capture {:.1}s)",
+            self.navigation.as_secs_f32(),
+            self.capture.as_secs_f32()
+        )
+    }
 }
 
 impl BrowserManager {
@@ -89,6 +211,8 @@ impl BrowserManager {
         Self {
             options,
             semaphore: Arc::new(Semaphore::new(permits)),
+            pool: Arc::new(BrowserPool::default()),
+            har: HarRecorder::default(),
         }
     }
 
@@ -105,7 +229,47 @@ impl BrowserManager {
             .await
             .map_err(|_| DpcError::Config("Browser manager unavailable".to_string()))?;
 
-        self.run_playwright(url, screenshot_path).await
+        let mut options: UrlToViewOptions = self.options.clone().into();
+        let recording = self.har.begin(&mut options.har);
+        let render = self
+            .pool
+            .render(
+                RenderRequest {
+                    url,
+                    screenshot_path,
+                    dom: false,
+                },
+                &options,
+            )
+            .await;
+        self.har.finish(recording, render.is_ok())?;
+        let render = render?;
+
+        Ok(PageRenderResult {
+            screenshot_path: screenshot_path.map(|path| path.to_path_buf()),
+            viewport: self.options.viewport,
+            elapsed: render.timing.total,
+            timing: render.timing,
+        })
+    }
+
+    /// Returns the options this manager was created with.
+    pub fn options(&self) -> &BrowserOptions {
+        &self.options
+    }
+
+    /// A manager rendering in `engine` with otherwise the same options. It has its own
+    /// pooled browser but shares this manager's session limit and HAR recording.
+    pub fn with_engine(&self, engine: BrowserEngine) -> Self {
+        Self {
+            options: BrowserOptions {
+                engine,
+                ..self.options.clone()
+            },
+            semaphore: self.semaphore.clone(),
+            pool: Arc::new(BrowserPool::default()),
+            har: self.har.clone(),
+        }
     }
 
     /// Render a URL to a full NormalizedView (screenshot + DOM snapshot) using the manager's settings.
@@ -114,122 +278,138 @@ impl BrowserManager {
         url: &str,
         screenshot_path: &Path,
     ) -> Result<NormalizedView> {
-        ensure_node_available(&self.options.node_command).await?;
-        let _permit = self
-            .semaphore
-            .acquire()
-            .await
-            .map_err(|_| DpcError::Config("Browser manager unavailable".to_string()))?;
-
         let options: UrlToViewOptions = self.options.clone().into();
-        url_to_normalized_view(url, screenshot_path, options).await
+        self.render_url_with_options(url, screenshot_path, options)
+            .await
     }
 
-    async fn run_playwright(
+    /// Render a URL to a NormalizedView with per-call options (e.g. a different viewport),
+    /// still bounded by the manager's session limit.
+    ///
+    /// When recording a HAR, every render through this manager ends up in the same file.
+    pub async fn render_url_with_options(
         &self,
         url: &str,
-        screenshot_path: Option<&Path>,
-    ) -> Result<PageRenderResult> {
-        // Fail fast if Node is missing to avoid spawning Playwright unnecessarily.
-        self.ensure_node_available().await?;
-        ensure_playwright_available(&self.options.node_command).await?;
+        screenshot_path: &Path,
+        mut options: UrlToViewOptions,
+    ) -> Result<NormalizedView> {
+        let recording = self.har.begin(&mut options.har);
+        let view = self.render_view(url, screenshot_path, options).await;
+        self.har.finish(recording, view.is_ok())?;
+        view
+    }
 
-        if let Some(path) = screenshot_path {
-            if let Some(parent) = path.parent() {
-                fs::create_dir_all(parent).map_err(|e| {
-                    DpcError::Config(format!("Failed to create screenshot dir: {}", e))
-                })?;
-            }
+    async fn render_view(
+        &self,
+        url: &str,
+        screenshot_path: &Path,
+        options: UrlToViewOptions,
+    ) -> Result<NormalizedView> {
+        // The CDP backend checks for Node itself, and only if it has to fall back.
+        let native =
+            options.backend == BrowserBackend::Cdp && options.engine == BrowserEngine::Chromium;
+        if !native {
+            ensure_node_available(&options.node_command).await?;
         }
+        let _permit = self
+            .semaphore
+            .acquire()
+            .await
+            .map_err(|_| DpcError::Config("Browser manager unavailable".to_string()))?;
 
-        let mut cmd = Command::new(&self.options.node_command);
-        cmd.arg("-e")
-            .arg(PLAYWRIGHT_SCRIPT)
-            .arg(url)
-            .arg(self.options.viewport.width.to_string())
-            .arg(self.options.viewport.height.to_string())
-            .arg(self.options.navigation_timeout.as_millis().to_string())
-            .arg(self.options.network_idle_timeout.as_millis().to_string())
-            .arg(
-                screenshot_path
-                    .map(|path| path.to_string_lossy().to_string())
-                    .unwrap_or_default(),
-            )
-            .arg(if self.options.headless { "1" } else { "0" })
-            .stdout(Stdio::piped())
-            .stderr(Stdio::piped());
-
-        let start = Instant::now();
-        let mut child = cmd
-            .spawn()
-            .map_err(|err| map_spawn_error(err, &self.options.node_command))?;
-
-        let stdout_pipe = child.stdout.take();
-        let stderr_pipe = child.stderr.take();
-
-        let stdout_task = tokio::spawn(async move {
-            let mut buf = Vec::new();
-            if let Some(mut out) = stdout_pipe {
-                let _ = out.read_to_end(&mut buf).await;
-            }
-            buf
-        });
-
-        let stderr_task = tokio::spawn(async move {
-            let mut buf = Vec::new();
-            if let Some(mut err) = stderr_pipe {
-                let _ = err.read_to_end(&mut buf).await;
-            }
-            buf
-        });
-
-        let status = match timeout(self.options.process_timeout, child.wait()).await {
-            Ok(Ok(status)) => status,
-            Ok(Err(err)) => return Err(DpcError::Io(err)),
-            Err(_) => {
-                let _ = child.kill().await;
-                let _ = child.wait().await;
-                return Err(DpcError::Config(format!(
-                    "Playwright timed out after {:?}",
-                    self.options.process_timeout
-                )));
-            }
-        };
-
-        let stdout = stdout_task.await.unwrap_or_else(|_| Vec::new());
-        let stderr = stderr_task.await.unwrap_or_else(|_| Vec::new());
-
-        if !status.success() {
-            let stderr = String::from_utf8_lossy(&stderr);
-            return Err(map_playwright_error(status.to_string(), &stderr));
+        if native {
+            return url_to_normalized_view(url, screenshot_path, options).await;
         }
 
-        let stdout = String::from_utf8_lossy(&stdout);
-        if let Ok(payload) = serde_json::from_str::<ScriptResult>(&stdout) {
-            if payload.status != "ok" {
-                let detail = payload
-                    .message
-                    .as_deref()
-                    .unwrap_or("no additional details");
-                return Err(DpcError::Config(format!(
-                    "Playwright returned non-ok status {}: {}",
-                    payload.status, detail
-                )));
+        log_progress(
+            &options.progress,
+            &format!(
+                "Rendering {} in {} ({}x{}, nav {}s, idle {}s)…",
+                url,
+                options.engine,
+                options.viewport.width,
+                options.viewport.height,
+                options.navigation_timeout.as_secs(),
+                options.network_idle_timeout.as_secs()
+            ),
+        );
+        if !options.device.is_default() {
+            log_progress(
+                &options.progress,
+                &format!("Emulating device: {}", options.device.describe()),
+            );
+            if options.device.is_mobile && options.engine == BrowserEngine::Firefox {
+                log_progress(
+                    &options.progress,
+                    "Firefox has no mobile mode; ignoring is_mobile (scale, touch and user agent still apply).",
+                );
             }
-        } else {
-            return Err(DpcError::Config(format!(
-                "Unexpected Playwright output: {}",
-                stdout.trim()
-            )));
         }
-
-        Ok(PageRenderResult {
-            screenshot_path: screenshot_path.map(|path| path.to_path_buf()),
-            viewport: self.options.viewport,
-            elapsed: start.elapsed(),
+        if let Some(har) = &options.har {
+            log_progress(&options.progress, &format!("HAR: {}…", har.describe()));
+        }
+        if let Some(scroll) = options.auto_scroll {
+            log_progress(
+                &options.progress,
+                &format!(
+                    "Auto-scrolling in {}px steps (up to {}px) before capture…",
+                    scroll.step, scroll.max_height
+                ),
+            );
+        }
+        if !options.steps.is_empty() {
+            log_progress(
+                &options.progress,
+                &format!(
+                    "Running {} interaction step(s) before capture…",
+                    options.steps.len()
+                ),
+            );
+        }
+        if let (Some(state), Some(selector)) = (options.state, &options.selector) {
+            log_progress(
+                &options.progress,
+                &format!("Capturing {selector} in the {state} state…"),
+            );
+        }
+        let render = self
+            .pool
+            .render(
+                RenderRequest {
+                    url,
+                    screenshot_path: Some(screenshot_path),
+                    dom: true,
+                },
+                &options,
+            )
+            .await?;
+        let dom = render.dom.ok_or_else(|| {
+            DpcError::Config("Playwright returned ok status but no DOM data".to_string())
+        })?;
+        log_progress(
+            &options.progress,
+            &format!("Capture finished in {}", render.timing),
+        );
+
+        let (width, height) = captured_size(screenshot_path, &options)?;
+        Ok(NormalizedView {
+            kind: ResourceKind::Url,
+            screenshot_path: screenshot_path.to_path_buf(),
+            width,
+            height,
+            dom: Some(convert_raw_dom(dom)),
+            figma_tree: None,
+            ocr_blocks: None,
+            diagnostics: reported(render.diagnostics),
         })
     }
 
+    /// Close the pooled browser, waiting briefly for it to exit. Later renders start a
+    /// new one; dropping the last clone of the manager also stops it.
+    pub async fn shutdown(&self) {
+        self.pool.shutdown().await;
+    }
+
     async fn ensure_node_available(&self) -> Result<()> {
         ensure_node_available(&self.options.node_command).await
     }
@@ -238,10 +418,38 @@ impl BrowserManager {
 /// Options for URL to NormalizedView conversion.
 #[derive(Clone)]
 pub struct UrlToViewOptions {
+    /// Rendering driver.
+    pub backend: BrowserBackend,
+    /// Browser engine; anything but Chromium renders through Node + Playwright.
+    pub engine: BrowserEngine,
+    /// Chrome/Chromium executable for the CDP backend; auto-detected when unset.
+    pub chrome_path: Option<PathBuf>,
     /// The Node.js command to use.
     pub node_command: String,
-    /// Viewport dimensions for the browser.
+    /// Headers, cookies, basic auth and storage state applied before navigation.
+    pub auth: AuthOptions,
+    /// Interactions run after the page settles and before the screenshot and DOM capture.
+    pub steps: Vec<InteractionStep>,
+    /// Freeze animations, caret, media, clock and randomness and wait for fonts
+    /// (see [`super::stabilize`]).
+    pub deterministic: bool,
+    /// Record the page's network traffic to a HAR file, or replay it from one.
+    pub har: Option<HarMode>,
+    /// Scroll through the page before capture so lazy-loaded content renders.
+    pub auto_scroll: Option<AutoScroll>,
+    /// Capture the whole document height instead of the viewport (screenshot and DOM).
+    /// Ignored when `selector` scopes the capture to one element.
+    pub full_page: bool,
+    /// CSS selector of the element to capture: the screenshot is clipped to the first
+    /// match and DOM bounding boxes are relative to its top-left corner.
+    pub selector: Option<String>,
+    /// State the `selector` element is put in right before capture; anything but
+    /// [`InteractiveState::Rest`] requires a selector.
+    pub state: Option<InteractiveState>,
+    /// Viewport dimensions for the browser, in CSS pixels.
     pub viewport: Viewport,
+    /// Scale factor, mobile/touch, user agent and media features of the browser context.
+    pub device: DeviceEmulation,
     /// Whether to run in headless mode.
     pub headless: bool,
     /// Timeout for page navigation.
@@ -251,14 +459,26 @@ pub struct UrlToViewOptions {
     /// Timeout for the entire Playwright process.
     pub process_timeout: Duration,
     /// Optional progress callback for logging.
-    pub progress: Option<Arc<dyn Fn(&str) + Send + Sync>>,
+    pub progress: Option<ProgressCallback>,
 }
 
 impl Default for UrlToViewOptions {
     fn default() -> Self {
         Self {
+            backend: BrowserBackend::default(),
+            engine: BrowserEngine::default(),
+            chrome_path: None,
             node_command: "node".to_string(),
+            auth: AuthOptions::default(),
+            steps: Vec::new(),
+            deterministic: false,
+            har: None,
+            auto_scroll: None,
+            full_page: false,
+            selector: None,
+            state: None,
             viewport: Viewport::default(),
+            device: DeviceEmulation::default(),
             headless: true,
             navigation_timeout: DEFAULT_NAVIGATION_TIMEOUT,
             network_idle_timeout: DEFAULT_NETWORK_IDLE_TIMEOUT,
@@ -271,8 +491,20 @@ impl Default for UrlToViewOptions {
 impl From<BrowserOptions> for UrlToViewOptions {
     fn from(opts: BrowserOptions) -> Self {
         Self {
+            backend: opts.backend,
+            engine: opts.engine,
+            chrome_path: opts.chrome_path,
             node_command: opts.node_command,
+            auth: opts.auth,
+            steps: opts.steps,
+            deterministic: opts.deterministic,
+            har: opts.har,
+            auto_scroll: opts.auto_scroll,
+            full_page: opts.full_page,
+            selector: None,
+            state: None,
             viewport: opts.viewport,
+            device: opts.device,
             headless: opts.headless,
             navigation_timeout: opts.navigation_timeout,
             network_idle_timeout: opts.network_idle_timeout,
@@ -282,7 +514,28 @@ impl From<BrowserOptions> for UrlToViewOptions {
     }
 }
 
-fn log_progress(progress: &Option<Arc<dyn Fn(&str) + Send + Sync>>, message: &str) {
+/// Size of a capture in CSS pixels: the viewport, or the screenshot's size (divided by the
+/// device scale factor) when a selector scoped the capture to one element or a full-page
+/// capture took the whole document.
+pub(crate) fn captured_size(
+    screenshot_path: &Path,
+    options: &UrlToViewOptions,
+) -> Result<(u32, u32)> {
+    if options.selector.is_none() && !options.full_page {
+        return Ok((options.viewport.width, options.viewport.height));
+    }
+    let (width, height) = image::image_dimensions(screenshot_path).map_err(|err| {
+        DpcError::Config(format!(
+            "Failed to read captured screenshot {}: {err}",
+            screenshot_path.display()
+        ))
+    })?;
+    let scale = options.device.device_scale_factor;
+    let css = |pixels: u32| ((pixels as f64 / scale).round() as u32).max(1);
+    Ok((css(width), css(height)))
+}
+
+pub(crate) fn log_progress(progress: &Option<ProgressCallback>, message: &str) {
     if let Some(cb) = progress {
         cb(message);
     }
@@ -313,12 +566,42 @@ pub async fn url_to_normalized_view(
     log_progress(
         &progress,
         &format!(
-            "Launching headless browser for {} ({}x{}, nav {}s, idle {}s)…",
-            url, options.viewport.width, options.viewport.height, nav_secs, idle_secs
+            "Launching headless {} for {} ({}x{}, nav {}s, idle {}s)…",
+            options.engine,
+            url,
+            options.viewport.width,
+            options.viewport.height,
+            nav_secs,
+            idle_secs
         ),
     );
+    if options.backend == BrowserBackend::Cdp && options.engine != BrowserEngine::Chromium {
+        log_progress(
+            &progress,
+            &format!(
+                "Native CDP backend only drives Chromium; rendering in {} with Node + Playwright…",
+                options.engine
+            ),
+        );
+    } else if options.backend == BrowserBackend::Cdp {
+        #[cfg(feature = "playwright")]
+        match super::cdp::CdpBrowser::launch(&options).await {
+            Ok(browser) => return browser.render(url, screenshot_path, &options).await,
+            Err(err) => log_progress(
+                &progress,
+                &format!(
+                    "Native CDP backend unavailable ({err}); falling back to Node + Playwright…"
+                ),
+            ),
+        }
+        #[cfg(not(feature = "playwright"))]
+        log_progress(
+            &progress,
+            "Native CDP backend not compiled in (build with `--features playwright`); falling back to Node + Playwright…",
+        );
+    }
     ensure_node_available(&options.node_command).await?;
-    ensure_playwright_available(&options.node_command).await?;
+    ensure_playwright_available(&options.node_command, options.engine).await?;
 
     if let Some(parent) = screenshot_path.parent() {
         fs::create_dir_all(parent)
@@ -335,6 +618,15 @@ pub async fn url_to_normalized_view(
         .arg(options.network_idle_timeout.as_millis().to_string())
         .arg(screenshot_path.to_string_lossy().to_string())
         .arg(if options.headless { "1" } else { "0" })
+        .arg(options.selector.as_deref().unwrap_or(""))
+        .arg(if options.deterministic { "1" } else { "0" })
+        .arg(har_script_value(options.har.as_ref()).to_string())
+        .arg(options.device.to_script_value(options.engine).to_string())
+        .arg(options.engine.as_str())
+        .arg(auto_scroll_script_value(options.auto_scroll).to_string())
+        .arg(if options.full_page { "1" } else { "0" })
+        .arg(options.state.map_or("", InteractiveState::as_str))
+        .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped());
 
@@ -347,6 +639,16 @@ pub async fn url_to_normalized_view(
         .spawn()
         .map_err(|err| map_spawn_error(err, &options.node_command))?;
 
+    // Credentials and steps (whose `fill` values may come from env) go over stdin: argv
+    // is readable by every local user through `ps`.
+    let secrets = serde_json::json!({
+        "auth": options.auth.to_script_value(),
+        "steps": steps_script_value(&options.steps),
+    });
+    if let Some(mut stdin) = child.stdin.take() {
+        let _ = stdin.write_all(secrets.to_string().as_bytes()).await;
+    }
+
     let stdout_pipe = child.stdout.take();
     let stderr_pipe = child.stderr.take();
 
@@ -388,6 +690,14 @@ pub async fn url_to_normalized_view(
 
     if !status.success() {
         let stderr = String::from_utf8_lossy(&stderr);
+        if let Ok(ScriptError {
+            message,
+            step: Some(index),
+            ..
+        }) = serde_json::from_str(&stderr)
+        {
+            return Err(step_error(&options.steps, index, url, message));
+        }
         return Err(map_playwright_error(status.to_string(), &stderr));
     }
 
@@ -421,14 +731,16 @@ pub async fn url_to_normalized_view(
         &format!("Capture finished in {:.1}s", start.elapsed().as_secs_f32()),
     );
 
+    let (width, height) = captured_size(screenshot_path, &options)?;
     Ok(NormalizedView {
         kind: ResourceKind::Url,
         screenshot_path: screenshot_path.to_path_buf(),
-        width: options.viewport.width,
-        height: options.viewport.height,
+        width,
+        height,
         dom: Some(dom_snapshot),
         figma_tree: None,
         ocr_blocks: None,
+        diagnostics: reported(result.diagnostics),
     })
 }
 
@@ -476,11 +788,27 @@ mod tests {
     #[test]
     fn url_to_view_options_from_browser_options() {
         let browser_opts = BrowserOptions {
+            backend: BrowserBackend::Cdp,
+            engine: BrowserEngine::Webkit,
+            chrome_path: Some(PathBuf::from("/opt/chrome")),
             node_command: "custom-node".to_string(),
+            auth: AuthOptions {
+                storage_state: Some(PathBuf::from("state.json")),
+                ..AuthOptions::default()
+            },
+            steps: vec![InteractionStep::WaitMs(100)],
+            deterministic: true,
+            har: Some(HarMode::Record(PathBuf::from("site.har"))),
             viewport: Viewport {
                 width: 1920,
                 height: 1080,
             },
+            device: DeviceEmulation {
+                device_scale_factor: 2.0,
+                ..DeviceEmulation::default()
+            },
+            auto_scroll: Some(AutoScroll::default()),
+            full_page: true,
             headless: false,
             navigation_timeout: Duration::from_secs(30),
             network_idle_timeout: Duration::from_secs(10),
@@ -489,16 +817,90 @@ mod tests {
         };
 
         let view_opts: UrlToViewOptions = browser_opts.into();
+        assert_eq!(view_opts.backend, BrowserBackend::Cdp);
+        assert_eq!(view_opts.engine, BrowserEngine::Webkit);
+        assert_eq!(view_opts.chrome_path, Some(PathBuf::from("/opt/chrome")));
         assert_eq!(view_opts.node_command, "custom-node");
+        assert_eq!(
+            view_opts.auth.storage_state,
+            Some(PathBuf::from("state.json"))
+        );
+        assert_eq!(view_opts.steps, vec![InteractionStep::WaitMs(100)]);
+        assert!(view_opts.deterministic);
+        assert_eq!(
+            view_opts.har,
+            Some(HarMode::Record(PathBuf::from("site.har")))
+        );
         assert!(!view_opts.headless);
         assert_eq!(view_opts.viewport.width, 1920);
         assert_eq!(view_opts.viewport.height, 1080);
+        assert_eq!(view_opts.device.device_scale_factor, 2.0);
+        assert_eq!(view_opts.auto_scroll, Some(AutoScroll::default()));
+        assert!(view_opts.full_page);
         assert_eq!(view_opts.navigation_timeout, Duration::from_secs(30));
         assert_eq!(view_opts.network_idle_timeout, Duration::from_secs(10));
         assert_eq!(view_opts.process_timeout, Duration::from_secs(60));
         assert!(view_opts.progress.is_none());
     }
 
+    #[test]
+    fn with_engine_keeps_the_other_options() {
+        let manager = BrowserManager::new(BrowserOptions {
+            deterministic: true,
+            max_concurrent_sessions: 2,
+            ..BrowserOptions::default()
+        });
+        let webkit = manager.with_engine(BrowserEngine::Webkit);
+        assert_eq!(webkit.options().engine, BrowserEngine::Webkit);
+        assert!(webkit.options().deterministic);
+        assert_eq!(webkit.options().max_concurrent_sessions, 2);
+        assert_eq!(manager.options().engine, BrowserEngine::Chromium);
+    }
+
+    #[test]
+    fn scoped_captures_take_the_element_screenshot_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("card.png");
+        image::RgbaImage::new(320, 180).save(&path).unwrap();
+
+        let mut options = UrlToViewOptions::default();
+        assert_eq!(
+            captured_size(&path, &options).unwrap(),
+            (options.viewport.width, options.viewport.height)
+        );
+        options.selector = Some(".card".to_string());
+        assert_eq!(captured_size(&path, &options).unwrap(), (320, 180));
+        options.device.device_scale_factor = 2.0;
+        assert_eq!(captured_size(&path, &options).unwrap(), (160, 90));
+        assert!(captured_size(&dir.path().join("missing.png"), &options).is_err());
+    }
+
+    #[test]
+    fn render_timing_display_reports_reuse() {
+        let fresh = RenderTiming {
+            launch: Duration::from_millis(1500),
+            navigation: Duration::from_millis(800),
+            capture: Duration::from_millis(200),
+            total: Duration::from_millis(2500),
+            reused_browser: false,
+        };
+        let reused = RenderTiming {
+            launch: Duration::ZERO,
+            total: Duration::from_millis(1000),
+            reused_browser: true,
+            ..fresh
+        };
+
+        assert_eq!(
+            fresh.to_string(),
+            "2.5s (launch 1.5s, navigation 0.8s, capture 0.2s)"
+        );
+        assert_eq!(
+            reused.to_string(),
+            "1.0s (browser reused, navigation 0.8s, capture 0.2s)"
+        );
+    }
+
     #[tokio::test]
     async fn ensure_node_available_fails_for_missing_binary() {
         let manager = BrowserManager::new(BrowserOptions {
@@ -510,6 +912,25 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[tokio::test]
+    async fn render_url_with_options_checks_node() {
+        let manager = BrowserManager::new(BrowserOptions {
+            max_concurrent_sessions: 2,
+            ..BrowserOptions::default()
+        });
+        let options = UrlToViewOptions {
+            node_command: "definitely-not-a-binary".to_string(),
+            ..manager.options().clone().into()
+        };
+
+        let result = manager
+            .render_url_with_options("https://example.com", Path::new("tmp.png"), options)
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(manager.semaphore.available_permits(), 2);
+    }
+
     #[tokio::test]
     async fn render_url_to_normalized_view_checks_node() {
         let manager = BrowserManager::new(BrowserOptions {
@@ -523,4 +944,29 @@ mod tests {
 
         assert!(result.is_err());
     }
+
+    #[cfg(not(feature = "playwright"))]
+    #[tokio::test]
+    async fn cdp_backend_falls_back_to_node_when_not_compiled_in() {
+        let messages = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let sink = messages.clone();
+        let options = UrlToViewOptions {
+            backend: BrowserBackend::Cdp,
+            node_command: "definitely-not-a-binary".to_string(),
+            progress: Some(Arc::new(move |msg: &str| {
+                sink.lock().unwrap().push(msg.to_string())
+            })),
+            ..UrlToViewOptions::default()
+        };
+
+        let result =
+            url_to_normalized_view("https://example.com", Path::new("tmp.png"), options).await;
+
+        assert!(result.is_err());
+        assert!(messages
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|m| m.contains("falling back to Node")));
+    }
 }
@@ -1,13 +1,25 @@
 //! Browser automation module for headless page rendering.
 //!
 //! This module provides functionality for capturing screenshots and DOM snapshots
-//! from web pages using Playwright via Node.js.
+//! from web pages using Playwright via Node.js, or natively over the Chrome DevTools
+//! Protocol when built with the `playwright` cargo feature.
 //!
 //! # Module Structure
 //!
+//! - [`auth`] - Headers, cookies, basic auth and storage state for authenticated pages
+//! - [`har`] - HAR recording and offline replay of page traffic
 //! - [`manager`] - Browser session management with concurrency control
 //! - [`playwright`] - Playwright scripts and availability checks
+//! - [`pool`] - Persistent Playwright worker shared across renders
+//! - [`scroll`] - Auto-scroll before capture, for lazy-loaded content
+//! - [`stabilize`] - Scripts behind deterministic rendering
+//! - [`state`] - Hover, focus and active states applied to the captured element
+//! - [`static_server`] - Loopback server for local HTML files and directories
+//! - [`steps`] - Interaction steps run before capture
+//! - `cdp` - Native Chrome DevTools Protocol backend (`playwright` feature only)
+//! - [`device`] - Device presets and emulation (scale factor, mobile, touch, media)
 //! - [`dom`] - DOM snapshot types and conversion
+//! - [`diagnostics`] - Console errors, failed requests and broken images seen while rendering
 //!
 //! # Example
 //!
@@ -23,12 +35,37 @@
 //! # }
 //! ```
 
+mod auth;
+#[cfg(feature = "playwright")]
+mod cdp;
+mod device;
+mod diagnostics;
 mod dom;
+mod har;
 mod manager;
 mod playwright;
+mod pool;
+mod scroll;
+mod stabilize;
+mod state;
+mod static_server;
+mod steps;
 
 // Re-export public types from manager
+pub use auth::{load_cookie_file, parse_header, resolve_secret, AuthOptions, BasicAuth, Cookie};
+pub use device::{
+    device_preset, ColorScheme, DeviceEmulation, DevicePreset, ReducedMotion, DEVICE_PRESETS,
+};
+pub use har::{HarMode, HarUnmatched};
 pub use manager::{
-    url_to_normalized_view, BrowserManager, BrowserOptions, PageRenderResult, UrlToViewOptions,
-    DEFAULT_NAVIGATION_TIMEOUT, DEFAULT_NETWORK_IDLE_TIMEOUT, DEFAULT_PROCESS_TIMEOUT,
+    url_to_normalized_view, BrowserBackend, BrowserEngine, BrowserManager, BrowserOptions,
+    PageRenderResult, ProgressCallback, RenderTiming, UrlToViewOptions, DEFAULT_NAVIGATION_TIMEOUT,
+    DEFAULT_NETWORK_IDLE_TIMEOUT, DEFAULT_PROCESS_TIMEOUT,
+};
+pub use scroll::{
+    AutoScroll, DEFAULT_SCROLL_MAX_HEIGHT, DEFAULT_SCROLL_SETTLE, DEFAULT_SCROLL_STEP,
 };
+pub use stabilize::{FROZEN_TIME_MS, RANDOM_SEED, STABILIZATIONS};
+pub use state::InteractiveState;
+pub use static_server::StaticServer;
+pub use steps::{load_steps, InteractionStep};
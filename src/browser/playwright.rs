@@ -3,196 +3,649 @@
 //! This module contains the inline Playwright scripts, error mapping,
 //! and availability checks for Node.js and Playwright.
 
+use super::diagnostics::{broken_images_fn, watch_page_fn};
+use super::manager::BrowserEngine;
+use super::scroll::auto_scroll_fn;
+use super::stabilize::{settle_page_fn, stabilize_init_fn};
+use super::state::{apply_state_fn, settle_state_fn};
 use crate::{DpcError, Result};
 use std::io;
 use std::process::Stdio;
 use std::time::Duration;
 use tokio::process::Command;
 
-/// Basic Playwright script for screenshot capture.
-pub(crate) const PLAYWRIGHT_SCRIPT: &str = r#"
-const [, , url, width, height, navTimeout, idleTimeout, screenshotPath, headlessFlag] = process.argv;
+/// DOM traversal shared by the Playwright script and the native CDP backend, as a JS
+/// arrow function returning `{ url, title, nodes }` (see [`super::dom::RawDomSnapshot`]).
+///
+/// It takes an optional root element: when given, only that subtree is captured and
+/// bounding boxes are relative to the root's top-left corner instead of the viewport.
+///
+/// Open shadow roots are walked as children of their host, and the document of a
+/// same-origin `<iframe>` as a child of the frame element, with boxes shifted into page
+/// coordinates. Nodes inside carry `shadowHost`/`frame` with the id of the nearest host or
+/// frame. Closed shadow roots and cross-origin frames stay opaque.
+///
+/// Computed styles include the box model (padding, margin, borders, radii), shadows, text
+/// spacing and casing, and any `::before`/`::after` generated content.
+///
+/// A macro rather than a const so it can be spliced into the script with `concat!`.
+macro_rules! dom_snapshot_fn {
+    () => {
+        r#"(root) => {
+  const nodes = [];
+  const origin = root ? root.getBoundingClientRect() : { x: 0, y: 0 };
+  let nodeId = 0;
+  const nodeMap = new Map();
 
-async function run() {
-  let browser;
-  try {
-    const { chromium } = require('playwright');
-    browser = await chromium.launch({ headless: headlessFlag !== '0' });
-    const context = await browser.newContext({
-      viewport: {
-        width: parseInt(width, 10),
-        height: parseInt(height, 10)
+  const px = (value) => {
+    const num = parseFloat(value);
+    return Number.isNaN(num) ? null : num;
+  };
+
+  const sides = (style, prefix, suffix) => ({
+    top: px(style[`${prefix}Top${suffix}`]) || 0,
+    right: px(style[`${prefix}Right${suffix}`]) || 0,
+    bottom: px(style[`${prefix}Bottom${suffix}`]) || 0,
+    left: px(style[`${prefix}Left${suffix}`]) || 0
+  });
+
+  // Percentage radii stay percentages in computed style; resolve them against the box.
+  const radius = (value, rect) => {
+    const num = px(value) || 0;
+    return String(value).trim().endsWith('%') ? (num / 100) * Math.min(rect.width, rect.height) : num;
+  };
+
+  function pseudoInfo(el, which) {
+    const style = el.ownerDocument.defaultView.getComputedStyle(el, which);
+    const content = style.content;
+    if (!content || content === 'none' || content === 'normal' || style.display === 'none') {
+      return null;
+    }
+    const quoted = /^"(.*)"$/s.exec(content);
+    return {
+      content: quoted ? quoted[1] : content,
+      width: px(style.width),
+      height: px(style.height)
+    };
+  }
+
+  function getComputedStyleInfo(el, rect) {
+    const style = el.ownerDocument.defaultView.getComputedStyle(el);
+    return {
+      fontFamily: style.fontFamily || null,
+      fontSize: parseFloat(style.fontSize) || null,
+      fontWeight: style.fontWeight || null,
+      lineHeight: parseFloat(style.lineHeight) || null,
+      color: style.color || null,
+      backgroundColor: style.backgroundColor || null,
+      display: style.display || null,
+      visibility: style.visibility || null,
+      opacity: style.opacity !== '' ? parseFloat(style.opacity) : null,
+      padding: sides(style, 'padding', ''),
+      margin: sides(style, 'margin', ''),
+      borderWidth: sides(style, 'border', 'Width'),
+      borderStyle: style.borderStyle || null,
+      borderColor: style.borderColor || null,
+      borderRadius: {
+        topLeft: radius(style.borderTopLeftRadius, rect),
+        topRight: radius(style.borderTopRightRadius, rect),
+        bottomRight: radius(style.borderBottomRightRadius, rect),
+        bottomLeft: radius(style.borderBottomLeftRadius, rect)
+      },
+      boxShadow: style.boxShadow && style.boxShadow !== 'none' ? style.boxShadow : null,
+      letterSpacing: style.letterSpacing === 'normal' ? 0 : px(style.letterSpacing),
+      textAlign: style.textAlign || null,
+      textTransform: style.textTransform || null,
+      before: pseudoInfo(el, '::before'),
+      after: pseudoInfo(el, '::after')
+    };
+  }
+
+  // Document of a same-origin frame, or null when it is cross-origin or not loaded.
+  function frameDocument(el) {
+    try {
+      return el.contentDocument;
+    } catch (err) {
+      return null;
+    }
+  }
+
+  // `scope` tracks where `node` lives: the enclosing shadow host and frame ids, and the
+  // frame's offset from the top-level viewport.
+  function traverse(node, parentId, scope) {
+    if (node.nodeType !== Node.ELEMENT_NODE) return null;
+
+    const el = node;
+    const id = `node-${nodeId++}`;
+    nodeMap.set(el, id);
+
+    const rect = el.getBoundingClientRect();
+    const tag = el.tagName.toLowerCase();
+
+    // Skip invisible elements
+    if (rect.width === 0 && rect.height === 0) return null;
+
+    const attributes = {};
+    for (const attr of el.attributes) {
+      attributes[attr.name] = attr.value;
+    }
+
+    // Get direct text content (not from children)
+    let text = null;
+    for (const child of el.childNodes) {
+      if (child.nodeType === Node.TEXT_NODE) {
+        const trimmed = child.textContent.trim();
+        if (trimmed) {
+          text = text ? text + ' ' + trimmed : trimmed;
+        }
       }
+    }
+
+    const childIds = [];
+    const visit = (child, childScope) => {
+      const childId = traverse(child, id, childScope);
+      if (childId) childIds.push(childId);
+    };
+    if (el.shadowRoot) {
+      for (const child of el.shadowRoot.children) {
+        visit(child, { ...scope, shadowHost: id });
+      }
+    }
+    for (const child of el.children) {
+      visit(child, scope);
+    }
+    if (tag === 'iframe' || tag === 'frame') {
+      const doc = frameDocument(el);
+      if (doc && doc.body) {
+        // The frame's viewport starts inside its border and padding.
+        const style = el.ownerDocument.defaultView.getComputedStyle(el);
+        visit(doc.body, {
+          shadowHost: null,
+          frame: id,
+          offsetX: scope.offsetX + rect.x + el.clientLeft + (parseFloat(style.paddingLeft) || 0),
+          offsetY: scope.offsetY + rect.y + el.clientTop + (parseFloat(style.paddingTop) || 0)
+        });
+      }
+    }
+
+    nodes.push({
+      id,
+      tag,
+      children: childIds,
+      parent: parentId,
+      attributes,
+      text,
+      boundingBox: {
+        x: rect.x + scope.offsetX - origin.x,
+        y: rect.y + scope.offsetY - origin.y,
+        width: rect.width,
+        height: rect.height
+      },
+      computedStyle: getComputedStyleInfo(el, rect),
+      shadowHost: scope.shadowHost,
+      frame: scope.frame
     });
-    const page = await context.newPage();
-    const navMs = parseInt(navTimeout, 10);
-    const idleMs = parseInt(idleTimeout, 10);
 
-    await page.goto(url, { waitUntil: 'networkidle', timeout: navMs });
-    await page.waitForLoadState('networkidle', { timeout: idleMs });
+    return id;
+  }
 
-    if (screenshotPath) {
-      await page.screenshot({ path: screenshotPath, fullPage: true });
+  traverse(root || document.body, null, { shadowHost: null, frame: null, offsetX: 0, offsetY: 0 });
+
+  return {
+    url: window.location.href,
+    title: document.title,
+    nodes
+  };
+}"#
+    };
+}
+
+/// `async function runSteps(page, steps, timeout)` running the interaction steps from
+/// [`super::steps::InteractionStep::to_script_value`] in order. A failing step's error
+/// gets a `stepIndex` so the caller can report which step it was.
+macro_rules! run_steps_fn {
+    () => {
+        r#"
+async function runSteps(page, steps, timeout) {
+  for (let i = 0; i < (steps || []).length; i++) {
+    const step = steps[i];
+    try {
+      switch (step.action) {
+        case 'click':
+          await page.click(step.selector, { timeout });
+          break;
+        case 'hover':
+          await page.hover(step.selector, { timeout });
+          break;
+        case 'fill':
+          await page.fill(step.selector, step.value, { timeout });
+          break;
+        case 'press':
+          await page.keyboard.press(step.key);
+          break;
+        case 'wait_for_selector':
+          await page.waitForSelector(step.selector, { state: 'visible', timeout });
+          break;
+        case 'wait_ms':
+          await page.waitForTimeout(step.ms);
+          break;
+        case 'scroll_to':
+          await page.locator(step.selector).first().scrollIntoViewIfNeeded({ timeout });
+          break;
+        default:
+          throw new Error('unknown step action ' + step.action);
+      }
+    } catch (err) {
+      const wrapped = err instanceof Error ? err : new Error(String(err));
+      wrapped.stepIndex = i;
+      throw wrapped;
     }
+  }
+}
+"#
+    };
+}
+
+/// `stabilizeInit` and `settlePage` from [`super::stabilize`], declared for `--deterministic`.
+macro_rules! stabilize_decls {
+    () => {
+        concat!(
+            "\nconst stabilizeInit = ",
+            stabilize_init_fn!(),
+            ";\nconst settlePage = ",
+            settle_page_fn!(),
+            ";\n"
+        )
+    };
+}
+
+/// `autoScroll` from [`super::scroll`], declared for `--auto-scroll`.
+macro_rules! auto_scroll_decl {
+    () => {
+        concat!("\nconst autoScroll = ", auto_scroll_fn!(), ";\n")
+    };
+}
 
-    console.log(JSON.stringify({ status: 'ok' }));
+/// `applyState` and the `settleStateFn` it runs from [`super::state`], for `--states`.
+macro_rules! state_decls {
+    () => {
+        concat!(
+            "\nconst settleStateFn = ",
+            settle_state_fn!(),
+            ";\n",
+            apply_state_fn!()
+        )
+    };
+}
+
+/// `watchPage` and `brokenImages` from [`super::diagnostics`].
+macro_rules! diagnostics_decls {
+    () => {
+        concat!(
+            watch_page_fn!(),
+            "\nconst brokenImages = ",
+            broken_images_fn!(),
+            ";\n"
+        )
+    };
+}
+
+/// `async function findScope(page, selector, timeout)` resolving the element a capture is
+/// clipped to (`null` without a selector).
+macro_rules! find_scope_fn {
+    () => {
+        r#"
+async function findScope(page, selector, timeout) {
+  if (!selector) return null;
+  try {
+    return await page.waitForSelector(selector, { state: 'visible', timeout });
   } catch (err) {
-    const message = err && err.message ? err.message : String(err);
-    console.error(JSON.stringify({ status: 'error', message }));
-    process.exitCode = 1;
-  } finally {
-    if (browser) {
-      await browser.close();
-    }
+    throw new Error('No visible element matches capture selector ' + selector + ' (' + (err && err.message ? err.message : String(err)) + ')');
   }
 }
+"#
+    };
+}
 
-run();
-"#;
+/// HAR helpers for `--har-record`/`--har-replay`, driven by the `har` object from
+/// [`super::har::HarMode::to_script_value`]: `harRecordOptions(har)` for `newContext`,
+/// `routeHar(context, har)` returning the list of requests the HAR could not answer, and
+/// `unmatchedError(har, unmatched)` turning that list into an error when replay is strict.
+macro_rules! har_fns {
+    () => {
+        r#"
+function harRecordOptions(har) {
+  return har && har.mode === 'record' ? { path: har.path, content: 'embed' } : undefined;
+}
+
+async function routeHar(context, har) {
+  const unmatched = [];
+  if (har && har.mode === 'replay') {
+    // Routes run newest first: this one only sees what the HAR route falls back on.
+    await context.route('**/*', (route) => {
+      const request = route.request();
+      unmatched.push(request.method() + ' ' + request.url());
+      return har.unmatched === 'stub' ? route.fulfill({ status: 404, body: '' }) : route.abort();
+    });
+    await context.routeFromHAR(har.path, { notFound: 'fallback' });
+  }
+  return unmatched;
+}
+
+function unmatchedError(har, unmatched) {
+  if (!har || har.mode !== 'replay' || har.unmatched === 'stub' || unmatched.length === 0) {
+    return null;
+  }
+  const more = unmatched.length > 5 ? ', and ' + (unmatched.length - 5) + ' more' : '';
+  return new Error(unmatched.length + ' request(s) not found in HAR ' + har.path + ': ' + unmatched.slice(0, 5).join(', ') + more);
+}
+"#
+    };
+}
+
+/// `async function launchBrowser(engineName, headless)` launching Playwright's
+/// `chromium`, `firefox` or `webkit` (see [`super::BrowserEngine`]); Chromium when unset.
+macro_rules! launch_browser_fn {
+    () => {
+        r#"
+async function launchBrowser(engineName, headless) {
+  const name = engineName || 'chromium';
+  const browserType = require('playwright')[name];
+  if (!browserType || typeof browserType.launch !== 'function') {
+    throw new Error('Unknown browser engine: ' + name);
+  }
+  return browserType.launch({ headless });
+}
+"#
+    };
+}
+
+/// JS arrow function that extracts the DOM snapshot.
+#[cfg_attr(not(feature = "playwright"), allow(dead_code))]
+pub(crate) const DOM_SNAPSHOT_FN: &str = dom_snapshot_fn!();
 
 /// Playwright script that captures both screenshot and DOM snapshot.
-pub(crate) const PLAYWRIGHT_SCRIPT_WITH_DOM: &str = r#"
-const [, , url, width, height, navTimeout, idleTimeout, screenshotPath, headlessFlag] = process.argv;
+pub(crate) const PLAYWRIGHT_SCRIPT_WITH_DOM: &str = concat!(
+    r#"
+const [, url, width, height, navTimeout, idleTimeout, screenshotPath, headlessFlag, selector, deterministicFlag, harJson, deviceJson, engineName, autoScrollJson, fullPageFlag, stateName] = process.argv;
+const deterministic = deterministicFlag === '1';
+const har = harJson ? JSON.parse(harJson) : null;
+const scrollOptions = autoScrollJson ? JSON.parse(autoScrollJson) : null;
+const fullPage = fullPageFlag === '1';
+// Secrets arrive on stdin as one JSON object, never on the command line.
+const secrets = JSON.parse(require('fs').readFileSync(0, 'utf8') || '{}');
+"#,
+    run_steps_fn!(),
+    find_scope_fn!(),
+    stabilize_decls!(),
+    auto_scroll_decl!(),
+    state_decls!(),
+    diagnostics_decls!(),
+    har_fns!(),
+    launch_browser_fn!(),
+    r#"
 
 async function run() {
   let browser;
   try {
-    const { chromium } = require('playwright');
-    browser = await chromium.launch({ headless: headlessFlag !== '0' });
+    const auth = secrets.auth || {};
+    browser = await launchBrowser(engineName, headlessFlag !== '0');
     const context = await browser.newContext({
+      ...(deviceJson ? JSON.parse(deviceJson) : {}),
       viewport: {
         width: parseInt(width, 10),
         height: parseInt(height, 10)
-      }
+      },
+      extraHTTPHeaders: auth.extraHTTPHeaders,
+      httpCredentials: auth.httpCredentials,
+      storageState: auth.storageState,
+      recordHar: harRecordOptions(har)
     });
+    if (auth.cookies) {
+      await context.addCookies(auth.cookies);
+    }
+    if (deterministic) {
+      await context.addInitScript(stabilizeInit);
+    }
+    const unmatched = await routeHar(context, har);
     const page = await context.newPage();
+    const watched = watchPage(page);
     const navMs = parseInt(navTimeout, 10);
     const idleMs = parseInt(idleTimeout, 10);
 
-    await page.goto(url, { waitUntil: 'networkidle', timeout: navMs });
-    await page.waitForLoadState('networkidle', { timeout: idleMs });
+    try {
+      await page.goto(url, { waitUntil: 'networkidle', timeout: navMs });
+      await page.waitForLoadState('networkidle', { timeout: idleMs });
+    } catch (err) {
+      throw unmatchedError(har, unmatched) || err;
+    }
+    await runSteps(page, secrets.steps || [], navMs);
+    if (scrollOptions) {
+      await page.evaluate(autoScroll, scrollOptions);
+      await page.waitForLoadState('networkidle', { timeout: idleMs }).catch(() => {});
+    }
+    const scope = await findScope(page, selector, navMs);
+    if (fullPage && !scope) {
+      // From the top, viewport boxes are document boxes, matching the full-page shot.
+      await page.evaluate(() => window.scrollTo(0, 0));
+    }
+    if (deterministic) {
+      await page.evaluate(settlePage);
+    }
+    await applyState(page, scope, stateName);
 
     if (screenshotPath) {
-      await page.screenshot({ path: screenshotPath, fullPage: false });
+      const animations = deterministic ? 'disabled' : 'allow';
+      if (scope) {
+        await scope.screenshot({ path: screenshotPath, animations });
+      } else {
+        await page.screenshot({ path: screenshotPath, fullPage, animations });
+      }
     }
 
     // Extract DOM snapshot
-    const domSnapshot = await page.evaluate(() => {
-      const nodes = [];
-      let nodeId = 0;
-      const nodeMap = new Map();
-
-      function getComputedStyleInfo(el) {
-        const style = window.getComputedStyle(el);
-        return {
-          fontFamily: style.fontFamily || null,
-          fontSize: parseFloat(style.fontSize) || null,
-          fontWeight: style.fontWeight || null,
-          lineHeight: parseFloat(style.lineHeight) || null,
-          color: style.color || null,
-          backgroundColor: style.backgroundColor || null,
-          display: style.display || null,
-          visibility: style.visibility || null,
-          opacity: style.opacity !== '' ? parseFloat(style.opacity) : null
-        };
-      }
-
-      function traverse(node, parentId) {
-        if (node.nodeType !== Node.ELEMENT_NODE) return null;
+    const domSnapshot = await page.evaluate("#,
+    dom_snapshot_fn!(),
+    r#", scope);
+    const diagnostics = { ...watched, brokenImages: await page.evaluate(brokenImages) };
+    const missing = unmatchedError(har, unmatched);
+    if (missing) throw missing;
+    // Closing the context is what writes a recorded HAR.
+    await context.close();
 
-        const el = node;
-        const id = `node-${nodeId++}`;
-        nodeMap.set(el, id);
-
-        const rect = el.getBoundingClientRect();
-        const tag = el.tagName.toLowerCase();
-
-        // Skip invisible elements
-        if (rect.width === 0 && rect.height === 0) return null;
+    console.log(JSON.stringify({ status: 'ok', dom: domSnapshot, diagnostics }));
+  } catch (err) {
+    const message = err && err.message ? err.message : String(err);
+    console.error(JSON.stringify({ status: 'error', message, step: err && err.stepIndex }));
+    process.exitCode = 1;
+  } finally {
+    if (browser) {
+      await browser.close();
+    }
+  }
+}
 
-        const attributes = {};
-        for (const attr of el.attributes) {
-          attributes[attr.name] = attr.value;
-        }
+run();
+"#
+);
 
-        // Get direct text content (not from children)
-        let text = null;
-        for (const child of el.childNodes) {
-          if (child.nodeType === Node.TEXT_NODE) {
-            const trimmed = child.textContent.trim();
-            if (trimmed) {
-              text = text ? text + ' ' + trimmed : trimmed;
-            }
-          }
-        }
+/// Long-lived Playwright worker behind [`super::pool::BrowserPool`].
+///
+/// Launches the browser once, prints `{ event: 'ready', launchMs }`, then serves one JSON
+/// request per stdin line in a fresh browser context, answering with a JSON line tagged
+/// with the request `id`. Closing stdin closes the browser and exits; if the browser dies
+/// the process exits too, so the pool can tell the worker is gone.
+pub(crate) const BROWSER_POOL_SCRIPT: &str = concat!(
+    r#"
+const readline = require('readline');
+const [, headlessFlag, engineName] = process.argv;
+const domSnapshotFn = "#,
+    dom_snapshot_fn!(),
+    r#";
+"#,
+    run_steps_fn!(),
+    find_scope_fn!(),
+    stabilize_decls!(),
+    auto_scroll_decl!(),
+    state_decls!(),
+    diagnostics_decls!(),
+    har_fns!(),
+    launch_browser_fn!(),
+    r#"
+const send = (msg) => process.stdout.write(JSON.stringify(msg) + '\n');
+const errorMessage = (err) => (err && err.message ? err.message : String(err));
 
-        const childIds = [];
-        for (const child of el.children) {
-          const childId = traverse(child, id);
-          if (childId) childIds.push(childId);
-        }
+// Contexts of renders in flight by request id, and ids cancelled before their context
+// existed, so `{ cancel: id }` can close a render the caller gave up on.
+const contexts = new Map();
+const cancelled = new Set();
 
-        nodes.push({
-          id,
-          tag,
-          children: childIds,
-          parent: parentId,
-          attributes,
-          text,
-          boundingBox: {
-            x: rect.x,
-            y: rect.y,
-            width: rect.width,
-            height: rect.height
-          },
-          computedStyle: getComputedStyleInfo(el)
-        });
+async function render(browser, req) {
+  const auth = req.auth || {};
+  const context = await browser.newContext({
+    ...(req.device || {}),
+    viewport: { width: req.width, height: req.height },
+    extraHTTPHeaders: auth.extraHTTPHeaders,
+    httpCredentials: auth.httpCredentials,
+    storageState: auth.storageState,
+    recordHar: harRecordOptions(req.har)
+  });
+  contexts.set(req.id, context);
+  try {
+    if (cancelled.delete(req.id)) {
+      throw new Error('Render cancelled');
+    }
+    if (auth.cookies) {
+      await context.addCookies(auth.cookies);
+    }
+    if (req.deterministic) {
+      await context.addInitScript(stabilizeInit);
+    }
+    const unmatched = await routeHar(context, req.har);
+    const page = await context.newPage();
+    const watched = watchPage(page);
+    const navStart = Date.now();
+    try {
+      await page.goto(req.url, { waitUntil: 'networkidle', timeout: req.navTimeout });
+      await page.waitForLoadState('networkidle', { timeout: req.idleTimeout });
+    } catch (err) {
+      throw unmatchedError(req.har, unmatched) || err;
+    }
+    await runSteps(page, req.steps, req.navTimeout);
+    if (req.autoScroll) {
+      await page.evaluate(autoScroll, req.autoScroll);
+      await page.waitForLoadState('networkidle', { timeout: req.idleTimeout }).catch(() => {});
+    }
+    const scope = await findScope(page, req.selector, req.navTimeout);
+    if (req.fullPage && !scope) {
+      await page.evaluate(() => window.scrollTo(0, 0));
+    }
+    if (req.deterministic) {
+      await page.evaluate(settlePage);
+    }
+    await applyState(page, scope, req.state);
 
-        return id;
+    const captureStart = Date.now();
+    if (req.screenshotPath) {
+      const animations = req.deterministic ? 'disabled' : 'allow';
+      if (scope) {
+        await scope.screenshot({ path: req.screenshotPath, animations });
+      } else {
+        await page.screenshot({ path: req.screenshotPath, fullPage: req.fullPage, animations });
       }
+    }
+    const dom = req.dom ? await page.evaluate(domSnapshotFn, scope) : null;
+    const diagnostics = { ...watched, brokenImages: await page.evaluate(brokenImages) };
+    const missing = unmatchedError(req.har, unmatched);
+    if (missing) throw missing;
 
-      traverse(document.body, null);
+    return {
+      status: 'ok',
+      dom,
+      diagnostics,
+      timing: { navigationMs: captureStart - navStart, captureMs: Date.now() - captureStart }
+    };
+  } finally {
+    contexts.delete(req.id);
+    await context.close().catch(() => {});
+  }
+}
 
-      return {
-        url: window.location.href,
-        title: document.title,
-        nodes
-      };
-    });
+function cancel(id) {
+  const context = contexts.get(id);
+  if (context) {
+    context.close().catch(() => {});
+  } else {
+    cancelled.add(id);
+  }
+}
 
-    console.log(JSON.stringify({ status: 'ok', dom: domSnapshot }));
+async function run() {
+  let browser;
+  try {
+    const start = Date.now();
+    browser = await launchBrowser(engineName, headlessFlag !== '0');
+    browser.on('disconnected', () => process.exit(1));
+    send({ event: 'ready', launchMs: Date.now() - start });
   } catch (err) {
-    const message = err && err.message ? err.message : String(err);
-    console.error(JSON.stringify({ status: 'error', message }));
-    process.exitCode = 1;
-  } finally {
-    if (browser) {
-      await browser.close();
-    }
+    send({ event: 'error', message: errorMessage(err) });
+    process.exit(1);
   }
+
+  const lines = readline.createInterface({ input: process.stdin });
+  lines.on('line', async (line) => {
+    let req;
+    try {
+      req = JSON.parse(line);
+    } catch (err) {
+      return;
+    }
+    if (req.cancel !== undefined) {
+      cancel(req.cancel);
+      return;
+    }
+    try {
+      send({ id: req.id, ...(await render(browser, req)) });
+    } catch (err) {
+      send({ id: req.id, status: 'error', message: errorMessage(err), step: err && err.stepIndex });
+    }
+  });
+  lines.on('close', async () => {
+    setTimeout(() => process.exit(0), 5000).unref();
+    browser.removeAllListeners('disconnected');
+    await browser.close().catch(() => {});
+    process.exit(0);
+  });
 }
 
 run();
-"#;
+"#
+);
 
 /// Timeout for checking node/playwright availability.
 pub(crate) const NODE_CHECK_TIMEOUT: Duration = Duration::from_secs(5);
 
-/// Script to check if Playwright is installed.
-const PLAYWRIGHT_CHECK_SCRIPT: &str = "require('playwright'); process.stdout.write('ok');";
-
-/// Simple script result for basic Playwright operations.
-#[derive(Debug, serde::Deserialize)]
-pub(crate) struct ScriptResult {
-    pub status: String,
-    pub message: Option<String>,
+/// Script checking that Playwright is installed along with the browser named by its
+/// argument.
+const PLAYWRIGHT_CHECK_SCRIPT: &str = r#"
+const [, engineName] = process.argv;
+const browserType = require('playwright')[engineName];
+const executable = browserType.executablePath();
+if (!executable || !require('fs').existsSync(executable)) {
+  process.stderr.write(engineName + ' executable is missing' + (executable ? ' at ' + executable : ''));
+  process.exit(1);
 }
+process.stdout.write('ok');
+"#;
 
 /// Error result from Playwright script.
 #[derive(Debug, serde::Deserialize)]
 pub(crate) struct ScriptError {
     pub status: String,
     pub message: String,
+    /// Index of the interaction step that failed, if any.
+    #[serde(default)]
+    pub step: Option<usize>,
 }
 
 /// Maps a spawn error to an appropriate DpcError.
@@ -207,6 +660,29 @@ pub(crate) fn map_spawn_error(err: io::Error, command: &str) -> DpcError {
     }
 }
 
+/// Recognizes a browser that Playwright has not downloaded, naming the engine when the
+/// message (usually the executable path) gives it away.
+fn missing_browser_error(message: &str) -> Option<DpcError> {
+    let lower = message.to_ascii_lowercase();
+    if !lower.contains("executable doesn't exist") && !lower.contains("executable is missing") {
+        return None;
+    }
+    let engine = [
+        BrowserEngine::Firefox,
+        BrowserEngine::Webkit,
+        BrowserEngine::Chromium,
+    ]
+    .into_iter()
+    .find(|engine| lower.contains(engine.as_str()));
+    Some(DpcError::Config(match engine {
+        Some(engine) => format!(
+            "Playwright {engine} executable is missing; install it with `npx playwright install {engine}`."
+        ),
+        None => "Playwright browser executable is missing; install browsers with `npx playwright install`."
+            .to_string(),
+    }))
+}
+
 /// Maps Playwright stderr output to an appropriate DpcError.
 pub(crate) fn map_playwright_error(status_text: impl Into<String>, stderr: &str) -> DpcError {
     if let Ok(error) = serde_json::from_str::<ScriptError>(stderr) {
@@ -215,6 +691,10 @@ pub(crate) fn map_playwright_error(status_text: impl Into<String>, stderr: &str)
 
     let lower = stderr.to_ascii_lowercase();
 
+    if let Some(err) = missing_browser_error(stderr) {
+        return err;
+    }
+
     if stderr
         .to_ascii_lowercase()
         .contains("cannot find module 'playwright'")
@@ -240,7 +720,9 @@ pub(crate) fn map_playwright_error(status_text: impl Into<String>, stderr: &str)
 
 /// Maps a Playwright status error to an appropriate DpcError.
 pub(crate) fn map_playwright_status_error(status: &str, message: String) -> DpcError {
-    if message
+    if let Some(err) = missing_browser_error(&message) {
+        err
+    } else if message
         .to_ascii_lowercase()
         .contains("cannot find module 'playwright'")
     {
@@ -291,8 +773,11 @@ pub(crate) async fn ensure_node_available(node_command: &str) -> Result<()> {
     Ok(())
 }
 
-/// Ensures Playwright npm package is installed.
-pub(crate) async fn ensure_playwright_available(node_command: &str) -> Result<()> {
+/// Ensures the Playwright npm package and the browser for `engine` are installed.
+pub(crate) async fn ensure_playwright_available(
+    node_command: &str,
+    engine: BrowserEngine,
+) -> Result<()> {
     if is_mock_rendering_enabled() {
         return Ok(());
     }
@@ -300,6 +785,7 @@ pub(crate) async fn ensure_playwright_available(node_command: &str) -> Result<()
     let mut cmd = Command::new(node_command);
     cmd.arg("-e")
         .arg(PLAYWRIGHT_CHECK_SCRIPT)
+        .arg(engine.as_str())
         .stdout(Stdio::null())
         .stderr(Stdio::piped());
 
@@ -328,6 +814,75 @@ pub(crate) async fn ensure_playwright_available(node_command: &str) -> Result<()
 mod tests {
     use super::*;
 
+    #[test]
+    fn scripts_are_valid_javascript() {
+        let dir = tempfile::tempdir().unwrap();
+        for (name, script) in [
+            ("with_dom.js", PLAYWRIGHT_SCRIPT_WITH_DOM),
+            ("pool.js", BROWSER_POOL_SCRIPT),
+        ] {
+            let path = dir.path().join(name);
+            std::fs::write(&path, script).unwrap();
+            let Ok(out) = std::process::Command::new("node")
+                .arg("--check")
+                .arg(&path)
+                .output()
+            else {
+                return; // Node is not installed.
+            };
+            assert!(
+                out.status.success(),
+                "{name}: {}",
+                String::from_utf8_lossy(&out.stderr)
+            );
+        }
+    }
+
+    #[test]
+    fn script_with_dom_embeds_shared_traversal() {
+        assert!(DOM_SNAPSHOT_FN.starts_with("(root) => {"));
+        assert!(PLAYWRIGHT_SCRIPT_WITH_DOM.contains(DOM_SNAPSHOT_FN));
+    }
+
+    #[test]
+    fn scripts_run_steps_before_capture() {
+        for script in [PLAYWRIGHT_SCRIPT_WITH_DOM, BROWSER_POOL_SCRIPT] {
+            let steps = script.find("await runSteps(page").unwrap();
+            assert!(script.contains("async function runSteps(page, steps, timeout)"));
+            assert!(steps < script.find("page.screenshot(").unwrap());
+        }
+    }
+
+    #[test]
+    fn scripts_stabilize_before_navigation_and_capture() {
+        for script in [PLAYWRIGHT_SCRIPT_WITH_DOM, BROWSER_POOL_SCRIPT] {
+            let init = script.find("context.addInitScript(stabilizeInit)").unwrap();
+            let settle = script.find("page.evaluate(settlePage)").unwrap();
+            assert!(init < script.find("page.goto(").unwrap());
+            assert!(settle < script.find("page.screenshot(").unwrap());
+        }
+    }
+
+    #[test]
+    fn scripts_route_har_before_navigation() {
+        for script in [PLAYWRIGHT_SCRIPT_WITH_DOM, BROWSER_POOL_SCRIPT] {
+            assert!(script.contains("recordHar: harRecordOptions("));
+            let route = script.find("= await routeHar(context, ").unwrap();
+            assert!(route < script.find("page.goto(").unwrap());
+            assert!(script.contains("unmatchedError("));
+        }
+    }
+
+    #[test]
+    fn scripts_clip_capture_to_scope_element() {
+        for script in [PLAYWRIGHT_SCRIPT_WITH_DOM, BROWSER_POOL_SCRIPT] {
+            assert!(script.contains("async function findScope(page, selector, timeout)"));
+            assert!(script.contains("await scope.screenshot("));
+            assert!(script.contains(", scope)"));
+        }
+        assert!(DOM_SNAPSHOT_FN.contains("traverse(root || document.body, null, {"));
+    }
+
     #[test]
     fn map_playwright_error_detects_missing_module() {
         let err = map_playwright_error(
@@ -423,6 +978,117 @@ mod tests {
         assert!(msg.contains("Timeout navigating"));
     }
 
+    #[test]
+    fn missing_browser_errors_name_the_engine() {
+        let err = map_playwright_status_error(
+            "error",
+            "browserType.launch: Executable doesn't exist at /root/.cache/ms-playwright/webkit-1944/pw_run.sh"
+                .to_string(),
+        );
+        assert!(
+            err.to_string().contains("npx playwright install webkit"),
+            "got: {err}"
+        );
+        let err = map_playwright_error("exit status: 1", "firefox executable is missing");
+        assert!(err
+            .to_string()
+            .contains("Playwright firefox executable is missing"));
+    }
+
+    #[test]
+    fn scripts_read_their_arguments_in_order() {
+        // `node -e` puts the first script argument at argv[1].
+        let probe = |script: &str, print: &str, args: &[&str]| {
+            let argv = script
+                .lines()
+                .find(|line| line.contains("= process.argv"))
+                .unwrap();
+            std::process::Command::new("node")
+                .arg("-e")
+                .arg(format!("{argv}\nprocess.stdout.write({print});"))
+                .args(args)
+                .output()
+                .ok()
+                .map(|out| String::from_utf8_lossy(&out.stdout).to_string())
+        };
+        let Some(pool) = probe(
+            BROWSER_POOL_SCRIPT,
+            "headlessFlag + ' ' + engineName",
+            &["0", "webkit"],
+        ) else {
+            return; // Node is not installed.
+        };
+        assert_eq!(pool, "0 webkit");
+
+        let mut args = vec!["https://example.com"];
+        args.extend(["1"; 10]);
+        args.extend(["firefox", "null", "1", "hover"]);
+        let one_shot = probe(
+            PLAYWRIGHT_SCRIPT_WITH_DOM,
+            "url + ' ' + engineName + ' ' + autoScrollJson + ' ' + fullPageFlag + ' ' + stateName",
+            &args,
+        );
+        assert_eq!(
+            one_shot.as_deref(),
+            Some("https://example.com firefox null 1 hover")
+        );
+    }
+
+    #[test]
+    fn one_shot_script_reads_secrets_from_stdin() {
+        use std::io::Write;
+
+        let secrets = PLAYWRIGHT_SCRIPT_WITH_DOM
+            .lines()
+            .find(|line| line.contains("readFileSync(0"))
+            .unwrap();
+        let Ok(mut child) = std::process::Command::new("node")
+            .arg("-e")
+            .arg(format!(
+                "{secrets}\nprocess.stdout.write(JSON.stringify([secrets.auth, secrets.steps]));"
+            ))
+            .stdin(std::process::Stdio::piped())
+            .stdout(std::process::Stdio::piped())
+            .spawn()
+        else {
+            return; // Node is not installed.
+        };
+        let mut stdin = child.stdin.take().unwrap();
+        stdin
+            .write_all(br#"{"auth":{"cookies":[]},"steps":[{"wait":"1s"}]}"#)
+            .unwrap();
+        drop(stdin);
+        let out = child.wait_with_output().unwrap();
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            r#"[{"cookies":[]},[{"wait":"1s"}]]"#
+        );
+    }
+
+    #[test]
+    fn scripts_auto_scroll_before_capturing_the_full_page() {
+        for script in [PLAYWRIGHT_SCRIPT_WITH_DOM, BROWSER_POOL_SCRIPT] {
+            let scroll = script.find("page.evaluate(autoScroll,").unwrap();
+            let screenshot = script.find("page.screenshot(").unwrap();
+            let dom = ["const domSnapshot = await", "const dom = req.dom"]
+                .iter()
+                .find_map(|marker| script.find(marker))
+                .unwrap();
+            assert!(scroll < screenshot && scroll < dom);
+            assert!(!script.contains("fullPage: false"));
+        }
+    }
+
+    #[test]
+    fn scripts_apply_the_state_after_settling_and_before_capture() {
+        for script in [PLAYWRIGHT_SCRIPT_WITH_DOM, BROWSER_POOL_SCRIPT] {
+            assert!(script.contains("async function applyState(page, scope, state)"));
+            let state = script.find("await applyState(page, scope, ").unwrap();
+            assert!(script.find("page.evaluate(settlePage)").unwrap() < state);
+            assert!(state < script.find("await scope.screenshot(").unwrap());
+        }
+    }
+
     #[tokio::test]
     async fn ensure_node_available_fails_for_missing_binary() {
         let result = ensure_node_available("definitely-not-a-binary").await;
@@ -431,7 +1097,8 @@ mod tests {
 
     #[tokio::test]
     async fn ensure_playwright_available_fails_for_missing_binary() {
-        let result = ensure_playwright_available("definitely-not-a-binary").await;
+        let result =
+            ensure_playwright_available("definitely-not-a-binary", BrowserEngine::Chromium).await;
         assert!(result.is_err());
     }
 }
@@ -0,0 +1,694 @@
+//! Persistent Playwright worker shared by every render of a [`super::BrowserManager`].
+//!
+//! Spawning Node and launching a browser costs far more than rendering a typical page, so
+//! the pool keeps one worker process (and its browser) alive and runs each render in a
+//! fresh, isolated browser context. A worker that dies is replaced on the next render,
+//! and a render that was in flight when it died is retried once. A render that times out
+//! is cancelled: the worker closes its context so a hung page does not stay open.
+
+use super::dom::RawDomSnapshot;
+use super::har::har_script_value;
+use super::manager::{log_progress, BrowserEngine, RenderTiming, UrlToViewOptions};
+use super::playwright::{
+    map_playwright_error, map_playwright_status_error, map_spawn_error, BROWSER_POOL_SCRIPT,
+};
+use super::scroll::auto_scroll_script_value;
+use super::state::InteractiveState;
+use super::steps::{step_error, steps_script_value};
+use crate::types::PageDiagnostics;
+use crate::{DpcError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex as StdMutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader, Lines};
+use tokio::process::{Child, ChildStdin, ChildStdout, Command};
+use tokio::sync::{oneshot, Mutex};
+use tokio::task::JoinHandle;
+use tokio::time::timeout;
+
+/// How long [`BrowserPool::shutdown`] waits for the worker to close its browser.
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+/// What a single pooled render should capture.
+pub(crate) struct RenderRequest<'a> {
+    pub url: &'a str,
+    pub screenshot_path: Option<&'a Path>,
+    pub dom: bool,
+}
+
+/// Output of a pooled render.
+#[derive(Debug)]
+pub(crate) struct PooledRender {
+    pub dom: Option<RawDomSnapshot>,
+    pub diagnostics: Option<PageDiagnostics>,
+    pub timing: RenderTiming,
+}
+
+/// A lazily started Playwright worker; see the module docs.
+#[derive(Default)]
+pub(crate) struct BrowserPool {
+    worker: Mutex<Option<Worker>>,
+    script: Option<&'static str>,
+    /// Request ids are unique across workers, so cancelling one never hits a request on
+    /// the worker that replaced it.
+    next_id: AtomicU64,
+}
+
+impl fmt::Debug for BrowserPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("BrowserPool").finish_non_exhaustive()
+    }
+}
+
+type Pending = Arc<StdMutex<PendingState>>;
+
+#[derive(Default)]
+struct PendingState {
+    closed: bool,
+    senders: HashMap<u64, oneshot::Sender<WorkerResponse>>,
+}
+
+/// A running worker process. Node, headless mode and the browser engine are fixed per
+/// process, so a render asking for different ones replaces the worker.
+///
+/// Dropping it closes the worker's stdin, which makes the script close its browser and
+/// exit on its own; [`Worker::shutdown`] additionally waits for that to happen.
+struct Worker {
+    node_command: String,
+    headless: bool,
+    engine: BrowserEngine,
+    child: Child,
+    stdin: ChildStdin,
+    pending: Pending,
+    reader: JoinHandle<()>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkerRequest<'a> {
+    id: u64,
+    url: &'a str,
+    width: u32,
+    height: u32,
+    nav_timeout: u128,
+    idle_timeout: u128,
+    screenshot_path: Option<String>,
+    full_page: bool,
+    dom: bool,
+    auth: serde_json::Value,
+    steps: serde_json::Value,
+    selector: Option<&'a str>,
+    deterministic: bool,
+    har: serde_json::Value,
+    device: serde_json::Value,
+    auto_scroll: serde_json::Value,
+    state: Option<&'static str>,
+}
+
+/// Asks the worker to close the context of request `cancel`.
+#[derive(Serialize)]
+struct CancelRequest {
+    cancel: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkerResponse {
+    id: Option<u64>,
+    event: Option<String>,
+    status: Option<String>,
+    message: Option<String>,
+    /// Index of the interaction step that failed, if any.
+    step: Option<usize>,
+    launch_ms: Option<u64>,
+    dom: Option<RawDomSnapshot>,
+    diagnostics: Option<PageDiagnostics>,
+    timing: Option<WorkerTiming>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WorkerTiming {
+    navigation_ms: u64,
+    capture_ms: u64,
+}
+
+impl BrowserPool {
+    /// Pool that runs `script` instead of the Playwright worker, for tests.
+    #[cfg(test)]
+    fn with_script(script: &'static str) -> Self {
+        Self {
+            script: Some(script),
+            ..Self::default()
+        }
+    }
+
+    /// Render one page, starting (or restarting) the worker if needed.
+    pub(crate) async fn render(
+        &self,
+        request: RenderRequest<'_>,
+        options: &UrlToViewOptions,
+    ) -> Result<PooledRender> {
+        if let Some(path) = request.screenshot_path.and_then(Path::parent) {
+            std::fs::create_dir_all(path)
+                .map_err(|e| DpcError::Config(format!("Failed to create screenshot dir: {}", e)))?;
+        }
+
+        let start = Instant::now();
+        let mut retried = false;
+        loop {
+            let (id, receiver, launch) = self.submit(&request, options).await?;
+            match timeout(options.process_timeout, receiver).await {
+                Ok(Ok(response)) => return finish(response, request.url, options, launch, start),
+                // The worker exited with this render in flight; give it one fresh worker.
+                Ok(Err(_)) if !retried => {
+                    retried = true;
+                    log_progress(
+                        &options.progress,
+                        "Browser worker exited unexpectedly; restarting it and retrying…",
+                    );
+                }
+                Ok(Err(_)) => {
+                    return Err(DpcError::Config(format!(
+                        "Browser worker exited unexpectedly twice while rendering; check that {engine} can run on this machine (`npx playwright install {engine}`).",
+                        engine = options.engine
+                    )))
+                }
+                Err(_) => {
+                    self.cancel(id).await;
+                    return Err(DpcError::Config(format!(
+                        "Playwright timed out after {:?}",
+                        options.process_timeout
+                    )))
+                }
+            }
+        }
+    }
+
+    /// Queue a request on a live worker, returning its id, its response channel and the
+    /// launch time if this call had to start the worker.
+    async fn submit(
+        &self,
+        request: &RenderRequest<'_>,
+        options: &UrlToViewOptions,
+    ) -> Result<(u64, oneshot::Receiver<WorkerResponse>, Option<Duration>)> {
+        let mut slot = self.worker.lock().await;
+        let reusable = slot.as_ref().is_some_and(|worker| {
+            worker.node_command == options.node_command
+                && worker.headless == options.headless
+                && worker.engine == options.engine
+                && !worker.pending.lock().unwrap().closed
+        });
+        let mut launch = None;
+        if !reusable {
+            if let Some(old) = slot.take() {
+                old.shutdown().await;
+            }
+            log_progress(
+                &options.progress,
+                &format!("Launching pooled headless {}…", options.engine),
+            );
+            let (worker, launched) = Worker::spawn(self.script, options).await?;
+            *slot = Some(worker);
+            launch = Some(launched);
+        }
+        let worker = slot.as_mut().expect("worker started above");
+
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = oneshot::channel();
+        {
+            let mut pending = worker.pending.lock().unwrap();
+            if !pending.closed {
+                pending.senders.insert(id, sender);
+            }
+            // Otherwise `sender` is dropped here and the caller sees the worker as crashed.
+        }
+        let line = serde_json::to_string(&WorkerRequest {
+            id,
+            url: request.url,
+            width: options.viewport.width,
+            height: options.viewport.height,
+            nav_timeout: options.navigation_timeout.as_millis(),
+            idle_timeout: options.network_idle_timeout.as_millis(),
+            screenshot_path: request
+                .screenshot_path
+                .map(|path| path.to_string_lossy().to_string()),
+            full_page: options.full_page,
+            dom: request.dom,
+            auth: options.auth.to_script_value(),
+            steps: steps_script_value(&options.steps),
+            selector: options.selector.as_deref(),
+            deterministic: options.deterministic,
+            har: har_script_value(options.har.as_ref()),
+            device: options.device.to_script_value(options.engine),
+            auto_scroll: auto_scroll_script_value(options.auto_scroll),
+            state: options.state.map(InteractiveState::as_str),
+        })
+        .map_err(|e| DpcError::Config(format!("Failed to encode render request: {}", e)))?;
+        if worker
+            .stdin
+            .write_all(format!("{line}\n").as_bytes())
+            .await
+            .is_err()
+        {
+            // Broken pipe: drop the sender so the caller retries on a fresh worker.
+            worker.pending.lock().unwrap().senders.remove(&id);
+        }
+        Ok((id, receiver, launch))
+    }
+
+    /// Give up on request `id`: forget its response channel and ask the worker to close
+    /// the request's browser context. Nothing is sent if the request already finished or
+    /// its worker has since been replaced.
+    async fn cancel(&self, id: u64) {
+        let mut slot = self.worker.lock().await;
+        let Some(worker) = slot.as_mut() else {
+            return;
+        };
+        if worker.pending.lock().unwrap().senders.remove(&id).is_none() {
+            return;
+        }
+        let line =
+            serde_json::to_string(&CancelRequest { cancel: id }).expect("cancel request encodes");
+        // A worker that cannot take the line is gone, and its contexts with it.
+        let _ = worker.stdin.write_all(format!("{line}\n").as_bytes()).await;
+    }
+
+    /// Close the browser and wait for the worker to exit (killing it after a grace period).
+    pub(crate) async fn shutdown(&self) {
+        if let Some(worker) = self.worker.lock().await.take() {
+            worker.shutdown().await;
+        }
+    }
+
+    /// Whether a worker process is currently running.
+    #[cfg(test)]
+    async fn is_running(&self) -> bool {
+        self.worker
+            .lock()
+            .await
+            .as_ref()
+            .is_some_and(|worker| !worker.pending.lock().unwrap().closed)
+    }
+
+    /// Number of requests waiting for a response from the current worker.
+    #[cfg(test)]
+    async fn pending_requests(&self) -> usize {
+        self.worker
+            .lock()
+            .await
+            .as_ref()
+            .map_or(0, |worker| worker.pending.lock().unwrap().senders.len())
+    }
+}
+
+impl Worker {
+    async fn spawn(
+        script: Option<&'static str>,
+        options: &UrlToViewOptions,
+    ) -> Result<(Self, Duration)> {
+        let started = Instant::now();
+        let mut cmd = Command::new(&options.node_command);
+        cmd.arg("-e")
+            .arg(script.unwrap_or(BROWSER_POOL_SCRIPT))
+            .arg(if options.headless { "1" } else { "0" })
+            .arg(options.engine.as_str())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped());
+        let mut child = cmd
+            .spawn()
+            .map_err(|err| map_spawn_error(err, &options.node_command))?;
+
+        let stdin = child.stdin.take().expect("stdin is piped");
+        let mut lines = BufReader::new(child.stdout.take().expect("stdout is piped")).lines();
+        // Drain stderr for the worker's whole life so a chatty browser cannot block it.
+        let stderr_pipe = child.stderr.take();
+        let stderr_task = tokio::spawn(async move {
+            let mut buf = Vec::new();
+            if let Some(mut err) = stderr_pipe {
+                let _ = err.read_to_end(&mut buf).await;
+            }
+            buf
+        });
+
+        let ready = match timeout(options.process_timeout, lines.next_line()).await {
+            Ok(Ok(Some(line))) => serde_json::from_str::<WorkerResponse>(&line).ok(),
+            Ok(_) => None,
+            Err(_) => {
+                let _ = child.kill().await;
+                return Err(DpcError::Config(format!(
+                    "Playwright timed out after {:?} while launching the browser",
+                    options.process_timeout
+                )));
+            }
+        };
+        let launch_ms = match ready {
+            Some(WorkerResponse {
+                event: Some(event),
+                launch_ms,
+                ..
+            }) if event == "ready" => launch_ms,
+            Some(WorkerResponse {
+                message: Some(message),
+                ..
+            }) => {
+                let _ = child.kill().await;
+                return Err(map_playwright_status_error("error", message));
+            }
+            _ => {
+                let status = match timeout(SHUTDOWN_GRACE, child.wait()).await {
+                    Ok(Ok(status)) => status.to_string(),
+                    _ => "unknown".to_string(),
+                };
+                let stderr = stderr_task.await.unwrap_or_default();
+                return Err(map_playwright_error(
+                    status,
+                    &String::from_utf8_lossy(&stderr),
+                ));
+            }
+        };
+
+        let pending = Pending::default();
+        let reader = tokio::spawn(read_responses(lines, pending.clone()));
+        let launch = launch_ms
+            .map(Duration::from_millis)
+            .unwrap_or_else(|| started.elapsed());
+        Ok((
+            Self {
+                node_command: options.node_command.clone(),
+                headless: options.headless,
+                engine: options.engine,
+                child,
+                stdin,
+                pending,
+                reader,
+            },
+            launch,
+        ))
+    }
+
+    async fn shutdown(mut self) {
+        // Closing stdin asks the worker to close its browser and exit.
+        drop(self.stdin);
+        if timeout(SHUTDOWN_GRACE, self.child.wait()).await.is_err() {
+            let _ = self.child.kill().await;
+        }
+        self.reader.abort();
+    }
+}
+
+/// Route worker responses to their callers; on EOF, fail everything still pending.
+async fn read_responses(mut lines: Lines<BufReader<ChildStdout>>, pending: Pending) {
+    while let Ok(Some(line)) = lines.next_line().await {
+        let Ok(response) = serde_json::from_str::<WorkerResponse>(&line) else {
+            continue;
+        };
+        let Some(id) = response.id else { continue };
+        if let Some(sender) = pending.lock().unwrap().senders.remove(&id) {
+            let _ = sender.send(response);
+        }
+    }
+    let mut pending = pending.lock().unwrap();
+    pending.closed = true;
+    pending.senders.clear();
+}
+
+fn finish(
+    response: WorkerResponse,
+    url: &str,
+    options: &UrlToViewOptions,
+    launch: Option<Duration>,
+    start: Instant,
+) -> Result<PooledRender> {
+    if response.status.as_deref() != Some("ok") {
+        let message = response
+            .message
+            .unwrap_or_else(|| "no additional details".to_string());
+        return Err(match response.step {
+            Some(index) => step_error(&options.steps, index, url, message),
+            None => {
+                map_playwright_status_error(response.status.as_deref().unwrap_or("error"), message)
+            }
+        });
+    }
+    let (navigation, capture) = response
+        .timing
+        .map(|t| {
+            (
+                Duration::from_millis(t.navigation_ms),
+                Duration::from_millis(t.capture_ms),
+            )
+        })
+        .unwrap_or_default();
+    Ok(PooledRender {
+        dom: response.dom,
+        diagnostics: response.diagnostics,
+        timing: RenderTiming {
+            launch: launch.unwrap_or_default(),
+            navigation,
+            capture,
+            total: start.elapsed(),
+            reused_browser: launch.is_none(),
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::browser::auth::{AuthOptions, BasicAuth};
+    use crate::browser::steps::InteractionStep;
+
+    /// Stand-in worker speaking the pool protocol without Playwright. Requests for a URL
+    /// containing `crash` make it exit without answering, ones containing `hang` are never
+    /// answered, and a step targeting `#missing` fails. A `cancelled` URL gets the ids
+    /// cancelled so far as its DOM title.
+    const FAKE_WORKER: &str = r#"
+const readline = require('readline');
+const send = (msg) => process.stdout.write(JSON.stringify(msg) + '\n');
+const cancelled = [];
+send({ event: 'ready', launchMs: 7 });
+readline.createInterface({ input: process.stdin }).on('line', (line) => {
+  const req = JSON.parse(line);
+  if (req.cancel !== undefined) {
+    cancelled.push(req.cancel);
+    return;
+  }
+  if (req.url.includes('crash')) process.exit(3);
+  if (req.url.includes('hang')) return;
+  if (req.url.includes('cancelled')) {
+    send({ id: req.id, status: 'ok', dom: { url: req.url, title: JSON.stringify(cancelled), nodes: [] } });
+    return;
+  }
+  if (req.url.includes('fail')) {
+    send({ id: req.id, status: 'error', message: 'net::ERR_NAME_NOT_RESOLVED' });
+    return;
+  }
+  const missing = req.steps.findIndex((step) => step.selector === '#missing');
+  if (missing >= 0) {
+    send({ id: req.id, status: 'error', step: missing, message: 'Timeout 30000ms exceeded' });
+    return;
+  }
+  send({
+    id: req.id,
+    status: 'ok',
+    dom: req.dom ? { url: req.url, title: JSON.stringify(req.auth), nodes: [] } : null,
+    timing: { navigationMs: 3, captureMs: 2 }
+  });
+});
+"#;
+
+    fn node_available() -> bool {
+        std::process::Command::new("node")
+            .arg("--version")
+            .output()
+            .is_ok_and(|out| out.status.success())
+    }
+
+    fn request(url: &str) -> RenderRequest<'_> {
+        RenderRequest {
+            url,
+            screenshot_path: None,
+            dom: true,
+        }
+    }
+
+    #[tokio::test]
+    async fn reuses_one_worker_across_renders() {
+        if !node_available() {
+            return;
+        }
+        let pool = BrowserPool::with_script(FAKE_WORKER);
+        let options = UrlToViewOptions::default();
+
+        let first = pool.render(request("https://a"), &options).await.unwrap();
+        let second = pool.render(request("https://b"), &options).await.unwrap();
+
+        assert!(!first.timing.reused_browser);
+        assert_eq!(first.timing.launch, Duration::from_millis(7));
+        assert!(second.timing.reused_browser);
+        assert_eq!(second.timing.launch, Duration::ZERO);
+        assert_eq!(second.timing.navigation, Duration::from_millis(3));
+        assert_eq!(second.dom.unwrap().url.as_deref(), Some("https://b"));
+
+        pool.shutdown().await;
+        assert!(!pool.is_running().await);
+    }
+
+    #[tokio::test]
+    async fn switching_engine_replaces_the_worker() {
+        if !node_available() {
+            return;
+        }
+        let pool = BrowserPool::with_script(FAKE_WORKER);
+        let chromium = UrlToViewOptions::default();
+        let firefox = UrlToViewOptions {
+            engine: BrowserEngine::Firefox,
+            ..UrlToViewOptions::default()
+        };
+
+        pool.render(request("https://a"), &chromium).await.unwrap();
+        let switched = pool.render(request("https://a"), &firefox).await.unwrap();
+        let again = pool.render(request("https://b"), &firefox).await.unwrap();
+
+        assert!(!switched.timing.reused_browser);
+        assert!(again.timing.reused_browser);
+        pool.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn sends_auth_with_each_request() {
+        if !node_available() {
+            return;
+        }
+        let pool = BrowserPool::with_script(FAKE_WORKER);
+        let options = UrlToViewOptions {
+            auth: AuthOptions {
+                headers: [("X-Env".to_string(), "staging".to_string())].into(),
+                basic_auth: Some(BasicAuth {
+                    username: "admin".to_string(),
+                    password: "pw".to_string(),
+                }),
+                ..AuthOptions::default()
+            },
+            ..UrlToViewOptions::default()
+        };
+
+        let render = pool.render(request("https://a"), &options).await.unwrap();
+        let auth: serde_json::Value =
+            serde_json::from_str(&render.dom.unwrap().title.unwrap()).unwrap();
+
+        assert_eq!(auth["extraHTTPHeaders"]["X-Env"], "staging");
+        assert_eq!(auth["httpCredentials"]["username"], "admin");
+        assert!(auth.get("storageState").is_none());
+        pool.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn restarts_worker_after_crash() {
+        if !node_available() {
+            return;
+        }
+        let pool = BrowserPool::with_script(FAKE_WORKER);
+        let options = UrlToViewOptions::default();
+
+        pool.render(request("https://a"), &options).await.unwrap();
+        // The fake worker crashes on every attempt, so the single retry fails too.
+        let err = pool
+            .render(request("https://crash"), &options)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("exited unexpectedly"));
+
+        let next = pool.render(request("https://b"), &options).await.unwrap();
+        assert!(!next.timing.reused_browser);
+        pool.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn step_failures_name_the_step() {
+        if !node_available() {
+            return;
+        }
+        let pool = BrowserPool::with_script(FAKE_WORKER);
+        let options = UrlToViewOptions {
+            steps: vec![
+                InteractionStep::Click("#menu".to_string()),
+                InteractionStep::WaitForSelector("#missing".to_string()),
+            ],
+            ..UrlToViewOptions::default()
+        };
+
+        let err = pool
+            .render(request("https://a"), &options)
+            .await
+            .unwrap_err();
+        match err {
+            DpcError::InteractionStep {
+                index, step, url, ..
+            } => {
+                assert_eq!(index, 2);
+                assert_eq!(step, "wait_for_selector #missing");
+                assert_eq!(url, "https://a");
+            }
+            other => panic!("expected an interaction step error, got {other:?}"),
+        }
+        pool.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn render_errors_keep_worker_alive() {
+        if !node_available() {
+            return;
+        }
+        let pool = BrowserPool::with_script(FAKE_WORKER);
+        let options = UrlToViewOptions::default();
+
+        let err = pool
+            .render(request("https://fail"), &options)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("ERR_NAME_NOT_RESOLVED"));
+        assert!(pool.is_running().await);
+
+        let next = pool.render(request("https://a"), &options).await.unwrap();
+        assert!(next.timing.reused_browser);
+        pool.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn timed_out_renders_are_cancelled() {
+        if !node_available() {
+            return;
+        }
+        let pool = BrowserPool::with_script(FAKE_WORKER);
+        let options = UrlToViewOptions {
+            process_timeout: Duration::from_millis(300),
+            ..UrlToViewOptions::default()
+        };
+
+        let err = pool
+            .render(request("https://hang"), &options)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("timed out"), "got: {err}");
+        assert_eq!(pool.pending_requests().await, 0);
+        assert!(pool.is_running().await);
+
+        let report = pool
+            .render(request("https://cancelled"), &options)
+            .await
+            .unwrap();
+        assert!(report.timing.reused_browser);
+        let cancelled: Vec<u64> =
+            serde_json::from_str(&report.dom.unwrap().title.unwrap()).unwrap();
+        assert_eq!(cancelled, vec![0]);
+        pool.shutdown().await;
+    }
+}
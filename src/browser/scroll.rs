@@ -0,0 +1,110 @@
+//! Pre-capture auto-scroll (`--auto-scroll`): walk the page down in steps so lazy images
+//! and infinite sections load, return to the top, and wait for images to decode before the
+//! screenshot and DOM capture.
+
+use serde_json::{json, Value};
+use std::time::Duration;
+
+/// Default distance scrolled per step, in CSS pixels.
+pub const DEFAULT_SCROLL_STEP: u32 = 800;
+
+/// Default height at which scrolling stops, in CSS pixels; pages that keep growing
+/// (infinite feeds) would otherwise never end.
+pub const DEFAULT_SCROLL_MAX_HEIGHT: u32 = 20_000;
+
+/// Default pause after each step for lazy content to start loading.
+pub const DEFAULT_SCROLL_SETTLE: Duration = Duration::from_millis(250);
+
+/// How to scroll a page before capture.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AutoScroll {
+    /// Distance per step, in CSS pixels.
+    pub step: u32,
+    /// Stop once this far down the document, in CSS pixels.
+    pub max_height: u32,
+    /// Pause after every step and after returning to the top.
+    pub settle: Duration,
+}
+
+impl Default for AutoScroll {
+    fn default() -> Self {
+        Self {
+            step: DEFAULT_SCROLL_STEP,
+            max_height: DEFAULT_SCROLL_MAX_HEIGHT,
+            settle: DEFAULT_SCROLL_SETTLE,
+        }
+    }
+}
+
+impl AutoScroll {
+    /// The argument `auto_scroll_fn!` takes: `{ step, maxHeight, settleMs }`.
+    pub(crate) fn to_script_value(self) -> Value {
+        json!({
+            "step": self.step.max(1),
+            "maxHeight": self.max_height,
+            "settleMs": self.settle.as_millis() as u64,
+        })
+    }
+}
+
+/// `auto_scroll_fn!`'s argument for the Playwright scripts; `null` turns scrolling off.
+pub(crate) fn auto_scroll_script_value(auto_scroll: Option<AutoScroll>) -> Value {
+    auto_scroll.map_or(Value::Null, AutoScroll::to_script_value)
+}
+
+/// JS async arrow function taking `{ step, maxHeight, settleMs }`: scrolls down until the
+/// bottom of the document (re-read every step, as lazy content grows it) or `maxHeight`,
+/// scrolls back to the top, then waits up to 5s for every image to load and decode.
+macro_rules! auto_scroll_fn {
+    () => {
+        r#"async ({ step, maxHeight, settleMs }) => {
+  const sleep = (ms) => new Promise((resolve) => setTimeout(resolve, ms));
+  const scroller = document.scrollingElement || document.documentElement;
+  for (let y = step; ; y += step) {
+    window.scrollTo(0, y);
+    await sleep(settleMs);
+    const bottom = Math.min(scroller.scrollHeight - window.innerHeight, maxHeight);
+    if (y >= bottom) break;
+  }
+  window.scrollTo(0, 0);
+  await sleep(settleMs);
+
+  const loaded = (img) =>
+    img.complete
+      ? Promise.resolve()
+      : new Promise((resolve) => {
+          img.addEventListener('load', resolve, { once: true });
+          img.addEventListener('error', resolve, { once: true });
+        });
+  const images = Array.from(document.images, (img) =>
+    loaded(img).then(() => (img.naturalWidth > 0 ? img.decode() : null)).catch(() => {})
+  );
+  await Promise.race([Promise.all(images), sleep(5000)]);
+}"#
+    };
+}
+pub(crate) use auto_scroll_fn;
+
+/// `auto_scroll_fn!` as a string, for the CDP backend.
+#[cfg_attr(not(feature = "playwright"), allow(dead_code))]
+pub(crate) const AUTO_SCROLL_FN: &str = auto_scroll_fn!();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_value_carries_step_height_and_settle() {
+        let value = auto_scroll_script_value(Some(AutoScroll {
+            step: 0,
+            max_height: 5000,
+            settle: Duration::from_millis(100),
+        }));
+        assert_eq!(
+            value,
+            json!({ "step": 1, "maxHeight": 5000, "settleMs": 100 })
+        );
+        assert!(auto_scroll_script_value(None).is_null());
+        assert!(AUTO_SCROLL_FN.contains("img.decode()"));
+    }
+}
@@ -0,0 +1,106 @@
+//! Deterministic rendering (`--deterministic`): scripts that remove sources of run-to-run
+//! pixel noise.
+//!
+//! The init function from `stabilize_init_fn!` runs in every document before the page's
+//! own scripts; the settle function from `settle_page_fn!` runs right before capture.
+//! Both are macros so the Playwright scripts can splice them in with `concat!`.
+
+/// Wall-clock time every `Date` reports: 2024-01-01T00:00:00Z.
+pub const FROZEN_TIME_MS: i64 = 1_704_067_200_000;
+
+/// Seed for the `Math.random` replacement.
+pub const RANDOM_SEED: u32 = 0x5eed;
+
+/// Names of the stabilizations applied, as recorded in compare output.
+pub const STABILIZATIONS: &[&str] = &[
+    "animations",
+    "transitions",
+    "caret",
+    "media",
+    "clock",
+    "random",
+    "fonts",
+];
+
+/// JS arrow function installed before navigation: fixed clock, seeded RNG, and a
+/// stylesheet disabling animations, transitions and the text caret. The literals match
+/// [`FROZEN_TIME_MS`] and [`RANDOM_SEED`].
+macro_rules! stabilize_init_fn {
+    () => {
+        r#"() => {
+  const frozen = 1704067200000;
+  let seed = 0x5eed;
+  Math.random = () => {
+    seed = (seed + 0x6d2b79f5) | 0;
+    let t = Math.imul(seed ^ (seed >>> 15), 1 | seed);
+    t = (t + Math.imul(t ^ (t >>> 7), 61 | t)) ^ t;
+    return ((t ^ (t >>> 14)) >>> 0) / 4294967296;
+  };
+  const RealDate = Date;
+  class FrozenDate extends RealDate {
+    constructor(...args) {
+      if (args.length === 0) super(frozen);
+      else super(...args);
+    }
+    static now() {
+      return frozen;
+    }
+  }
+  globalThis.Date = FrozenDate;
+
+  const css = '*, *::before, *::after { animation: none !important; transition: none !important; caret-color: transparent !important; scroll-behavior: auto !important; }';
+  const inject = () => {
+    const style = document.createElement('style');
+    style.textContent = css;
+    (document.head || document.documentElement).appendChild(style);
+  };
+  if (document.documentElement) inject();
+  else document.addEventListener('DOMContentLoaded', inject, { once: true });
+}"#
+    };
+}
+pub(crate) use stabilize_init_fn;
+
+/// JS async arrow function run just before capture: pause and rewind media, finish any
+/// remaining Web Animations, and wait for web fonts.
+macro_rules! settle_page_fn {
+    () => {
+        r#"async () => {
+  for (const media of document.querySelectorAll('video, audio')) {
+    media.pause();
+    try {
+      media.currentTime = 0;
+    } catch (err) {}
+  }
+  for (const animation of document.getAnimations()) {
+    try {
+      animation.finish();
+    } catch (err) {
+      animation.cancel();
+    }
+  }
+  await document.fonts.ready;
+}"#
+    };
+}
+pub(crate) use settle_page_fn;
+
+/// `stabilize_init_fn!` as a string, for the CDP backend.
+#[cfg_attr(not(feature = "playwright"), allow(dead_code))]
+pub(crate) const STABILIZE_INIT_FN: &str = stabilize_init_fn!();
+
+/// `settle_page_fn!` as a string, for the CDP backend.
+#[cfg_attr(not(feature = "playwright"), allow(dead_code))]
+pub(crate) const SETTLE_PAGE_FN: &str = settle_page_fn!();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn init_script_uses_the_recorded_clock_and_seed() {
+        assert!(STABILIZE_INIT_FN.contains(&format!("const frozen = {FROZEN_TIME_MS};")));
+        assert!(STABILIZE_INIT_FN.contains(&format!("let seed = {RANDOM_SEED:#x};")));
+        assert!(SETTLE_PAGE_FN.contains("document.fonts.ready"));
+    }
+}
@@ -0,0 +1,114 @@
+//! Interactive states (`--states`): hover, keyboard focus or press the captured element
+//! before the screenshot and DOM capture, so each state can be compared on its own.
+//!
+//! The states are reached with real input rather than by forcing pseudo-classes, so
+//! `:hover`, `:focus-visible` and `:active` rules, and any script listening for the same
+//! events, behave as they would for a user. Every state needs a capture selector.
+
+use serde::{Deserialize, Serialize};
+
+/// State an element is captured in.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default, Serialize, Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum InteractiveState {
+    /// Untouched: no pointer over the element and nothing focused.
+    #[default]
+    Rest,
+    /// Pointer over the element's center.
+    Hover,
+    /// Focused after a key press, so `:focus-visible` matches as it would for keyboard users.
+    Focus,
+    /// Pointer pressed (and held) on the element's center.
+    Active,
+}
+
+impl InteractiveState {
+    /// Every state, in capture order.
+    pub const ALL: [InteractiveState; 4] = [
+        InteractiveState::Rest,
+        InteractiveState::Hover,
+        InteractiveState::Focus,
+        InteractiveState::Active,
+    ];
+
+    /// Name used in scripts, labels and output (`rest`, `hover`, `focus`, `active`).
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InteractiveState::Rest => "rest",
+            InteractiveState::Hover => "hover",
+            InteractiveState::Focus => "focus",
+            InteractiveState::Active => "active",
+        }
+    }
+}
+
+impl std::fmt::Display for InteractiveState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// JS `async function applyState(page, scope, state)` for the Playwright scripts: puts the
+/// `scope` element handle into `state` (one of [`InteractiveState::as_str`], or empty for
+/// none), then gives transitions started by the change up to 1s to finish.
+macro_rules! apply_state_fn {
+    () => {
+        r#"
+async function applyState(page, scope, state) {
+  if (!state || state === 'rest') return;
+  if (!scope) throw new Error('The ' + state + ' state needs a capture selector');
+  if (state === 'hover') {
+    await scope.hover();
+  } else if (state === 'focus') {
+    await page.keyboard.press('Shift');
+    await scope.focus();
+  } else if (state === 'active') {
+    await scope.scrollIntoViewIfNeeded();
+    const box = await scope.boundingBox();
+    if (!box) throw new Error('The element to press has no layout box');
+    await page.mouse.move(box.x + box.width / 2, box.y + box.height / 2);
+    await page.mouse.down();
+  } else {
+    throw new Error('Unknown interactive state ' + state);
+  }
+  await scope.evaluate(settleStateFn);
+}
+"#
+    };
+}
+pub(crate) use apply_state_fn;
+
+/// JS async function run on the element after its state changes: waits up to 1s for the
+/// animations and transitions on it and its descendants to finish.
+macro_rules! settle_state_fn {
+    () => {
+        r#"async (el) => {
+  const running = el.getAnimations({ subtree: true }).map((a) => a.finished.catch(() => {}));
+  await Promise.race([Promise.all(running), new Promise((resolve) => setTimeout(resolve, 1000))]);
+}"#
+    };
+}
+pub(crate) use settle_state_fn;
+
+/// `settle_state_fn!` as a string, for the CDP backend.
+#[cfg_attr(not(feature = "playwright"), allow(dead_code))]
+pub(crate) const SETTLE_STATE_FN: &str = settle_state_fn!();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn states_round_trip_by_name() {
+        for state in InteractiveState::ALL {
+            let json = serde_json::to_string(&state).unwrap();
+            assert_eq!(json, format!("\"{state}\""));
+            let parsed: InteractiveState = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, state);
+        }
+        assert!(apply_state_fn!().contains("'focus'"));
+        assert!(SETTLE_STATE_FN.contains("getAnimations"));
+    }
+}
@@ -0,0 +1,284 @@
+//! Loopback HTTP server for local HTML inputs.
+//!
+//! A local `.html` file or a directory with an `index.html` is served from its directory on
+//! an ephemeral `127.0.0.1` port and rendered like any other URL, so relative stylesheets,
+//! scripts, images and fonts resolve the way they would when hosted. Only `GET` and `HEAD`
+//! are answered, and nothing outside the served directory is reachable.
+
+use crate::{DpcError, Result};
+use std::net::SocketAddr;
+use std::path::{Component, Path, PathBuf};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Request heads larger than this are rejected.
+const MAX_REQUEST_HEAD: usize = 16 * 1024;
+
+/// A running server; it stops when dropped.
+#[derive(Debug)]
+pub struct StaticServer {
+    addr: SocketAddr,
+    entry: String,
+    task: JoinHandle<()>,
+}
+
+impl StaticServer {
+    /// Serve `path`: an HTML file (its directory becomes the root) or a directory whose
+    /// `index.html` is the entry page.
+    pub async fn start(path: &Path) -> Result<Self> {
+        let path = path
+            .canonicalize()
+            .map_err(|err| DpcError::Config(format!("Cannot serve {}: {err}", path.display())))?;
+        let (root, entry) = if path.is_dir() {
+            (path.clone(), String::new())
+        } else {
+            let root = path.parent().map(Path::to_path_buf).unwrap_or_default();
+            let name = path
+                .file_name()
+                .map(|n| n.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            (root, encode_path_segment(&name))
+        };
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let addr = listener.local_addr()?;
+        let task = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let root = root.clone();
+                tokio::spawn(async move {
+                    // A client hanging up mid-response is not worth reporting.
+                    let _ = handle_connection(stream, &root).await;
+                });
+            }
+        });
+
+        Ok(Self { addr, entry, task })
+    }
+
+    /// URL of the entry page, e.g. `http://127.0.0.1:49152/index.html`.
+    pub fn url(&self) -> String {
+        format!("http://{}/{}", self.addr, self.entry)
+    }
+}
+
+impl Drop for StaticServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn handle_connection(mut stream: TcpStream, root: &Path) -> std::io::Result<()> {
+    let mut head = Vec::new();
+    let mut buf = [0u8; 2048];
+    while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+        let read = stream.read(&mut buf).await?;
+        if read == 0 {
+            return Ok(());
+        }
+        head.extend_from_slice(&buf[..read]);
+        if head.len() > MAX_REQUEST_HEAD {
+            return respond(
+                &mut stream,
+                "431 Request Header Fields Too Large",
+                None,
+                false,
+            )
+            .await;
+        }
+    }
+
+    let head = String::from_utf8_lossy(&head);
+    let mut request_line = head.lines().next().unwrap_or_default().split_whitespace();
+    let method = request_line.next().unwrap_or_default();
+    let target = request_line.next().unwrap_or("/");
+    let is_head = method == "HEAD";
+    if method != "GET" && !is_head {
+        return respond(&mut stream, "405 Method Not Allowed", None, false).await;
+    }
+
+    match resolve_request_path(root, target) {
+        Some(file) => match tokio::fs::read(&file).await {
+            Ok(body) => {
+                let file = (content_type(&file), body);
+                respond(&mut stream, "200 OK", Some(file), is_head).await
+            }
+            Err(_) => respond(&mut stream, "404 Not Found", None, is_head).await,
+        },
+        None => respond(&mut stream, "404 Not Found", None, is_head).await,
+    }
+}
+
+async fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    file: Option<(&str, Vec<u8>)>,
+    head_only: bool,
+) -> std::io::Result<()> {
+    let (content_type, body) = file.unwrap_or(("text/plain; charset=utf-8", status.into()));
+    let header = format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nCache-Control: no-store\r\nConnection: close\r\n\r\n",
+        body.len()
+    );
+    stream.write_all(header.as_bytes()).await?;
+    if !head_only {
+        stream.write_all(&body).await?;
+    }
+    stream.shutdown().await
+}
+
+/// Map a request target onto a file under `root`: the query and fragment are dropped,
+/// segments are percent-decoded, and directories resolve to their `index.html`. Targets
+/// that would leave `root` (`..`, absolute or drive-prefixed segments) resolve to nothing.
+fn resolve_request_path(root: &Path, target: &str) -> Option<PathBuf> {
+    let path = target.split(['?', '#']).next().unwrap_or_default();
+    let mut file = root.to_path_buf();
+    for segment in path.split('/').filter(|s| !s.is_empty()) {
+        let decoded = percent_decode(segment)?;
+        let mut components = Path::new(&decoded).components();
+        match (components.next(), components.next()) {
+            (Some(Component::Normal(part)), None) => file.push(part),
+            (Some(Component::CurDir), None) => {}
+            _ => return None,
+        }
+    }
+    if file.is_dir() {
+        file.push("index.html");
+    }
+    file.is_file().then_some(file)
+}
+
+fn percent_decode(segment: &str) -> Option<String> {
+    let bytes = segment.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            out.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            out.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(out).ok()
+}
+
+fn encode_path_segment(segment: &str) -> String {
+    segment
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{b:02X}"),
+        })
+        .collect()
+}
+
+fn content_type(path: &Path) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    match extension.as_str() {
+        "html" | "htm" => "text/html; charset=utf-8",
+        "css" => "text/css; charset=utf-8",
+        "js" | "mjs" => "text/javascript; charset=utf-8",
+        "json" | "map" => "application/json",
+        "svg" => "image/svg+xml",
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "webp" => "image/webp",
+        "avif" => "image/avif",
+        "ico" => "image/x-icon",
+        "woff" => "font/woff",
+        "woff2" => "font/woff2",
+        "ttf" => "font/ttf",
+        "otf" => "font/otf",
+        "txt" => "text/plain; charset=utf-8",
+        "xml" => "application/xml",
+        "wasm" => "application/wasm",
+        "mp4" => "video/mp4",
+        "webm" => "video/webm",
+        _ => "application/octet-stream",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    async fn fetch(url: &str) -> (u16, String, String) {
+        let response = reqwest::get(url).await.expect("request");
+        let status = response.status().as_u16();
+        let content_type = response
+            .headers()
+            .get("content-type")
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        (status, content_type, response.text().await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn serves_the_entry_page_and_relative_assets() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("index.html"),
+            "<link href=\"css/site.css\">",
+        )
+        .unwrap();
+        fs::create_dir(dir.path().join("css")).unwrap();
+        fs::write(dir.path().join("css/site.css"), "body { margin: 0 }").unwrap();
+        fs::write(dir.path().join("my page.html"), "<p>hi</p>").unwrap();
+
+        let server = StaticServer::start(dir.path()).await.unwrap();
+        assert!(server.url().starts_with("http://127.0.0.1:"));
+        let (status, content_type, body) = fetch(&server.url()).await;
+        assert_eq!(status, 200);
+        assert!(content_type.starts_with("text/html"));
+        assert!(body.contains("css/site.css"));
+
+        let css = format!("{}css/site.css?v=2", server.url());
+        let (status, content_type, _) = fetch(&css).await;
+        assert_eq!(
+            (status, content_type.as_str()),
+            (200, "text/css; charset=utf-8")
+        );
+
+        let page = StaticServer::start(&dir.path().join("my page.html"))
+            .await
+            .unwrap();
+        assert!(page.url().ends_with("/my%20page.html"));
+        assert_eq!(fetch(&page.url()).await.2, "<p>hi</p>");
+        let (status, _, _) = fetch(&format!("{}missing.png", server.url())).await;
+        assert_eq!(status, 404);
+    }
+
+    #[test]
+    fn request_paths_stay_inside_the_root() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().join("site");
+        fs::create_dir(&root).unwrap();
+        fs::write(root.join("index.html"), "").unwrap();
+        fs::write(dir.path().join("secret.txt"), "").unwrap();
+
+        assert_eq!(
+            resolve_request_path(&root, "/"),
+            Some(root.join("index.html"))
+        );
+        assert_eq!(
+            resolve_request_path(&root, "/./index.html#top"),
+            Some(root.join("index.html"))
+        );
+        assert!(resolve_request_path(&root, "/../secret.txt").is_none());
+        assert!(resolve_request_path(&root, "/%2E%2E/secret.txt").is_none());
+        assert!(resolve_request_path(&root, "/..%2Fsecret.txt").is_none());
+        assert!(resolve_request_path(&root, "/%2Fetc%2Fpasswd").is_none());
+    }
+}
@@ -0,0 +1,190 @@
+//! Declarative interactions run after navigation and before capture.
+//!
+//! Steps are written as single-key maps, e.g. in YAML:
+//!
+//! ```yaml
+//! - click: "#menu-button"
+//! - wait_for_selector: ".menu[open]"
+//! - fill: { selector: "#email", value: "env:TEST_EMAIL" }
+//! - press: Enter
+//! - wait_ms: 300
+//! - scroll_to: "#footer"
+//! ```
+
+use super::auth::resolve_secret;
+use crate::{DpcError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fmt;
+use std::path::Path;
+
+/// One pre-capture interaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InteractionStep {
+    /// Click the first element matching the selector.
+    Click(String),
+    /// Move the mouse over the first element matching the selector.
+    Hover(String),
+    /// Replace the value of an input; `value` may be `env:NAME`.
+    Fill { selector: String, value: String },
+    /// Press a key (Playwright key name, e.g. `Enter`, `Escape`) on the focused element.
+    Press(String),
+    /// Wait until the selector matches a visible element.
+    WaitForSelector(String),
+    /// Sleep for a fixed number of milliseconds.
+    WaitMs(u64),
+    /// Scroll the first element matching the selector into view.
+    ScrollTo(String),
+}
+
+impl fmt::Display for InteractionStep {
+    /// Short description for errors and logs; fill values are omitted.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Click(selector) => write!(f, "click {selector}"),
+            Self::Hover(selector) => write!(f, "hover {selector}"),
+            Self::Fill { selector, .. } => write!(f, "fill {selector}"),
+            Self::Press(key) => write!(f, "press {key}"),
+            Self::WaitForSelector(selector) => write!(f, "wait_for_selector {selector}"),
+            Self::WaitMs(ms) => write!(f, "wait_ms {ms}"),
+            Self::ScrollTo(selector) => write!(f, "scroll_to {selector}"),
+        }
+    }
+}
+
+impl InteractionStep {
+    /// Resolve `env:` fill values.
+    pub fn resolve_secrets(self) -> Result<Self> {
+        match self {
+            Self::Fill { selector, value } => {
+                let value = resolve_secret(&value, &format!("fill {selector}"))?;
+                Ok(Self::Fill { selector, value })
+            }
+            other => Ok(other),
+        }
+    }
+
+    /// Flat `{ action, selector, value, key, ms }` object consumed by the capture scripts.
+    pub(crate) fn to_script_value(&self) -> Value {
+        match self {
+            Self::Click(selector) => json!({ "action": "click", "selector": selector }),
+            Self::Hover(selector) => json!({ "action": "hover", "selector": selector }),
+            Self::Fill { selector, value } => {
+                json!({ "action": "fill", "selector": selector, "value": value })
+            }
+            Self::Press(key) => json!({ "action": "press", "key": key }),
+            Self::WaitForSelector(selector) => {
+                json!({ "action": "wait_for_selector", "selector": selector })
+            }
+            Self::WaitMs(ms) => json!({ "action": "wait_ms", "ms": ms }),
+            Self::ScrollTo(selector) => json!({ "action": "scroll_to", "selector": selector }),
+        }
+    }
+}
+
+/// Load a step list from a YAML or JSON file.
+pub fn load_steps(path: &Path) -> Result<Vec<InteractionStep>> {
+    let data = std::fs::read_to_string(path).map_err(|err| {
+        DpcError::Config(format!(
+            "Failed to read steps file {}: {err}",
+            path.display()
+        ))
+    })?;
+    parse_yaml_steps(&data).map_err(|err| {
+        DpcError::Config(format!(
+            "Invalid steps file {} (expected a list like `- click: \"#menu\"`): {err}",
+            path.display()
+        ))
+    })
+}
+
+/// serde_yaml writes enums as `!tag` values by default; steps use single-key maps instead,
+/// matching the TOML form.
+fn parse_yaml_steps(data: &str) -> std::result::Result<Vec<InteractionStep>, serde_yaml::Error> {
+    serde_yaml::with::singleton_map_recursive::deserialize(serde_yaml::Deserializer::from_str(data))
+}
+
+/// Script form of a step list.
+pub(crate) fn steps_script_value(steps: &[InteractionStep]) -> Value {
+    Value::Array(steps.iter().map(InteractionStep::to_script_value).collect())
+}
+
+/// Error for the step at `index` (0-based, as reported by the scripts).
+pub(crate) fn step_error(
+    steps: &[InteractionStep],
+    index: usize,
+    url: &str,
+    message: String,
+) -> DpcError {
+    DpcError::InteractionStep {
+        index: index + 1,
+        step: steps
+            .get(index)
+            .map(ToString::to_string)
+            .unwrap_or_else(|| "unknown step".to_string()),
+        url: url.to_string(),
+        message,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_yaml_and_toml_step_lists() {
+        let yaml = parse_yaml_steps(
+            r##"
+- click: "#menu"
+- fill: { selector: "#email", value: "a@example.com" }
+- press: Enter
+- wait_ms: 250
+"##,
+        )
+        .unwrap();
+        assert_eq!(yaml[0], InteractionStep::Click("#menu".to_string()));
+        assert_eq!(yaml[2], InteractionStep::Press("Enter".to_string()));
+        assert_eq!(yaml[3], InteractionStep::WaitMs(250));
+
+        #[derive(Deserialize)]
+        struct Wrapper {
+            steps: Vec<InteractionStep>,
+        }
+        let toml: Wrapper = toml::from_str(
+            r##"
+[[steps]]
+hover = ".card"
+
+[[steps]]
+wait_for_selector = ".card .tooltip"
+"##,
+        )
+        .unwrap();
+        assert_eq!(toml.steps[0], InteractionStep::Hover(".card".to_string()));
+        assert_eq!(
+            toml.steps[1].to_script_value(),
+            json!({ "action": "wait_for_selector", "selector": ".card .tooltip" })
+        );
+    }
+
+    #[test]
+    fn step_errors_name_the_step_without_fill_values() {
+        let steps = vec![
+            InteractionStep::Click("#open".to_string()),
+            InteractionStep::Fill {
+                selector: "#password".to_string(),
+                value: "hunter2".to_string(),
+            },
+        ];
+
+        let err = step_error(&steps, 1, "https://app", "Timeout 30000ms".to_string());
+        let text = err.to_string();
+        assert!(text.contains("step 2 (fill #password)"), "{text}");
+        assert!(!text.contains("hunter2"));
+        assert_eq!(
+            err.to_payload().category,
+            crate::error::ErrorCategory::Interaction
+        );
+    }
+}
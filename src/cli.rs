@@ -1,5 +1,5 @@
-use clap::{Parser, Subcommand, ValueEnum};
-use dpc_lib::Viewport;
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use dpc_lib::{Viewport, DEFAULT_BASELINE_DIR};
 use std::path::PathBuf;
 
 #[derive(Parser)]
@@ -7,7 +7,7 @@ use std::path::PathBuf;
 #[command(
     version,
     about = "Design Parity Checker - Compare implementations against design references",
-    long_about = "Design Parity Checker (DPC)\n\nModes:\n- compare: measure similarity between a reference (Figma/URL/image) and an implementation (Figma/URL/image).\n- generate-code: create HTML/Tailwind from a single input via a screenshot-to-code backend (or mock).\n- quality: experimental reference-free scoring.\n\nUse --help on any subcommand for details."
+    long_about = "Design Parity Checker (DPC)\n\nModes:\n- compare: measure similarity between a reference (Figma/URL/image) and an implementation (Figma/URL/image).\n- generate-code: create HTML/Tailwind from a single input via a screenshot-to-code backend (or mock).\n- quality: experimental reference-free scoring.\n- batch: run many compares from a manifest with one aggregated report.\n- baseline: record, compare against, and approve stored snapshots for visual regression.\n- report: render a saved JSON result as an interactive HTML report.\n\nUse --help on any subcommand for details."
 )]
 #[command(propagate_version = true)]
 pub struct Cli {
@@ -24,18 +24,217 @@ pub struct Cli {
         help = "Optional config file (TOML) to set defaults for viewport/threshold/weights/timeouts; CLI flags override config"
     )]
     pub config: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Stabilize URL renders: disable animations/transitions, hide the caret, pause media, freeze the clock and Math.random, and wait for web fonts"
+    )]
+    pub deterministic: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "ENGINE",
+        help = "Browser engine for URL renders; overrides config [browser] engine (default: chromium)"
+    )]
+    pub browser: Option<BrowserEngine>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        value_name = "MODE",
+        help = "Disk cache for Figma node data and exports: off, read (reuse exports of the file's current version), refresh (always refetch), or offline (cache only, no token needed); overrides config [figma] cache"
+    )]
+    pub figma_cache: Option<FigmaCacheMode>,
+
+    #[command(flatten)]
+    pub auth: AuthArgs,
+
+    #[command(flatten)]
+    pub har: HarArgs,
+
+    #[command(flatten)]
+    pub device: DeviceArgs,
+
+    #[command(flatten)]
+    pub capture: CaptureArgs,
+}
+
+/// Credentials for URL renders; each flag overrides the matching config `[auth]` key.
+#[derive(Args, Debug, Clone, Default)]
+pub struct AuthArgs {
+    #[arg(
+        long = "header",
+        global = true,
+        value_name = "NAME: VALUE",
+        help = "Extra HTTP header for URL renders (repeatable); the value may be env:VAR to read it from the environment"
+    )]
+    pub headers: Vec<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Cookies to set before navigation (Netscape cookies.txt or JSON export)"
+    )]
+    pub cookies: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "USER:PASSWORD",
+        help = "HTTP basic auth for URL renders; the password may be env:VAR"
+    )]
+    pub basic_auth: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Playwright storageState JSON (cookies + localStorage) to start each page from"
+    )]
+    pub storage_state: Option<PathBuf>,
+}
+
+/// Network recording and offline replay for URL renders.
+#[derive(Args, Debug, Clone, Default)]
+pub struct HarArgs {
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        conflicts_with = "har_replay",
+        help = "Record the network traffic of every URL render to a HAR file (replaced; all renders of the run end up in it)"
+    )]
+    pub har_record: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PATH",
+        help = "Serve URL renders from a HAR recorded with --har-record instead of the network"
+    )]
+    pub har_replay: Option<PathBuf>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value_t = HarUnmatched::Fail,
+        requires = "har_replay",
+        help = "Requests missing from the --har-replay file: fail the render, or stub them with an empty 404"
+    )]
+    pub har_unmatched: HarUnmatched,
+}
+
+/// Device emulation for URL renders; each flag overrides the matching config `[device]` key.
+#[derive(Args, Debug, Clone, Default)]
+pub struct DeviceArgs {
+    #[arg(
+        long,
+        global = true,
+        value_name = "NAME",
+        help = "Emulate a device preset (iphone-se, iphone-14, iphone-14-pro-max, pixel-7, ipad, desktop, desktop-hd, desktop-retina); its viewport applies unless --viewport is given"
+    )]
+    pub device: Option<String>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "FACTOR",
+        help = "Device pixel ratio for URL renders; screenshots are taken in device pixels, DOM boxes stay in CSS pixels"
+    )]
+    pub device_scale_factor: Option<f64>,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Emulate a mobile browser (meta viewport and mobile layout rules)"
+    )]
+    pub mobile: bool,
+
+    #[arg(long, global = true, help = "Emulate a touch screen")]
+    pub touch: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "UA",
+        help = "User agent for URL renders"
+    )]
+    pub user_agent: Option<String>,
+
+    #[arg(long, global = true, value_enum, help = "Emulate prefers-color-scheme")]
+    pub color_scheme: Option<ColorScheme>,
+
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        help = "Emulate prefers-reduced-motion"
+    )]
+    pub reduced_motion: Option<ReducedMotion>,
+}
+
+/// Capture extent for URL renders; each flag overrides the matching config `[capture]` key.
+#[derive(Args, Debug, Clone, Default)]
+pub struct CaptureArgs {
+    #[arg(
+        long,
+        global = true,
+        help = "Capture the screenshot and DOM of the whole document height, not just the viewport"
+    )]
+    pub full_page: bool,
+
+    #[arg(
+        long,
+        global = true,
+        help = "Scroll to the bottom and back before capture so lazy images and sections load"
+    )]
+    pub auto_scroll: bool,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PX",
+        value_parser = clap::value_parser!(u32).range(1..),
+        help = "Pixels per auto-scroll step (default 800; implies --auto-scroll)"
+    )]
+    pub scroll_step: Option<u32>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "PX",
+        help = "Stop auto-scrolling this far down the page (default 20000; implies --auto-scroll)"
+    )]
+    pub scroll_max_height: Option<u32>,
+
+    #[arg(
+        long,
+        global = true,
+        value_name = "MS",
+        help = "Pause after each auto-scroll step, in milliseconds (default 250; implies --auto-scroll)"
+    )]
+    pub scroll_settle_ms: Option<u64>,
 }
 
 #[derive(Subcommand)]
 pub enum Commands {
     /// Compare a reference design against an implementation
     Compare {
-        #[arg(long, help = "Reference resource (Figma URL, web URL, or local image)")]
+        #[arg(
+            long,
+            help = "Reference resource (Figma URL, web URL, local image, or saved view bundle)"
+        )]
         r#ref: String,
 
         #[arg(
             long,
-            help = "Implementation resource (Figma URL, web URL, or local image)"
+            help = "Implementation resource (Figma URL, web URL, local image, or saved view bundle)"
         )]
         r#impl: String,
 
@@ -52,6 +251,24 @@ pub enum Commands {
         )]
         viewport: Viewport,
 
+        #[arg(
+            long,
+            value_delimiter = ',',
+            conflicts_with = "viewport",
+            value_name = "WxH,...",
+            help = "Compare at several viewports in one run (e.g., 375x812,768x1024,1440x900); overrides config [[breakpoints]]"
+        )]
+        viewports: Option<Vec<Viewport>>,
+
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            value_name = "ENGINE,...",
+            help = "Render URL inputs in several engines and compare once per engine (e.g., chromium,firefox,webkit); overrides --browser"
+        )]
+        browsers: Option<Vec<BrowserEngine>>,
+
         #[arg(
             long,
             default_value = "0.95",
@@ -78,6 +295,44 @@ pub enum Commands {
         )]
         ignore_regions: Option<PathBuf>,
 
+        #[arg(
+            long,
+            value_name = "SELECTOR",
+            help = "Capture only the first element matching this CSS selector on a URL reference (screenshot clipped, DOM boxes relative to the element)"
+        )]
+        ref_selector: Option<String>,
+
+        #[arg(
+            long,
+            value_name = "SELECTOR",
+            help = "Capture only the first element matching this CSS selector on a URL implementation, e.g. to compare one component against a Figma frame"
+        )]
+        impl_selector: Option<String>,
+
+        #[arg(
+            long,
+            value_enum,
+            value_delimiter = ',',
+            value_name = "STATE,...",
+            help = "Compare the --ref-selector/--impl-selector element once per state (rest,hover,focus,active); overrides config [[states]]"
+        )]
+        states: Option<Vec<InteractiveState>>,
+
+        #[arg(
+            long = "state-node",
+            value_name = "STATE=NODE_ID",
+            requires = "states",
+            help = "Figma node-id of the reference variant for one state (repeatable), e.g. hover=12-40"
+        )]
+        state_nodes: Vec<String>,
+
+        #[arg(
+            long,
+            value_name = "FILE",
+            help = "YAML/JSON list of steps (click, hover, fill, press, wait_for_selector, wait_ms, scroll_to) run on URL inputs before capture; replaces config steps"
+        )]
+        steps: Option<PathBuf>,
+
         #[arg(long, value_enum, default_value = "json", help = "Output format")]
         format: OutputFormat,
 
@@ -186,6 +441,276 @@ pub enum Commands {
         #[arg(long, value_enum, default_value = "json", help = "Output format")]
         format: OutputFormat,
     },
+
+    /// Run many ref/impl comparisons from a YAML/TOML manifest
+    Batch {
+        #[arg(
+            long,
+            value_name = "PATH",
+            help = "Manifest (YAML or TOML) listing named entries with ref/impl and optional viewport, metrics, ignore_regions, threshold"
+        )]
+        manifest: PathBuf,
+
+        #[arg(
+            long,
+            help = "Maximum concurrent renders (overrides manifest `concurrency`; default 4)"
+        )]
+        concurrency: Option<usize>,
+
+        #[arg(long, value_enum, default_value = "json", help = "Output format")]
+        format: OutputFormat,
+
+        #[arg(long, short, help = "Output file path (stdout if omitted)")]
+        output: Option<PathBuf>,
+
+        #[arg(
+            long,
+            help = "Keep intermediate artifacts for every entry; otherwise cleaned up"
+        )]
+        keep_artifacts: bool,
+
+        #[arg(
+            long,
+            help = "Directory to store artifacts, one subdirectory per entry (implies --keep-artifacts)",
+            value_name = "PATH"
+        )]
+        artifacts_dir: Option<PathBuf>,
+    },
+
+    /// Record, compare against, and approve stored baseline snapshots
+    Baseline {
+        #[command(subcommand)]
+        command: BaselineCommand,
+    },
+
+    /// Save Figma nodes for offline, token-free compares
+    Figma {
+        #[command(subcommand)]
+        command: FigmaCommand,
+    },
+
+    /// Render a saved JSON result as a self-contained HTML report
+    Report {
+        #[arg(
+            value_name = "RESULT",
+            help = "JSON output from compare, batch, or baseline compare"
+        )]
+        input: PathBuf,
+
+        #[arg(long, short, help = "Output HTML path (stdout if omitted)")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FigmaCommand {
+    /// Write a node's JSON and PNG export to a directory usable as a `figma-export` input
+    Pull {
+        #[arg(
+            value_name = "URL",
+            help = "Figma file URL with the node-id of the frame to save"
+        )]
+        url: String,
+
+        #[arg(
+            long,
+            value_name = "DIR",
+            help = "Export directory for nodes.json, frame.png and source.json (default: .dpc/figma/<FILE_KEY>/<NODE_ID>)"
+        )]
+        out_dir: Option<PathBuf>,
+
+        #[arg(long, default_value = "1", help = "PNG export scale (0.01-4)")]
+        scale: f32,
+
+        #[arg(long, value_enum, default_value = "json", help = "Output format")]
+        format: OutputFormat,
+
+        #[arg(long, short, help = "Output file path (stdout if omitted)")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BaselineCommand {
+    /// Capture a resource and store it as the scenario's baseline
+    Record {
+        #[arg(long, help = "Scenario name the baseline is stored under")]
+        scenario: String,
+
+        #[arg(
+            long,
+            help = "Resource to capture (web URL, Figma URL, or local image)"
+        )]
+        input: String,
+
+        #[arg(long, value_enum, help = "Override type detection for input")]
+        input_type: Option<ResourceType>,
+
+        #[arg(
+            long,
+            default_value = "1440x900",
+            help = "Viewport dimensions (WIDTHxHEIGHT)"
+        )]
+        viewport: Viewport,
+
+        #[arg(
+            long,
+            default_value = DEFAULT_BASELINE_DIR,
+            value_name = "PATH",
+            help = "Baseline store directory"
+        )]
+        baseline_dir: PathBuf,
+
+        #[arg(long, value_enum, default_value = "json", help = "Output format")]
+        format: OutputFormat,
+
+        #[arg(long, short, help = "Output file path (stdout if omitted)")]
+        output: Option<PathBuf>,
+    },
+
+    /// Compare an implementation against the stored baseline (no re-render of the baseline)
+    Compare {
+        #[arg(long, help = "Scenario name to compare against")]
+        scenario: String,
+
+        #[arg(
+            long,
+            help = "Implementation resource (web URL, Figma URL, or local image)"
+        )]
+        r#impl: String,
+
+        #[arg(long, value_enum, help = "Override type detection for implementation")]
+        impl_type: Option<ResourceType>,
+
+        #[arg(
+            long,
+            default_value = "1440x900",
+            help = "Viewport dimensions (WIDTHxHEIGHT); selects the stored baseline"
+        )]
+        viewport: Viewport,
+
+        #[arg(
+            long,
+            default_value = "0.95",
+            help = "Similarity threshold for pass/fail (similarity >= threshold passes)"
+        )]
+        threshold: f64,
+
+        #[arg(
+            long,
+            value_delimiter = ',',
+            help = "Metrics to compute (pixel,layout,typography,color,content)"
+        )]
+        metrics: Option<Vec<String>>,
+
+        #[arg(
+            long,
+            help = "CSS selectors to ignore in DOM comparisons (comma-separated; supports #id, .class, tag)"
+        )]
+        ignore_selectors: Option<String>,
+
+        #[arg(
+            long,
+            help = "Path to JSON array of {x,y,width,height} regions to mask before metrics (values can be px or 0-1 normalized)"
+        )]
+        ignore_regions: Option<PathBuf>,
+
+        #[arg(
+            long,
+            default_value = DEFAULT_BASELINE_DIR,
+            value_name = "PATH",
+            help = "Baseline store directory"
+        )]
+        baseline_dir: PathBuf,
+
+        #[arg(long, value_enum, default_value = "json", help = "Output format")]
+        format: OutputFormat,
+
+        #[arg(long, short, help = "Output file path (stdout if omitted)")]
+        output: Option<PathBuf>,
+
+        #[arg(
+            long,
+            help = "Keep intermediate artifacts (screenshots, DOM snapshots); otherwise cleaned up"
+        )]
+        keep_artifacts: bool,
+
+        #[arg(
+            long,
+            help = "Directory to store artifacts (implies --keep-artifacts); created if missing",
+            value_name = "PATH"
+        )]
+        artifacts_dir: Option<PathBuf>,
+    },
+
+    /// Promote the last `baseline compare` capture to the new baseline
+    Approve {
+        #[arg(long, help = "Scenario name to approve")]
+        scenario: String,
+
+        #[arg(
+            long,
+            help = "Only approve this viewport (WIDTHxHEIGHT); defaults to every pending capture"
+        )]
+        viewport: Option<Viewport>,
+
+        #[arg(
+            long,
+            default_value = DEFAULT_BASELINE_DIR,
+            value_name = "PATH",
+            help = "Baseline store directory"
+        )]
+        baseline_dir: PathBuf,
+
+        #[arg(long, value_enum, default_value = "json", help = "Output format")]
+        format: OutputFormat,
+
+        #[arg(long, short, help = "Output file path (stdout if omitted)")]
+        output: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum BrowserEngine {
+    Chromium,
+    Firefox,
+    Webkit,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum InteractiveState {
+    Rest,
+    Hover,
+    Focus,
+    Active,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum FigmaCacheMode {
+    Off,
+    Read,
+    Refresh,
+    Offline,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, ValueEnum)]
+pub enum HarUnmatched {
+    #[default]
+    Fail,
+    Stub,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ColorScheme {
+    Light,
+    Dark,
+    NoPreference,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum ReducedMotion {
+    Reduce,
+    NoPreference,
 }
 
 #[derive(Clone, Copy, ValueEnum)]
@@ -193,13 +718,24 @@ pub enum ResourceType {
     Url,
     Image,
     Figma,
+    View,
+    Html,
+    FigmaExport,
 }
 
-#[derive(Clone, Copy, ValueEnum, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Default)]
 pub enum OutputFormat {
     #[default]
     Json,
     Pretty,
+    /// Self-contained HTML report with screenshots and diff overlays.
+    Html,
+    /// JUnit XML: one suite per compare, one testcase per metric.
+    Junit,
+    /// SARIF 2.1.0: one result per diff region or typography/color/content diff.
+    Sarif,
+    /// GitHub-flavoured Markdown summary for pull request comments.
+    Markdown,
 }
 
 pub fn parse() -> Cli {
@@ -208,7 +744,10 @@ pub fn parse() -> Cli {
 
 #[cfg(test)]
 mod tests {
-    use super::{Cli, Commands, OutputFormat, ResourceType};
+    use super::{
+        BaselineCommand, BrowserEngine, Cli, Commands, FigmaCacheMode, FigmaCommand,
+        InteractiveState, OutputFormat, ResourceType,
+    };
     use clap::Parser;
 
     #[test]
@@ -354,6 +893,292 @@ mod tests {
         }
     }
 
+    #[test]
+    fn compare_command_parses_viewports_list() {
+        let cli = Cli::parse_from([
+            "dpc",
+            "compare",
+            "--ref",
+            "ref.png",
+            "--impl",
+            "impl.png",
+            "--viewports",
+            "375x812,1440x900",
+        ]);
+
+        match cli.command {
+            Commands::Compare { viewports, .. } => {
+                let viewports = viewports.expect("viewports");
+                assert_eq!(viewports.len(), 2);
+                assert_eq!(viewports[0].width, 375);
+                assert_eq!(viewports[1].height, 900);
+            }
+            _ => panic!("expected compare command"),
+        }
+
+        let conflict = Cli::try_parse_from([
+            "dpc",
+            "compare",
+            "--ref",
+            "ref.png",
+            "--impl",
+            "impl.png",
+            "--viewport",
+            "800x600",
+            "--viewports",
+            "375x812",
+        ]);
+        assert!(conflict.is_err());
+    }
+
+    #[test]
+    fn browser_flags_parse_engines() {
+        let cli = Cli::parse_from([
+            "dpc",
+            "compare",
+            "--ref",
+            "https://example.com",
+            "--impl",
+            "http://localhost:3000",
+            "--browser",
+            "firefox",
+            "--browsers",
+            "chromium,webkit",
+        ]);
+        assert_eq!(cli.browser, Some(BrowserEngine::Firefox));
+        match cli.command {
+            Commands::Compare { browsers, .. } => assert_eq!(
+                browsers,
+                Some(vec![BrowserEngine::Chromium, BrowserEngine::Webkit])
+            ),
+            _ => panic!("expected compare command"),
+        }
+
+        let unknown = Cli::try_parse_from(["dpc", "--browser", "edge", "report", "result.json"]);
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn states_flags_parse_states_and_nodes() {
+        let cli = Cli::parse_from([
+            "dpc",
+            "compare",
+            "--ref",
+            "https://www.figma.com/file/abc/Design?node-id=1-2",
+            "--impl",
+            "http://localhost:3000",
+            "--impl-selector",
+            ".btn",
+            "--states",
+            "hover,focus",
+            "--state-node",
+            "hover=1-3",
+            "--state-node",
+            "focus=1-4",
+        ]);
+        match cli.command {
+            Commands::Compare {
+                states,
+                state_nodes,
+                ..
+            } => {
+                assert_eq!(
+                    states,
+                    Some(vec![InteractiveState::Hover, InteractiveState::Focus])
+                );
+                assert_eq!(state_nodes, vec!["hover=1-3", "focus=1-4"]);
+            }
+            _ => panic!("expected compare command"),
+        }
+
+        let orphan = Cli::try_parse_from([
+            "dpc",
+            "compare",
+            "--ref",
+            "a.png",
+            "--impl",
+            "b.png",
+            "--state-node",
+            "hover=1-3",
+        ]);
+        assert!(orphan.is_err());
+    }
+
+    #[test]
+    fn batch_command_parses_flags() {
+        let cli = Cli::parse_from([
+            "dpc",
+            "batch",
+            "--manifest",
+            "dpc-batch.yaml",
+            "--concurrency",
+            "8",
+            "--artifacts-dir",
+            "artifacts",
+        ]);
+
+        match cli.command {
+            Commands::Batch {
+                manifest,
+                concurrency,
+                format,
+                output,
+                keep_artifacts,
+                artifacts_dir,
+            } => {
+                assert_eq!(manifest, std::path::PathBuf::from("dpc-batch.yaml"));
+                assert_eq!(concurrency, Some(8));
+                assert!(matches!(format, OutputFormat::Json));
+                assert!(output.is_none());
+                assert!(!keep_artifacts);
+                assert_eq!(
+                    artifacts_dir.as_deref(),
+                    Some(std::path::Path::new("artifacts"))
+                );
+            }
+            _ => panic!("expected batch command"),
+        }
+    }
+
+    #[test]
+    fn figma_pull_parses_url_and_scale() {
+        let cli = Cli::parse_from([
+            "dpc",
+            "figma",
+            "pull",
+            "https://www.figma.com/design/FILE/Home?node-id=1-2",
+            "--scale",
+            "2",
+            "--figma-cache",
+            "refresh",
+        ]);
+        assert_eq!(cli.figma_cache, Some(FigmaCacheMode::Refresh));
+        match cli.command {
+            Commands::Figma {
+                command:
+                    FigmaCommand::Pull {
+                        url,
+                        out_dir,
+                        scale,
+                        ..
+                    },
+            } => {
+                assert!(url.ends_with("node-id=1-2"));
+                assert_eq!(out_dir, None);
+                assert_eq!(scale, 2.0);
+            }
+            _ => panic!("expected figma pull command"),
+        }
+    }
+
+    #[test]
+    fn baseline_subcommands_parse() {
+        let cli = Cli::parse_from([
+            "dpc",
+            "baseline",
+            "compare",
+            "--scenario",
+            "home",
+            "--impl",
+            "https://staging.example.com",
+            "--viewport",
+            "375x812",
+        ]);
+        match cli.command {
+            Commands::Baseline {
+                command:
+                    BaselineCommand::Compare {
+                        scenario,
+                        r#impl,
+                        viewport,
+                        baseline_dir,
+                        ..
+                    },
+            } => {
+                assert_eq!(scenario, "home");
+                assert_eq!(r#impl, "https://staging.example.com");
+                assert_eq!(viewport.width, 375);
+                assert_eq!(baseline_dir, std::path::PathBuf::from(".dpc/baselines"));
+            }
+            _ => panic!("expected baseline compare command"),
+        }
+
+        let cli = Cli::parse_from(["dpc", "baseline", "approve", "--scenario", "home"]);
+        match cli.command {
+            Commands::Baseline {
+                command: BaselineCommand::Approve { viewport, .. },
+            } => assert!(viewport.is_none()),
+            _ => panic!("expected baseline approve command"),
+        }
+    }
+
+    #[test]
+    fn report_formats_and_report_command_parse() {
+        let cli = Cli::parse_from([
+            "dpc", "compare", "--ref", "ref.png", "--impl", "impl.png", "--format", "html",
+        ]);
+        match cli.command {
+            Commands::Compare { format, .. } => assert!(matches!(format, OutputFormat::Html)),
+            _ => panic!("expected compare command"),
+        }
+
+        for (raw, expected) in [
+            ("junit", OutputFormat::Junit),
+            ("sarif", OutputFormat::Sarif),
+            ("markdown", OutputFormat::Markdown),
+        ] {
+            let cli =
+                Cli::parse_from(["dpc", "batch", "--manifest", "batch.yaml", "--format", raw]);
+            match cli.command {
+                Commands::Batch { format, .. } => assert_eq!(format, expected),
+                _ => panic!("expected batch command"),
+            }
+        }
+
+        let cli = Cli::parse_from(["dpc", "report", "result.json", "-o", "report.html"]);
+        match cli.command {
+            Commands::Report { input, output } => {
+                assert_eq!(input, std::path::PathBuf::from("result.json"));
+                assert_eq!(output, Some(std::path::PathBuf::from("report.html")));
+            }
+            _ => panic!("expected report command"),
+        }
+    }
+
+    #[test]
+    fn auth_flags_are_global_and_repeatable() {
+        let cli = Cli::parse_from([
+            "dpc",
+            "baseline",
+            "record",
+            "--scenario",
+            "home",
+            "--input",
+            "https://app.local",
+            "--header",
+            "Authorization: env:APP_TOKEN",
+            "--header",
+            "X-Env: staging",
+            "--basic-auth",
+            "admin:env:APP_PASSWORD",
+            "--storage-state",
+            "state.json",
+        ]);
+        assert_eq!(
+            cli.auth.headers,
+            vec!["Authorization: env:APP_TOKEN", "X-Env: staging"]
+        );
+        assert_eq!(
+            cli.auth.basic_auth.as_deref(),
+            Some("admin:env:APP_PASSWORD")
+        );
+        assert_eq!(
+            cli.auth.storage_state,
+            Some(std::path::PathBuf::from("state.json"))
+        );
+        assert!(cli.auth.cookies.is_none());
+    }
+
     #[test]
     fn quality_command_sets_verbose() {
         let cli = Cli::parse_from([
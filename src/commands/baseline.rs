@@ -0,0 +1,441 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dpc_lib::output::DPC_OUTPUT_VERSION;
+use dpc_lib::types::ResourceKind;
+use dpc_lib::{
+    parse_resource, BaselineAction, BaselineEntryOutput, BaselineMetadata, BaselineOutput,
+    BaselineRender, BaselineStore, Config, DpcError, DpcOutput, ParsedResource, ProgressCallback,
+    ResourceDescriptor, Viewport,
+};
+
+use crate::cli::{
+    AuthArgs, BaselineCommand, BrowserEngine, CaptureArgs, DeviceArgs, FigmaCacheMode, HarArgs,
+    OutputFormat,
+};
+use crate::commands::compare::{
+    compare_views, parse_metric_kinds, rendering_info, resource_kind_from_cli, CompareJob,
+};
+use crate::formatting::{exit_code_for_compare, render_error, write_output};
+use crate::pipeline::{
+    browser_manager, load_ignore_regions, parse_ignore_selectors, resolve_artifacts_dir,
+    resource_error, resource_to_normalized_view,
+};
+use crate::settings::{
+    load_config, resolve_compare_settings, with_browser_arg, with_capture_args, with_device_args,
+    with_figma_cache_arg, CompareFlagSources,
+};
+
+/// Run a `baseline` subcommand.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_baseline(
+    raw_args: &[String],
+    config_path: Option<PathBuf>,
+    verbose: bool,
+    auth: &AuthArgs,
+    deterministic: bool,
+    har: &HarArgs,
+    device: &DeviceArgs,
+    browser: Option<BrowserEngine>,
+    figma_cache: Option<FigmaCacheMode>,
+    capture: &CaptureArgs,
+    command: BaselineCommand,
+) -> ExitCode {
+    match command {
+        BaselineCommand::Record {
+            scenario,
+            input,
+            input_type,
+            viewport,
+            baseline_dir,
+            format,
+            output,
+        } => {
+            let input_res = match parse_resource(&input, input_type.map(resource_kind_from_cli)) {
+                Ok(res) => res,
+                Err(err) => return render_error(DpcError::Config(err.to_string()), format, output),
+            };
+            let result = record(
+                raw_args,
+                config_path.as_deref(),
+                verbose,
+                auth,
+                deterministic,
+                har,
+                device,
+                browser,
+                figma_cache,
+                capture,
+                &scenario,
+                &input_res,
+                viewport,
+                &BaselineStore::new(baseline_dir),
+            )
+            .await;
+            finish(result, format, output)
+        }
+        BaselineCommand::Compare {
+            scenario,
+            r#impl,
+            impl_type,
+            viewport,
+            threshold,
+            metrics,
+            ignore_selectors,
+            ignore_regions,
+            baseline_dir,
+            format,
+            output,
+            keep_artifacts,
+            artifacts_dir,
+        } => {
+            let config = match load_config(config_path.as_deref())
+                .and_then(|cfg| with_device_args(cfg, device))
+                .map(|cfg| with_browser_arg(cfg, browser))
+                .map(|cfg| with_capture_args(cfg, capture))
+                .map(|cfg| with_figma_cache_arg(cfg, figma_cache))
+            {
+                Ok(cfg) => cfg,
+                Err(err) => return render_error(err, format, output),
+            };
+            let flag_sources = CompareFlagSources::from_args(raw_args);
+            let timeouts = &config.timeouts;
+            let resolved = resolve_compare_settings(
+                viewport,
+                threshold,
+                timeouts.navigation.as_secs(),
+                timeouts.network_idle.as_secs(),
+                timeouts.process.as_secs(),
+                &config,
+                &flag_sources,
+            );
+
+            let impl_res = match parse_resource(&r#impl, impl_type.map(resource_kind_from_cli)) {
+                Ok(res) => res,
+                Err(err) => return render_error(DpcError::Config(err.to_string()), format, output),
+            };
+            let selected_metrics = match parse_metric_kinds(metrics.as_deref()) {
+                Ok(k) => k,
+                Err(err) => return render_error(DpcError::Config(err.to_string()), format, output),
+            };
+            let ignore_regions = match ignore_regions {
+                Some(path) => match load_ignore_regions(&path) {
+                    Ok(regions) => regions,
+                    Err(err) => return render_error(err, format, output),
+                },
+                None => Vec::new(),
+            };
+
+            let store = BaselineStore::new(baseline_dir);
+            let (baseline_meta, baseline_view) = match store.load(&scenario, &resolved.viewport) {
+                Ok(loaded) => loaded,
+                Err(err) => return render_error(err, format, output),
+            };
+            let impl_render = render_settings(impl_res.kind, &config);
+            if let Err(err) = baseline_meta.check_render(impl_render.as_ref()) {
+                return render_error(err, format, output);
+            }
+            // `load` already validated the scenario, so the bundle path resolves.
+            let bundle_dir = store
+                .baseline_dir(&scenario, &resolved.viewport)
+                .unwrap_or_default();
+
+            let (artifacts_dir, artifacts_from_cli) =
+                resolve_artifacts_dir(artifacts_dir.as_deref());
+            if let Err(err) = std::fs::create_dir_all(&artifacts_dir) {
+                return render_error(DpcError::Io(err), format, output);
+            }
+            let should_keep_artifacts = keep_artifacts || artifacts_from_cli;
+            let browser = match browser_manager(
+                resolved.nav_timeout,
+                resolved.network_idle_timeout,
+                resolved.process_timeout,
+                1,
+                &config,
+                auth,
+                deterministic,
+                har,
+            ) {
+                Ok(browser) => browser,
+                Err(err) => return render_error(err, format, output),
+            };
+
+            let job = CompareJob {
+                ref_res: ParsedResource {
+                    kind: ResourceKind::View,
+                    value: bundle_dir.to_string_lossy().into_owned(),
+                    figma_info: None,
+                },
+                impl_res,
+                viewport: resolved.viewport,
+                threshold: resolved.threshold,
+                metrics: selected_metrics,
+                ignore_selectors: parse_ignore_selectors(ignore_selectors.as_deref()),
+                ignore_regions,
+                ref_selector: None,
+                impl_selector: None,
+                state: None,
+                figma: config.figma.clone(),
+                weights: resolved.weights,
+                artifacts_dir: artifacts_dir.clone(),
+                keep_artifacts: should_keep_artifacts,
+            };
+
+            if verbose {
+                eprintln!(
+                    "Comparing against baseline '{}' at {} (recorded from {})\u{2026}",
+                    scenario, resolved.viewport, baseline_meta.source.value
+                );
+                eprintln!(
+                    "Normalizing implementation ({:?})\u{2026}",
+                    job.impl_res.kind
+                );
+            }
+            let impl_view = match resource_to_normalized_view(
+                &job.impl_res,
+                &job.viewport,
+                &artifacts_dir,
+                "impl",
+                None,
+                None,
+                &browser,
+                &config.figma,
+                progress_logger(verbose),
+            )
+            .await
+            {
+                Ok(view) => view,
+                Err(err) => {
+                    return render_error(resource_error(err, "implementation"), format, output)
+                }
+            };
+
+            let pending_meta = BaselineMetadata {
+                scenario: scenario.clone(),
+                viewport: job.viewport,
+                source: ResourceDescriptor {
+                    kind: job.impl_res.kind,
+                    value: job.impl_res.value.clone(),
+                },
+                captured_at: unix_now(),
+                render: impl_render,
+            };
+            let pending_dir = match store.record_pending(&pending_meta, &impl_view) {
+                Ok(dir) => dir,
+                Err(err) => return render_error(err, format, output),
+            };
+            if verbose {
+                eprintln!(
+                    "Saved capture to {}; run `dpc baseline approve --scenario {}` to accept it",
+                    pending_dir.display(),
+                    scenario
+                );
+            }
+
+            let mut compare_output =
+                match compare_views(&job, &baseline_view, &impl_view, None, verbose).await {
+                    Ok(out) => out,
+                    Err(err) => return render_error(err, format, output),
+                };
+            compare_output.rendering = rendering_info(&job, &browser);
+
+            let passed = compare_output.passed;
+            let body = DpcOutput::Compare(compare_output);
+            if let Err(err) = write_output(&body, format, output.clone()) {
+                return render_error(DpcError::Config(err.to_string()), format, output);
+            }
+            if !should_keep_artifacts {
+                let _ = std::fs::remove_dir_all(&artifacts_dir);
+            }
+            exit_code_for_compare(passed)
+        }
+        BaselineCommand::Approve {
+            scenario,
+            viewport,
+            baseline_dir,
+            format,
+            output,
+        } => {
+            let result = approve(&scenario, viewport, &BaselineStore::new(baseline_dir));
+            finish(result, format, output)
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn record(
+    raw_args: &[String],
+    config_path: Option<&Path>,
+    verbose: bool,
+    auth: &AuthArgs,
+    deterministic: bool,
+    har: &HarArgs,
+    device: &DeviceArgs,
+    browser: Option<BrowserEngine>,
+    figma_cache: Option<FigmaCacheMode>,
+    capture: &CaptureArgs,
+    scenario: &str,
+    input_res: &ParsedResource,
+    viewport: Viewport,
+    store: &BaselineStore,
+) -> Result<BaselineOutput, DpcError> {
+    let config = with_figma_cache_arg(
+        with_capture_args(
+            with_browser_arg(
+                with_device_args(load_config(config_path)?, device)?,
+                browser,
+            ),
+            capture,
+        ),
+        figma_cache,
+    );
+    let flag_sources = CompareFlagSources::from_args(raw_args);
+    let timeouts = &config.timeouts;
+    let resolved = resolve_compare_settings(
+        viewport,
+        config.threshold,
+        timeouts.navigation.as_secs(),
+        timeouts.network_idle.as_secs(),
+        timeouts.process.as_secs(),
+        &config,
+        &flag_sources,
+    );
+    // Validate the scenario before paying for a render.
+    store.baseline_dir(scenario, &resolved.viewport)?;
+    let browser = browser_manager(
+        resolved.nav_timeout,
+        resolved.network_idle_timeout,
+        resolved.process_timeout,
+        1,
+        &config,
+        auth,
+        deterministic,
+        har,
+    )?;
+
+    let (artifacts_dir, _) = resolve_artifacts_dir(None);
+    std::fs::create_dir_all(&artifacts_dir)?;
+    if verbose {
+        eprintln!(
+            "Capturing {} ({:?}) at {} for baseline '{}'\u{2026}",
+            input_res.value, input_res.kind, resolved.viewport, scenario
+        );
+    }
+    let rendered = resource_to_normalized_view(
+        input_res,
+        &resolved.viewport,
+        &artifacts_dir,
+        "ref",
+        None,
+        None,
+        &browser,
+        &config.figma,
+        progress_logger(verbose),
+    )
+    .await
+    .map_err(|err| resource_error(err, "input"));
+
+    let source = ResourceDescriptor {
+        kind: input_res.kind,
+        value: input_res.value.clone(),
+    };
+    let stored = rendered.and_then(|view| {
+        let meta = BaselineMetadata {
+            scenario: scenario.to_string(),
+            viewport: resolved.viewport,
+            source: source.clone(),
+            captured_at: unix_now(),
+            render: render_settings(input_res.kind, &config),
+        };
+        store.record(&meta, &view)
+    });
+    let _ = std::fs::remove_dir_all(&artifacts_dir);
+    let path = stored?;
+
+    Ok(BaselineOutput {
+        version: DPC_OUTPUT_VERSION.to_string(),
+        action: BaselineAction::Record,
+        scenario: scenario.to_string(),
+        store: store.root().to_path_buf(),
+        entries: vec![BaselineEntryOutput {
+            viewport: resolved.viewport,
+            path,
+            source,
+        }],
+    })
+}
+
+fn approve(
+    scenario: &str,
+    viewport: Option<Viewport>,
+    store: &BaselineStore,
+) -> Result<BaselineOutput, DpcError> {
+    let viewports = match viewport {
+        Some(vp) => vec![vp],
+        None => store.pending_viewports(scenario)?,
+    };
+    if viewports.is_empty() {
+        return Err(DpcError::Config(format!(
+            "No pending captures for scenario '{scenario}'; run `dpc baseline compare` first"
+        )));
+    }
+
+    let mut entries = Vec::with_capacity(viewports.len());
+    for vp in viewports {
+        let path = store.approve(scenario, &vp)?;
+        let (meta, _) = store.load(scenario, &vp)?;
+        entries.push(BaselineEntryOutput {
+            viewport: vp,
+            path,
+            source: meta.source,
+        });
+    }
+
+    Ok(BaselineOutput {
+        version: DPC_OUTPUT_VERSION.to_string(),
+        action: BaselineAction::Approve,
+        scenario: scenario.to_string(),
+        store: store.root().to_path_buf(),
+        entries,
+    })
+}
+
+fn finish(
+    result: Result<BaselineOutput, DpcError>,
+    format: OutputFormat,
+    output: Option<PathBuf>,
+) -> ExitCode {
+    let body = match result {
+        Ok(out) => DpcOutput::Baseline(out),
+        Err(err) => return render_error(err, format, output),
+    };
+    if let Err(err) = write_output(&body, format, output.clone()) {
+        return render_error(DpcError::Config(err.to_string()), format, output);
+    }
+    ExitCode::SUCCESS
+}
+
+/// Engine and device of a capture of `kind`; `None` unless it renders in a browser.
+fn render_settings(kind: ResourceKind, config: &Config) -> Option<BaselineRender> {
+    matches!(kind, ResourceKind::Url | ResourceKind::Html).then(|| BaselineRender {
+        engine: config.browser.engine,
+        device: config.device.emulation(),
+    })
+}
+
+fn progress_logger(verbose: bool) -> Option<ProgressCallback> {
+    if verbose {
+        Some(Arc::new(|msg: &str| eprintln!("{msg}")))
+    } else {
+        None
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
@@ -0,0 +1,584 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use dpc_lib::config::deserialize_optional_viewport;
+use dpc_lib::output::DPC_OUTPUT_VERSION;
+use dpc_lib::types::ResourceKind;
+use dpc_lib::{
+    parse_resource, BatchEntryOutput, BatchOutput, BrowserManager, Config, DpcError, DpcOutput,
+    ProgressCallback, Viewport,
+};
+use futures::stream::{self, StreamExt};
+use serde::Deserialize;
+
+use crate::cli::{
+    AuthArgs, BrowserEngine, CaptureArgs, DeviceArgs, FigmaCacheMode, HarArgs, OutputFormat,
+};
+use crate::commands::compare::{
+    check_capture_selector, execute_compare, parse_metric_kinds, CompareJob,
+};
+use crate::formatting::{exit_code_for_batch, render_error, write_output};
+use crate::pipeline::{
+    artifact_subdir_name, browser_manager, load_ignore_regions, resolve_artifacts_dir, IgnoreRegion,
+};
+use crate::settings::{
+    load_config, resolve_compare_settings, with_browser_arg, with_capture_args, with_device_args,
+    with_figma_cache_arg, CompareFlagSources,
+};
+
+/// Concurrent renders when neither `--concurrency` nor the manifest sets a limit.
+const DEFAULT_BATCH_CONCURRENCY: usize = 4;
+
+/// Batch manifest: shared defaults plus a list of named ref/impl entries.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchManifest {
+    #[serde(default)]
+    concurrency: Option<usize>,
+    #[serde(default, deserialize_with = "deserialize_optional_viewport")]
+    viewport: Option<Viewport>,
+    #[serde(default)]
+    threshold: Option<f64>,
+    #[serde(default)]
+    metrics: Option<Vec<String>>,
+    #[serde(default)]
+    entries: Vec<BatchEntry>,
+}
+
+/// A single named comparison; unset fields fall back to the manifest, then the config.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BatchEntry {
+    name: String,
+    #[serde(rename = "ref")]
+    ref_resource: String,
+    #[serde(rename = "impl")]
+    impl_resource: String,
+    #[serde(default)]
+    ref_type: Option<ResourceKind>,
+    #[serde(default)]
+    impl_type: Option<ResourceKind>,
+    #[serde(default, deserialize_with = "deserialize_optional_viewport")]
+    viewport: Option<Viewport>,
+    #[serde(default)]
+    threshold: Option<f64>,
+    #[serde(default)]
+    metrics: Option<Vec<String>>,
+    #[serde(default)]
+    ignore_selectors: Vec<String>,
+    #[serde(default)]
+    ignore_regions: Option<IgnoreRegionsSpec>,
+    #[serde(default)]
+    ref_selector: Option<String>,
+    #[serde(default)]
+    impl_selector: Option<String>,
+}
+
+/// Ignore regions given either as a JSON file path or inline rectangles.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+enum IgnoreRegionsSpec {
+    Path(PathBuf),
+    Inline(Vec<IgnoreRegion>),
+}
+
+/// Run the batch command.
+#[allow(clippy::too_many_arguments)]
+pub async fn run_batch(
+    config_path: Option<PathBuf>,
+    verbose: bool,
+    auth: &AuthArgs,
+    deterministic: bool,
+    har: &HarArgs,
+    device: &DeviceArgs,
+    browser: Option<BrowserEngine>,
+    figma_cache: Option<FigmaCacheMode>,
+    capture: &CaptureArgs,
+    manifest_path: PathBuf,
+    concurrency: Option<usize>,
+    format: OutputFormat,
+    output: Option<PathBuf>,
+    keep_artifacts: bool,
+    artifacts_dir: Option<PathBuf>,
+) -> ExitCode {
+    let config = match load_config(config_path.as_deref())
+        .and_then(|cfg| with_device_args(cfg, device))
+        .map(|cfg| with_browser_arg(cfg, browser))
+        .map(|cfg| with_capture_args(cfg, capture))
+        .map(|cfg| with_figma_cache_arg(cfg, figma_cache))
+    {
+        Ok(cfg) => cfg,
+        Err(err) => return render_error(err, format, output.clone()),
+    };
+    let manifest = match load_manifest(&manifest_path) {
+        Ok(manifest) => manifest,
+        Err(err) => return render_error(err, format, output.clone()),
+    };
+    let concurrency = concurrency
+        .or(manifest.concurrency)
+        .unwrap_or(DEFAULT_BATCH_CONCURRENCY);
+    if concurrency == 0 {
+        return render_error(
+            DpcError::Config("Batch concurrency must be at least 1".to_string()),
+            format,
+            output.clone(),
+        );
+    }
+
+    let (artifacts_dir, artifacts_from_cli) = resolve_artifacts_dir(artifacts_dir.as_deref());
+    let should_keep_artifacts = keep_artifacts || artifacts_from_cli;
+    let manifest_dir = manifest_path
+        .parent()
+        .map(Path::to_path_buf)
+        .unwrap_or_default();
+
+    // Resolve every entry up front so manifest mistakes fail before any rendering.
+    let jobs = match resolve_jobs(
+        &manifest,
+        &manifest_dir,
+        &config,
+        &artifacts_dir,
+        should_keep_artifacts,
+    ) {
+        Ok(jobs) => jobs,
+        Err(err) => return render_error(err, format, output.clone()),
+    };
+    if let Err(err) = std::fs::create_dir_all(&artifacts_dir) {
+        return render_error(DpcError::Io(err), format, output.clone());
+    }
+
+    let settings = resolve_compare_settings(
+        config.viewport,
+        config.threshold,
+        0,
+        0,
+        0,
+        &config,
+        &CompareFlagSources::default(),
+    );
+    let browser = match browser_manager(
+        settings.nav_timeout,
+        settings.network_idle_timeout,
+        settings.process_timeout,
+        concurrency,
+        &config,
+        auth,
+        deterministic,
+        har,
+    ) {
+        Ok(browser) => browser,
+        Err(err) => return render_error(err, format, output),
+    };
+    if verbose && !browser.options().auth.is_empty() {
+        eprintln!("Auth: {}", browser.options().auth.describe());
+    }
+    if verbose {
+        eprintln!(
+            "Running {} batch entries from {} (concurrency {}, artifacts: {})",
+            jobs.len(),
+            manifest_path.display(),
+            concurrency,
+            artifacts_dir.display()
+        );
+    }
+
+    let entries: Vec<BatchEntryOutput> = stream::iter(jobs)
+        .map(|(name, job)| run_entry(name, job, &browser, verbose))
+        .buffered(concurrency)
+        .collect()
+        .await;
+
+    let total = entries.len();
+    let error_count = entries.iter().filter(|e| e.error.is_some()).count();
+    let passed_count = entries.iter().filter(|e| e.passed).count();
+    let failed_count = total - passed_count - error_count;
+    let body = BatchOutput {
+        version: DPC_OUTPUT_VERSION.to_string(),
+        manifest: manifest_path,
+        passed: passed_count == total,
+        total,
+        passed_count,
+        failed_count,
+        error_count,
+        entries,
+    };
+    let exit_code = exit_code_for_batch(&body);
+
+    if should_keep_artifacts || verbose {
+        eprintln!(
+            "Artifacts directory: {} (kept: {})",
+            artifacts_dir.display(),
+            should_keep_artifacts
+        );
+    }
+
+    if let Err(err) = write_output(&DpcOutput::Batch(body), format, output.clone()) {
+        return render_error(DpcError::Config(err.to_string()), format, output);
+    }
+
+    if !should_keep_artifacts {
+        let _ = std::fs::remove_dir_all(&artifacts_dir);
+    }
+
+    exit_code
+}
+
+async fn run_entry(
+    name: String,
+    job: CompareJob,
+    browser: &BrowserManager,
+    verbose: bool,
+) -> BatchEntryOutput {
+    let progress: Option<ProgressCallback> = if verbose {
+        let prefix = name.clone();
+        Some(Arc::new(move |msg: &str| eprintln!("[{prefix}] {msg}")))
+    } else {
+        None
+    };
+    if verbose {
+        eprintln!("[{name}] Starting compare\u{2026}");
+    }
+
+    let result = match std::fs::create_dir_all(&job.artifacts_dir) {
+        Ok(()) => execute_compare(&job, browser, None, progress, false).await,
+        Err(err) => Err(DpcError::Io(err)),
+    };
+
+    match result {
+        Ok(out) => {
+            if verbose {
+                eprintln!(
+                    "[{name}] {} ({:.1}% similarity)",
+                    if out.passed { "passed" } else { "failed" },
+                    out.similarity * 100.0
+                );
+            }
+            BatchEntryOutput {
+                name,
+                passed: out.passed,
+                result: Some(out),
+                error: None,
+            }
+        }
+        Err(err) => {
+            if verbose {
+                eprintln!("[{name}] error: {err}");
+            }
+            BatchEntryOutput {
+                name,
+                passed: false,
+                result: None,
+                error: Some(err.to_payload()),
+            }
+        }
+    }
+}
+
+/// Read a manifest, picking TOML or YAML (which also accepts JSON) by extension.
+fn load_manifest(path: &Path) -> Result<BatchManifest, DpcError> {
+    let data = std::fs::read_to_string(path).map_err(|e| {
+        DpcError::Config(format!(
+            "Failed to read batch manifest {}: {e}",
+            path.display()
+        ))
+    })?;
+    let is_toml = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("toml"));
+    let manifest: BatchManifest = if is_toml {
+        toml::from_str(&data).map_err(|e| e.to_string())
+    } else {
+        serde_yaml::from_str(&data).map_err(|e| e.to_string())
+    }
+    .map_err(|e| DpcError::Config(format!("Invalid batch manifest {}: {e}", path.display())))?;
+
+    if manifest.entries.is_empty() {
+        return Err(DpcError::Config(format!(
+            "Batch manifest {} contains no entries",
+            path.display()
+        )));
+    }
+    Ok(manifest)
+}
+
+fn resolve_jobs(
+    manifest: &BatchManifest,
+    manifest_dir: &Path,
+    config: &Config,
+    artifacts_dir: &Path,
+    keep_artifacts: bool,
+) -> Result<Vec<(String, CompareJob)>, DpcError> {
+    let settings = resolve_compare_settings(
+        config.viewport,
+        config.threshold,
+        0,
+        0,
+        0,
+        config,
+        &CompareFlagSources::default(),
+    );
+    let mut seen_dirs: HashMap<String, &str> = HashMap::new();
+    let mut jobs = Vec::with_capacity(manifest.entries.len());
+
+    for entry in &manifest.entries {
+        let name = entry.name.trim();
+        if name.is_empty() {
+            return Err(DpcError::Config(
+                "Batch entry is missing a name".to_string(),
+            ));
+        }
+        let entry_err = |msg: String| DpcError::Config(format!("Batch entry '{name}': {msg}"));
+
+        let dir_name = artifact_subdir_name(name);
+        if let Some(other) = seen_dirs.insert(dir_name.clone(), name) {
+            return Err(entry_err(if other == name {
+                "duplicate name (entry names must be unique)".to_string()
+            } else {
+                format!(
+                    "maps to the same artifacts directory '{dir_name}' as entry '{other}' (rename one of them)"
+                )
+            }));
+        }
+
+        let ref_value = resolve_local_path(&entry.ref_resource, manifest_dir);
+        let ref_res =
+            parse_resource(&ref_value, entry.ref_type).map_err(|e| entry_err(e.to_string()))?;
+        let impl_value = resolve_local_path(&entry.impl_resource, manifest_dir);
+        let impl_res =
+            parse_resource(&impl_value, entry.impl_type).map_err(|e| entry_err(e.to_string()))?;
+        check_capture_selector(&ref_res, entry.ref_selector.as_deref(), "ref_selector")
+            .and_then(|()| {
+                check_capture_selector(&impl_res, entry.impl_selector.as_deref(), "impl_selector")
+            })
+            .map_err(|e| entry_err(e.to_string()))?;
+
+        let threshold = entry
+            .threshold
+            .or(manifest.threshold)
+            .unwrap_or(settings.threshold);
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(entry_err(format!(
+                "threshold must be between 0 and 1 (got {threshold})"
+            )));
+        }
+
+        let metrics = parse_metric_kinds(
+            entry
+                .metrics
+                .as_ref()
+                .or(manifest.metrics.as_ref())
+                .map(Vec::as_slice),
+        )
+        .map_err(|e| entry_err(e.to_string()))?;
+
+        let ignore_regions = match &entry.ignore_regions {
+            Some(IgnoreRegionsSpec::Path(path)) => load_ignore_regions(&manifest_dir.join(path))
+                .map_err(|e| entry_err(e.to_string()))?,
+            Some(IgnoreRegionsSpec::Inline(regions)) => regions.clone(),
+            None => Vec::new(),
+        };
+
+        jobs.push((
+            name.to_string(),
+            CompareJob {
+                ref_res,
+                impl_res,
+                viewport: entry
+                    .viewport
+                    .or(manifest.viewport)
+                    .unwrap_or(settings.viewport),
+                threshold,
+                metrics,
+                ignore_selectors: entry.ignore_selectors.clone(),
+                ignore_regions,
+                ref_selector: entry.ref_selector.clone(),
+                impl_selector: entry.impl_selector.clone(),
+                state: None,
+                figma: config.figma.clone(),
+                weights: settings.weights,
+                artifacts_dir: artifacts_dir.join(dir_name),
+                keep_artifacts,
+            },
+        ));
+    }
+
+    Ok(jobs)
+}
+
+/// Resolve relative local paths against the manifest's directory; URLs pass through.
+fn resolve_local_path(value: &str, manifest_dir: &Path) -> String {
+    if value.contains("://") || Path::new(value).is_absolute() {
+        return value.to_string();
+    }
+    manifest_dir.join(value).to_string_lossy().into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dpc_lib::MetricKind;
+    use tempfile::TempDir;
+
+    fn write_png(path: &Path) {
+        image::RgbaImage::from_pixel(4, 4, image::Rgba([0, 0, 0, 255]))
+            .save(path)
+            .expect("write png");
+    }
+
+    #[test]
+    fn load_manifest_parses_yaml_and_toml() {
+        let dir = TempDir::new().expect("tempdir");
+        let yaml = dir.path().join("batch.yaml");
+        std::fs::write(
+            &yaml,
+            r#"
+concurrency: 2
+viewport: 800x600
+entries:
+  - name: home
+    ref: ref.png
+    impl: https://example.com
+    threshold: 0.9
+    metrics: [pixel]
+    ignore_regions:
+      - {x: 0, y: 0, w: 1, h: 0.1}
+"#,
+        )
+        .expect("write yaml");
+        let manifest = load_manifest(&yaml).expect("yaml manifest");
+        assert_eq!(manifest.concurrency, Some(2));
+        assert_eq!(manifest.viewport.map(|v| v.width), Some(800));
+        assert_eq!(manifest.entries[0].ref_resource, "ref.png");
+        assert!(matches!(
+            manifest.entries[0].ignore_regions,
+            Some(IgnoreRegionsSpec::Inline(ref regions)) if regions.len() == 1
+        ));
+
+        let toml_path = dir.path().join("batch.toml");
+        std::fs::write(
+            &toml_path,
+            r#"
+threshold = 0.8
+
+[[entries]]
+name = "checkout"
+ref = "ref.png"
+impl = "impl.png"
+viewport = { width = 375, height = 812 }
+ignore_regions = "regions.json"
+"#,
+        )
+        .expect("write toml");
+        let manifest = load_manifest(&toml_path).expect("toml manifest");
+        assert_eq!(manifest.threshold, Some(0.8));
+        assert_eq!(manifest.entries[0].viewport.map(|v| v.height), Some(812));
+        assert!(matches!(
+            manifest.entries[0].ignore_regions,
+            Some(IgnoreRegionsSpec::Path(_))
+        ));
+    }
+
+    #[test]
+    fn load_manifest_rejects_empty_and_unknown_fields() {
+        let dir = TempDir::new().expect("tempdir");
+        let empty = dir.path().join("empty.yaml");
+        std::fs::write(&empty, "entries: []\n").expect("write");
+        let err = load_manifest(&empty).unwrap_err().to_string();
+        assert!(err.contains("no entries"), "got {err}");
+
+        let typo = dir.path().join("typo.yaml");
+        std::fs::write(
+            &typo,
+            "entries:\n  - name: a\n    ref: a.png\n    impl: b.png\n    treshold: 0.5\n",
+        )
+        .expect("write");
+        assert!(load_manifest(&typo).is_err());
+    }
+
+    #[test]
+    fn resolve_jobs_applies_defaults_and_relative_paths() {
+        let dir = TempDir::new().expect("tempdir");
+        write_png(&dir.path().join("ref.png"));
+        write_png(&dir.path().join("impl.png"));
+        let manifest: BatchManifest = serde_yaml::from_str(
+            r#"
+threshold: 0.8
+metrics: [pixel, color]
+entries:
+  - name: home page
+    ref: ref.png
+    impl: impl.png
+  - name: mobile
+    ref: ref.png
+    impl: impl.png
+    viewport: 375x812
+    threshold: 0.5
+    metrics: [pixel]
+"#,
+        )
+        .expect("manifest");
+        let artifacts = dir.path().join("artifacts");
+
+        let jobs = resolve_jobs(&manifest, dir.path(), &Config::default(), &artifacts, false)
+            .expect("jobs");
+
+        assert_eq!(jobs.len(), 2);
+        let (name, home) = &jobs[0];
+        assert_eq!(name, "home page");
+        assert_eq!(home.viewport, Config::default().viewport);
+        assert!((home.threshold - 0.8).abs() < f64::EPSILON);
+        assert_eq!(home.metrics, vec![MetricKind::Pixel, MetricKind::Color]);
+        assert_eq!(home.artifacts_dir, artifacts.join("home_page"));
+        assert_eq!(
+            PathBuf::from(&home.ref_res.value),
+            dir.path().join("ref.png")
+        );
+
+        let (_, mobile) = &jobs[1];
+        assert_eq!(mobile.viewport.width, 375);
+        assert!((mobile.threshold - 0.5).abs() < f64::EPSILON);
+        assert_eq!(mobile.metrics, vec![MetricKind::Pixel]);
+    }
+
+    #[test]
+    fn resolve_jobs_rejects_duplicate_names() {
+        let dir = TempDir::new().expect("tempdir");
+        write_png(&dir.path().join("ref.png"));
+        let manifest: BatchManifest = serde_yaml::from_str(
+            r#"
+entries:
+  - {name: a, ref: ref.png, impl: ref.png}
+  - {name: a, ref: ref.png, impl: ref.png}
+"#,
+        )
+        .expect("manifest");
+
+        let err = resolve_jobs(&manifest, dir.path(), &Config::default(), dir.path(), false)
+            .err()
+            .expect("duplicate names should fail")
+            .to_string();
+        assert!(err.contains("duplicate"), "got {err}");
+
+        let manifest: BatchManifest = serde_yaml::from_str(
+            r#"
+entries:
+  - {name: home/a, ref: ref.png, impl: ref.png}
+  - {name: home_a, ref: ref.png, impl: ref.png}
+"#,
+        )
+        .expect("manifest");
+        let err = resolve_jobs(&manifest, dir.path(), &Config::default(), dir.path(), false)
+            .err()
+            .expect("colliding artifact dirs should fail")
+            .to_string();
+        assert!(
+            err.contains(
+                "'home_a': maps to the same artifacts directory 'home_a' as entry 'home/a'"
+            ),
+            "got {err}"
+        );
+    }
+}
@@ -2,23 +2,32 @@ use std::path::PathBuf;
 use std::process::ExitCode;
 use std::sync::Arc;
 
+use dpc_lib::browser::load_steps;
+use dpc_lib::config::SemanticConfig;
 use dpc_lib::output::DPC_OUTPUT_VERSION;
 use dpc_lib::types::ResourceKind;
 use dpc_lib::{
-    calculate_combined_score, default_metrics, parse_resource, run_metrics, CompareOutput,
-    DpcError, DpcOutput, MetricKind, ResourceDescriptor, SemanticAnalyzer, Viewport,
+    calculate_combined_score, default_metrics, parse_resource, run_metrics, Breakpoint,
+    BreakpointCompareOutput, BrowserEngine, BrowserManager, CompareOutput, DpcError, DpcOutput,
+    FigmaConfig, InteractiveState, MetricKind, NormalizedView, ParsedResource, PixelMetric,
+    ProgressCallback, RenderingInfo, ResourceDescriptor, ResponsiveCompareOutput, ScoreWeights,
+    SemanticAnalyzer, StateTarget, Viewport,
 };
 
-use crate::cli::OutputFormat;
+use crate::cli::{
+    AuthArgs, BrowserEngine as CliBrowserEngine, CaptureArgs, DeviceArgs, FigmaCacheMode, HarArgs,
+    InteractiveState as CliInteractiveState, OutputFormat,
+};
 use crate::formatting::{exit_code_for_compare, render_error, write_output};
 use crate::pipeline::{
-    apply_dom_ignores, apply_ignore_regions, generate_summary, load_ignore_regions,
-    parse_ignore_selectors, persist_compare_artifacts, resolve_artifacts_dir,
-    resource_to_normalized_view,
+    apply_dom_ignores, apply_ignore_regions, artifact_subdir_name, browser_manager,
+    generate_summary, load_ignore_regions, parse_ignore_selectors, persist_compare_artifacts,
+    resolve_artifacts_dir, resource_error, resource_to_normalized_view, IgnoreRegion,
 };
 use crate::settings::{
-    format_effective_config, load_config, log_effective_config, resolve_compare_settings,
-    CompareFlagSources,
+    format_effective_config, load_config, log_effective_config, resolve_breakpoints,
+    resolve_browsers, resolve_compare_settings, resolve_state_targets, with_browser_arg,
+    with_capture_args, with_device_args, with_figma_cache_arg, CompareFlagSources,
 };
 
 /// Run the compare command.
@@ -27,11 +36,20 @@ pub async fn run_compare(
     raw_args: &[String],
     config_path: Option<PathBuf>,
     verbose: bool,
+    auth: &AuthArgs,
+    deterministic: bool,
+    har: &HarArgs,
+    device: &DeviceArgs,
+    browser: Option<CliBrowserEngine>,
+    figma_cache: Option<FigmaCacheMode>,
+    capture: &CaptureArgs,
     r#ref: String,
     r#impl: String,
     ref_type: Option<crate::cli::ResourceType>,
     impl_type: Option<crate::cli::ResourceType>,
     viewport: Viewport,
+    viewports: Option<Vec<Viewport>>,
+    browsers: Option<Vec<CliBrowserEngine>>,
     threshold: f64,
     metrics: Option<Vec<String>>,
     format: OutputFormat,
@@ -39,6 +57,11 @@ pub async fn run_compare(
     keep_artifacts: bool,
     ignore_selectors: Option<String>,
     ignore_regions: Option<PathBuf>,
+    ref_selector: Option<String>,
+    impl_selector: Option<String>,
+    states: Option<Vec<CliInteractiveState>>,
+    state_nodes: Vec<String>,
+    steps: Option<PathBuf>,
     artifacts_dir: Option<PathBuf>,
     nav_timeout: u64,
     network_idle_timeout: u64,
@@ -46,10 +69,21 @@ pub async fn run_compare(
     semantic_analysis: bool,
     context: Option<String>,
 ) -> ExitCode {
-    let config = match load_config(config_path.as_deref()) {
+    let mut config = match load_config(config_path.as_deref())
+        .and_then(|cfg| with_device_args(cfg, device))
+        .map(|cfg| with_browser_arg(cfg, browser))
+        .map(|cfg| with_capture_args(cfg, capture))
+        .map(|cfg| with_figma_cache_arg(cfg, figma_cache))
+    {
         Ok(cfg) => cfg,
         Err(err) => return render_error(err, format, output.clone()),
     };
+    if let Some(path) = &steps {
+        match load_steps(path) {
+            Ok(steps) => config.steps = steps,
+            Err(err) => return render_error(err, format, output.clone()),
+        }
+    }
     let config_source = config_path.as_deref();
     let flag_sources = CompareFlagSources::from_args(raw_args);
     let resolved = resolve_compare_settings(
@@ -104,6 +138,15 @@ pub async fn run_compare(
         Err(err) => return render_error(DpcError::Config(err.to_string()), format, output.clone()),
     };
 
+    for (res, selector, flag) in [
+        (&ref_res, ref_selector.as_deref(), "--ref-selector"),
+        (&impl_res, impl_selector.as_deref(), "--impl-selector"),
+    ] {
+        if let Err(err) = check_capture_selector(res, selector, flag) {
+            return render_error(err, format, output.clone());
+        }
+    }
+
     let selected_metrics = match parse_metric_kinds(metrics.as_deref()) {
         Ok(k) => k,
         Err(err) => return render_error(DpcError::Config(err.to_string()), format, output.clone()),
@@ -123,197 +166,146 @@ pub async fn run_compare(
         return render_error(DpcError::Io(err), format, output.clone());
     }
     let should_keep_artifacts = keep_artifacts || artifacts_from_cli;
-    let progress_logger: Option<Arc<dyn Fn(&str) + Send + Sync>> = if verbose {
+    let progress_logger: Option<ProgressCallback> = if verbose {
         Some(Arc::new(|msg: &str| eprintln!("{msg}")))
     } else {
         None
     };
-
-    // Convert resources to NormalizedViews
-    if verbose {
-        eprintln!("Normalizing reference ({:?})\u{2026}", ref_res.kind);
-    }
-    let ref_view_raw = match resource_to_normalized_view(
-        &ref_res,
-        &viewport,
-        &artifacts_dir,
-        "ref",
-        progress_logger.clone(),
+    let browser = match browser_manager(
         nav_timeout,
         network_idle_timeout,
         process_timeout,
-    )
-    .await
-    {
-        Ok(view) => view,
-        Err(err) => {
-            return render_error(
-                DpcError::Config(format!("Failed to process reference: {}", err)),
-                format,
-                output.clone(),
-            )
-        }
+        1,
+        &config,
+        auth,
+        deterministic,
+        har,
+    ) {
+        Ok(browser) => browser,
+        Err(err) => return render_error(err, format, output),
     };
-
-    if verbose {
-        eprintln!("Normalizing implementation ({:?})\u{2026}", impl_res.kind);
+    if verbose && !browser.options().auth.is_empty() {
+        // Names and paths only; header values and passwords are never logged.
+        eprintln!("Auth: {}", browser.options().auth.describe());
+    }
+    if verbose && !browser.options().steps.is_empty() {
+        let steps: Vec<String> = browser
+            .options()
+            .steps
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        eprintln!("Steps: {}", steps.join(" → "));
     }
-    let impl_view_raw = match resource_to_normalized_view(
-        &impl_res,
-        &viewport,
-        &artifacts_dir,
-        "impl",
-        progress_logger.clone(),
-        nav_timeout,
-        network_idle_timeout,
-        process_timeout,
-    )
-    .await
-    {
-        Ok(view) => view,
-        Err(err) => {
-            return render_error(
-                DpcError::Config(format!("Failed to process implementation: {}", err)),
-                format,
-                output.clone(),
-            )
-        }
-    };
-
-    let ref_view = apply_dom_ignores(&ref_view_raw, &ignore_selectors);
-    let impl_view = apply_dom_ignores(&impl_view_raw, &ignore_selectors);
 
-    let ref_view = if ignore_regions.is_empty() {
-        ref_view
-    } else {
-        match apply_ignore_regions(&ref_view, &ignore_regions, &artifacts_dir, "ref") {
-            Ok(view) => view,
-            Err(err) => return render_error(err, format, output.clone()),
-        }
-    };
-    let impl_view = if ignore_regions.is_empty() {
-        impl_view
-    } else {
-        match apply_ignore_regions(&impl_view, &ignore_regions, &artifacts_dir, "impl") {
-            Ok(view) => view,
-            Err(err) => return render_error(err, format, output.clone()),
-        }
+    let job = CompareJob {
+        ref_res,
+        impl_res,
+        viewport,
+        threshold,
+        metrics: selected_metrics,
+        ignore_selectors,
+        ignore_regions,
+        ref_selector,
+        impl_selector,
+        state: None,
+        figma: config.figma.clone(),
+        weights: score_weights,
+        artifacts_dir: artifacts_dir.clone(),
+        keep_artifacts: should_keep_artifacts,
     };
+    let semantic = semantic_analysis.then_some(SemanticOptions {
+        config: &config.semantic,
+        context: context.as_deref(),
+    });
 
-    // Determine effective metrics based on input types
-    let effective_metrics =
-        if selected_metrics.is_empty() && ref_view.dom.is_none() && impl_view.dom.is_none() {
-            vec![MetricKind::Pixel, MetricKind::Color]
-        } else {
-            selected_metrics
+    let mut breakpoints =
+        match resolve_breakpoints(viewports.as_deref(), flag_sources.viewport, &config) {
+            Ok(breakpoints) => breakpoints,
+            Err(err) => return render_error(err, format, output.clone()),
         };
-
-    // Run metrics
-    if verbose {
-        eprintln!("Running metrics: {:?}", effective_metrics);
+    let engines = browsers
+        .as_deref()
+        .map(resolve_browsers)
+        .unwrap_or_default();
+    let state_targets = match resolve_state_targets(states.as_deref(), &state_nodes, &config)
+        .and_then(|targets| check_state_targets(&job, &targets).map(|()| targets))
+    {
+        Ok(targets) => targets,
+        Err(err) => return render_error(err, format, output.clone()),
+    };
+    if (!engines.is_empty() || !state_targets.is_empty()) && breakpoints.is_empty() {
+        breakpoints.push(Breakpoint::from_viewport(viewport));
     }
-    let all_metrics = default_metrics();
-    let mut metrics_scores =
-        match run_metrics(&all_metrics, &effective_metrics, &ref_view, &impl_view) {
-            Ok(scores) => scores,
-            Err(err) => {
-                return render_error(
-                    DpcError::Config(format!("Failed to compute metrics: {}", err)),
-                    format,
-                    output.clone(),
-                )
+    if !breakpoints.is_empty() {
+        if verbose {
+            let labels: Vec<String> = breakpoints.iter().map(Breakpoint::label).collect();
+            eprintln!("Comparing at breakpoints: {}", labels.join(", "));
+        }
+        if verbose && !engines.is_empty() {
+            let names: Vec<&str> = engines.iter().map(|engine| engine.as_str()).collect();
+            eprintln!("Comparing in browsers: {}", names.join(", "));
+        }
+        if verbose {
+            for target in &state_targets {
+                let states: Vec<&str> = target.states.iter().map(|s| s.as_str()).collect();
+                eprintln!(
+                    "Comparing {} in states: {}",
+                    target.label(),
+                    states.join(", ")
+                );
             }
+        }
+        let responsive = match execute_breakpoints(
+            &job,
+            &breakpoints,
+            &engines,
+            &state_targets,
+            &browser,
+            semantic,
+            progress_logger,
+            verbose,
+        )
+        .await
+        {
+            Ok(out) => out,
+            Err(err) => return render_error(err, format, output.clone()),
         };
+        if verbose || should_keep_artifacts {
+            eprintln!(
+                "Artifacts directory: {} (kept: {}; one subdirectory per breakpoint, browser and state)",
+                artifacts_dir.display(),
+                should_keep_artifacts
+            );
+        }
 
-    // Run semantic analysis if enabled and we have pixel diff regions
-    if semantic_analysis {
-        if let Some(ref mut pixel_metric) = metrics_scores.pixel {
-            if !pixel_metric.diff_regions.is_empty() {
-                if verbose {
-                    eprintln!("Running semantic analysis on {} diff regions...", pixel_metric.diff_regions.len());
-                }
-
-                if let Some(analyzer) = SemanticAnalyzer::from_config(&config.semantic) {
-                    // Use image-aware clustering to separate different UI components
-                    match analyzer
-                        .analyze_diff_regions(
-                            &ref_view.screenshot_path,
-                            &impl_view.screenshot_path,
-                            &pixel_metric.diff_regions,
-                            context.as_deref(),
-                        )
-                        .await
-                    {
-                        Ok(semantic_diffs) => {
-                            if verbose {
-                                eprintln!("Semantic analysis found {} diff types", semantic_diffs.len());
-                                for diff in &semantic_diffs {
-                                    eprintln!("  - {}: {}", diff.diff_type, diff.description);
-                                }
-                            }
-                            // Convert to the types expected by PixelMetric
-                            let typed_diffs: Vec<dpc_lib::types::SemanticDiff> = semantic_diffs
-                                .into_iter()
-                                .map(|d| dpc_lib::types::SemanticDiff {
-                                    x: d.x,
-                                    y: d.y,
-                                    width: d.width,
-                                    height: d.height,
-                                    severity: d.severity,
-                                    diff_type: match d.diff_type {
-                                        dpc_lib::SemanticDiffType::TextContent => dpc_lib::types::SemanticDiffType::TextContent,
-                                        dpc_lib::SemanticDiffType::TextReflow => dpc_lib::types::SemanticDiffType::TextReflow,
-                                        dpc_lib::SemanticDiffType::Typography => dpc_lib::types::SemanticDiffType::Typography,
-                                        dpc_lib::SemanticDiffType::Layout => dpc_lib::types::SemanticDiffType::Layout,
-                                        dpc_lib::SemanticDiffType::Color => dpc_lib::types::SemanticDiffType::Color,
-                                        dpc_lib::SemanticDiffType::MissingElement => dpc_lib::types::SemanticDiffType::MissingElement,
-                                        dpc_lib::SemanticDiffType::ExtraElement => dpc_lib::types::SemanticDiffType::ExtraElement,
-                                        dpc_lib::SemanticDiffType::Spacing => dpc_lib::types::SemanticDiffType::Spacing,
-                                        dpc_lib::SemanticDiffType::ImageChange => dpc_lib::types::SemanticDiffType::ImageChange,
-                                        dpc_lib::SemanticDiffType::Decoration => dpc_lib::types::SemanticDiffType::Decoration,
-                                        dpc_lib::SemanticDiffType::Other => dpc_lib::types::SemanticDiffType::Other,
-                                    },
-                                    description: d.description,
-                                    confidence: d.confidence,
-                                })
-                                .collect();
-                            pixel_metric.semantic_diffs = Some(typed_diffs);
-                        }
-                        Err(e) => {
-                            eprintln!("Warning: Semantic analysis failed: {}", e);
-                        }
-                    }
-                } else {
-                    eprintln!(
-                        "Warning: --semantic-analysis requires an API key. Set DPC_VISION_API_KEY, OPENAI_API_KEY, or add [semantic] api_key to config"
-                    );
-                }
-            }
+        let passed = responsive.passed;
+        let body = DpcOutput::ResponsiveCompare(responsive);
+        if let Err(err) = write_output(&body, format, output.clone()) {
+            return render_error(DpcError::Config(err.to_string()), format, output);
         }
+        if !should_keep_artifacts {
+            let _ = std::fs::remove_dir_all(&artifacts_dir);
+        }
+        return exit_code_for_compare(passed);
     }
 
-    // Calculate combined score
-    let similarity = calculate_combined_score(&metrics_scores, &score_weights);
-
-    // Determine pass/fail
-    let passed = similarity >= threshold as f32;
-
-    // Generate summary
-    let summary = generate_summary(&metrics_scores, similarity, threshold as f32);
-
-    let artifacts = match persist_compare_artifacts(
-        &artifacts_dir,
-        &ref_view,
-        &impl_view,
-        should_keep_artifacts,
-    ) {
-        Ok(paths) => Some(paths),
-        Err(err) => return render_error(err, format, output.clone()),
-    };
+    let compare_output =
+        match execute_compare(&job, &browser, semantic, progress_logger, verbose).await {
+            Ok(out) => out,
+            Err(err) => return render_error(err, format, output.clone()),
+        };
 
-    if let Some(paths) = &artifacts {
-        let should_log = matches!(format, OutputFormat::Json | OutputFormat::Pretty)
-            || verbose
+    if let Some(paths) = &compare_output.artifacts {
+        let should_log = matches!(
+            format,
+            OutputFormat::Json
+                | OutputFormat::Pretty
+                | OutputFormat::Html
+                | OutputFormat::Junit
+                | OutputFormat::Sarif
+                | OutputFormat::Markdown
+        ) || verbose
             || paths.kept
             || artifacts_from_cli;
         if should_log {
@@ -341,6 +333,13 @@ pub async fn run_compare(
                 if let Some(path) = &paths.impl_figma_snapshot {
                     eprintln!("  impl figma tree: {}", path.display());
                 }
+                if let (Some(ref_path), Some(impl_path)) = (&paths.ref_view, &paths.impl_view) {
+                    eprintln!(
+                        "  saved views: {} {} (replay with --ref-type view --impl-type view)",
+                        ref_path.display(),
+                        impl_path.display()
+                    );
+                }
                 if let Some(path) = &paths.diff_image {
                     eprintln!("  pixel diff: {}", path.display());
                 } else {
@@ -362,60 +361,581 @@ pub async fn run_compare(
                         "Artifacts will be cleaned up; pass --keep-artifacts or --artifacts-dir to retain."
                     );
                 }
-            } else if paths.kept {
-                if paths.diff_image.is_some()
+            } else if paths.kept
+                && (paths.diff_image.is_some()
                     || paths.ref_dom_snapshot.is_some()
-                    || paths.impl_dom_snapshot.is_some()
-                {
-                    eprintln!(
-                        "Hint: view diff heatmap or DOM snapshots in {}",
-                        paths.directory.display()
-                    );
-                }
+                    || paths.impl_dom_snapshot.is_some())
+            {
+                eprintln!(
+                    "Hint: view diff heatmap or DOM snapshots in {}",
+                    paths.directory.display()
+                );
             }
         }
     }
 
-    let body = DpcOutput::Compare(CompareOutput {
+    let passed = compare_output.passed;
+    let body = DpcOutput::Compare(compare_output);
+
+    if let Err(err) = write_output(&body, format, output.clone()) {
+        return render_error(DpcError::Config(err.to_string()), format, output);
+    }
+
+    // Cleanup artifacts unless --keep-artifacts is set
+    if !should_keep_artifacts {
+        let _ = std::fs::remove_dir_all(&artifacts_dir);
+    }
+
+    exit_code_for_compare(passed)
+}
+
+/// A fully resolved ref/impl comparison, shared by `compare` and `batch`.
+#[derive(Clone)]
+pub(crate) struct CompareJob {
+    pub ref_res: ParsedResource,
+    pub impl_res: ParsedResource,
+    pub viewport: Viewport,
+    pub threshold: f64,
+    /// Explicitly selected metrics; empty means "all that apply".
+    pub metrics: Vec<MetricKind>,
+    pub ignore_selectors: Vec<String>,
+    pub ignore_regions: Vec<IgnoreRegion>,
+    /// CSS selectors scoping URL captures to one element on each side.
+    pub ref_selector: Option<String>,
+    pub impl_selector: Option<String>,
+    /// State the selected elements are put in before capture (`--states`).
+    pub state: Option<InteractiveState>,
+    /// Disk cache and retry policy used by Figma inputs.
+    pub figma: FigmaConfig,
+    pub weights: ScoreWeights,
+    pub artifacts_dir: PathBuf,
+    pub keep_artifacts: bool,
+}
+
+/// Settings for the optional vision-model pass over pixel diff regions.
+#[derive(Clone, Copy)]
+pub(crate) struct SemanticOptions<'a> {
+    pub config: &'a SemanticConfig,
+    pub context: Option<&'a str>,
+}
+
+/// Normalize both sides of a job, run metrics, and build the compare payload.
+///
+/// Artifacts are written to `job.artifacts_dir`; cleanup is left to the caller.
+pub(crate) async fn execute_compare(
+    job: &CompareJob,
+    browser: &BrowserManager,
+    semantic: Option<SemanticOptions<'_>>,
+    progress: Option<ProgressCallback>,
+    verbose: bool,
+) -> Result<CompareOutput, DpcError> {
+    let artifacts_dir = job.artifacts_dir.as_path();
+
+    // Convert resources to NormalizedViews
+    if verbose {
+        eprintln!("Normalizing reference ({:?})\u{2026}", job.ref_res.kind);
+    }
+    let ref_view_raw = resource_to_normalized_view(
+        &job.ref_res,
+        &job.viewport,
+        artifacts_dir,
+        "ref",
+        job.ref_selector.as_deref(),
+        job.state,
+        browser,
+        &job.figma,
+        progress.clone(),
+    )
+    .await
+    .map_err(|err| resource_error(err, "reference"))?;
+
+    if verbose {
+        eprintln!(
+            "Normalizing implementation ({:?})\u{2026}",
+            job.impl_res.kind
+        );
+    }
+    let impl_view_raw = resource_to_normalized_view(
+        &job.impl_res,
+        &job.viewport,
+        artifacts_dir,
+        "impl",
+        job.impl_selector.as_deref(),
+        job.state,
+        browser,
+        &job.figma,
+        progress.clone(),
+    )
+    .await
+    .map_err(|err| resource_error(err, "implementation"))?;
+
+    let mut output = compare_views(job, &ref_view_raw, &impl_view_raw, semantic, verbose).await?;
+    output.rendering = rendering_info(job, browser);
+    Ok(output)
+}
+
+/// Stabilizations applied to the job's URL renders, if deterministic mode is on.
+pub(crate) fn rendering_info(job: &CompareJob, browser: &BrowserManager) -> Option<RenderingInfo> {
+    let renders_url = [&job.ref_res, &job.impl_res]
+        .iter()
+        .any(|res| matches!(res.kind, ResourceKind::Url | ResourceKind::Html));
+    (browser.options().deterministic && renders_url).then(RenderingInfo::deterministic)
+}
+
+/// Apply the job's ignores to already-normalized views, run metrics, and build the payload.
+///
+/// Used directly when the reference comes from a stored baseline instead of a render.
+pub(crate) async fn compare_views(
+    job: &CompareJob,
+    ref_view_raw: &NormalizedView,
+    impl_view_raw: &NormalizedView,
+    semantic: Option<SemanticOptions<'_>>,
+    verbose: bool,
+) -> Result<CompareOutput, DpcError> {
+    let artifacts_dir = job.artifacts_dir.as_path();
+    let ref_view = apply_dom_ignores(ref_view_raw, &job.ignore_selectors);
+    let impl_view = apply_dom_ignores(impl_view_raw, &job.ignore_selectors);
+
+    let ref_view = if job.ignore_regions.is_empty() {
+        ref_view
+    } else {
+        apply_ignore_regions(&ref_view, &job.ignore_regions, artifacts_dir, "ref")?
+    };
+    let impl_view = if job.ignore_regions.is_empty() {
+        impl_view
+    } else {
+        apply_ignore_regions(&impl_view, &job.ignore_regions, artifacts_dir, "impl")?
+    };
+
+    // Determine effective metrics based on input types
+    let effective_metrics =
+        if job.metrics.is_empty() && ref_view.dom.is_none() && impl_view.dom.is_none() {
+            vec![MetricKind::Pixel, MetricKind::Color]
+        } else {
+            job.metrics.clone()
+        };
+
+    // Run metrics
+    if verbose {
+        eprintln!("Running metrics: {:?}", effective_metrics);
+    }
+    let all_metrics = default_metrics();
+    let mut metrics_scores =
+        run_metrics(&all_metrics, &effective_metrics, &ref_view, &impl_view)
+            .map_err(|err| DpcError::Config(format!("Failed to compute metrics: {}", err)))?;
+
+    // Run semantic analysis if enabled and we have pixel diff regions
+    if let Some(semantic) = semantic {
+        if let Some(ref mut pixel_metric) = metrics_scores.pixel {
+            if !pixel_metric.diff_regions.is_empty() {
+                run_semantic_analysis(pixel_metric, &ref_view, &impl_view, &semantic, verbose)
+                    .await;
+            }
+        }
+    }
+
+    // Calculate combined score
+    let similarity = calculate_combined_score(&metrics_scores, &job.weights);
+
+    // Determine pass/fail
+    let passed = similarity >= job.threshold as f32;
+
+    // Generate summary
+    let pages: Vec<_> = [
+        ("Reference", ref_view_raw),
+        ("Implementation", impl_view_raw),
+    ]
+    .into_iter()
+    .filter_map(|(label, view)| view.diagnostics.as_ref().map(|d| (label, d)))
+    .collect();
+    let summary = generate_summary(&metrics_scores, similarity, job.threshold as f32, &pages);
+
+    let artifacts = persist_compare_artifacts(
+        artifacts_dir,
+        ref_view_raw,
+        impl_view_raw,
+        &ref_view,
+        &impl_view,
+        job.keep_artifacts,
+    )?;
+
+    Ok(CompareOutput {
         version: DPC_OUTPUT_VERSION.to_string(),
         ref_resource: ResourceDescriptor {
-            kind: ref_res.kind,
-            value: ref_res.value,
+            kind: job.ref_res.kind,
+            value: job.ref_res.value.clone(),
         },
         impl_resource: ResourceDescriptor {
-            kind: impl_res.kind,
-            value: impl_res.value,
+            kind: job.impl_res.kind,
+            value: job.impl_res.value.clone(),
         },
-        viewport,
+        viewport: job.viewport,
         similarity,
-        threshold: threshold as f32,
+        threshold: job.threshold as f32,
         passed,
         metrics: metrics_scores,
         summary: Some(summary),
-        artifacts,
-    });
+        artifacts: Some(artifacts),
+        rendering: None,
+    })
+}
 
-    if let Err(err) = write_output(&body, format, output.clone()) {
-        return render_error(DpcError::Config(err.to_string()), format, output);
+/// Run a job once per breakpoint, each in its own artifacts subdirectory.
+///
+/// With `engines`, every breakpoint is compared once per browser engine, each engine in
+/// its own pooled browser; entries are named after the engine alone when there is a
+/// single breakpoint, and `breakpoint/engine` otherwise. With `states`, each of those runs
+/// again captures the target element once per state, adding `state` (or `target/state`
+/// for several targets) to the name; the breakpoint is left out when there is only one.
+#[allow(clippy::too_many_arguments)]
+pub(crate) async fn execute_breakpoints(
+    job: &CompareJob,
+    breakpoints: &[Breakpoint],
+    engines: &[BrowserEngine],
+    states: &[StateTarget],
+    browser: &BrowserManager,
+    semantic: Option<SemanticOptions<'_>>,
+    progress: Option<ProgressCallback>,
+    verbose: bool,
+) -> Result<ResponsiveCompareOutput, DpcError> {
+    let browsers: Vec<(Option<BrowserEngine>, BrowserManager)> = if engines.is_empty() {
+        vec![(None, browser.clone())]
+    } else {
+        engines
+            .iter()
+            .map(|&engine| (Some(engine), browser.with_engine(engine)))
+            .collect()
+    };
+    let captures: Vec<Option<(&StateTarget, InteractiveState)>> = if states.is_empty() {
+        vec![None]
+    } else {
+        states
+            .iter()
+            .flat_map(|target| {
+                target
+                    .states
+                    .iter()
+                    .map(move |&state| Some((target, state)))
+            })
+            .collect()
+    };
+    let mut results = Vec::with_capacity(breakpoints.len() * browsers.len() * captures.len());
+    for breakpoint in breakpoints {
+        for (engine, engine_browser) in &browsers {
+            for &capture in &captures {
+                let mut parts = Vec::new();
+                if breakpoints.len() > 1 || (engine.is_none() && capture.is_none()) {
+                    parts.push(breakpoint.label());
+                }
+                if let Some(engine) = engine {
+                    parts.push(engine.to_string());
+                }
+                if let Some((target, state)) = capture {
+                    if states.len() > 1 {
+                        parts.push(target.label());
+                    }
+                    parts.push(state.to_string());
+                }
+                let label = parts.join("/");
+                let kind = match (capture, engine) {
+                    (Some(_), _) => "State",
+                    (None, Some(_)) if breakpoints.len() == 1 => "Browser",
+                    _ => "Breakpoint",
+                };
+                let context = format!("{} {label}", kind.to_ascii_lowercase());
+                if verbose {
+                    eprintln!(
+                        "{kind} {label} ({}x{})\u{2026}",
+                        breakpoint.viewport.width, breakpoint.viewport.height
+                    );
+                }
+                let mut breakpoint_job = CompareJob {
+                    ref_res: with_figma_node(
+                        &job.ref_res,
+                        breakpoint.ref_node_id.as_deref(),
+                        &context,
+                    ),
+                    impl_res: with_figma_node(
+                        &job.impl_res,
+                        breakpoint.impl_node_id.as_deref(),
+                        &context,
+                    ),
+                    viewport: breakpoint.viewport,
+                    artifacts_dir: job.artifacts_dir.join(artifact_subdir_name(&label)),
+                    ..job.clone()
+                };
+                if let Some((target, state)) = capture {
+                    let (ref_selector, impl_selector) = state_selectors(job, target);
+                    breakpoint_job = CompareJob {
+                        ref_res: with_figma_node(
+                            &breakpoint_job.ref_res,
+                            target.ref_node_ids.get(&state).map(String::as_str),
+                            &context,
+                        ),
+                        impl_res: with_figma_node(
+                            &breakpoint_job.impl_res,
+                            target.impl_node_ids.get(&state).map(String::as_str),
+                            &context,
+                        ),
+                        ref_selector,
+                        impl_selector,
+                        state: Some(state),
+                        ..breakpoint_job
+                    };
+                }
+                std::fs::create_dir_all(&breakpoint_job.artifacts_dir)?;
+                let result = execute_compare(
+                    &breakpoint_job,
+                    engine_browser,
+                    semantic,
+                    progress.clone(),
+                    verbose,
+                )
+                .await
+                .map_err(|err| match err {
+                    DpcError::Config(msg) => DpcError::Config(format!("{kind} {label}: {msg}")),
+                    other => other,
+                })?;
+                results.push(BreakpointCompareOutput {
+                    name: label,
+                    browser: *engine,
+                    state: capture.map(|(_, state)| state),
+                    result,
+                });
+            }
+        }
+    }
+    if !engines.is_empty() {
+        for (_, engine_browser) in &browsers {
+            engine_browser.shutdown().await;
+        }
     }
 
-    // Cleanup artifacts unless --keep-artifacts is set
-    if !should_keep_artifacts {
-        let _ = std::fs::remove_dir_all(&artifacts_dir);
+    let similarity = if results.is_empty() {
+        0.0
+    } else {
+        results.iter().map(|r| r.result.similarity).sum::<f32>() / results.len() as f32
+    };
+    Ok(ResponsiveCompareOutput {
+        version: DPC_OUTPUT_VERSION.to_string(),
+        ref_resource: ResourceDescriptor {
+            kind: job.ref_res.kind,
+            value: job.ref_res.value.clone(),
+        },
+        impl_resource: ResourceDescriptor {
+            kind: job.impl_res.kind,
+            value: job.impl_res.value.clone(),
+        },
+        similarity,
+        threshold: job.threshold as f32,
+        passed: !results.is_empty() && results.iter().all(|r| r.result.passed),
+        breakpoints: results,
+    })
+}
+
+/// Point a Figma resource at a breakpoint- or state-specific node; other kinds are returned
+/// unchanged. `context` names the run in the warning, e.g. `breakpoint mobile`.
+fn with_figma_node(
+    resource: &ParsedResource,
+    node_id: Option<&str>,
+    context: &str,
+) -> ParsedResource {
+    let mut resource = resource.clone();
+    let Some(node_id) = node_id else {
+        return resource;
+    };
+    match resource.figma_info.as_mut() {
+        Some(info) => info.node_id = Some(node_id.replace('-', ":")),
+        None => eprintln!(
+            "Warning: {context} sets a Figma node-id but {} is not a Figma input; ignoring",
+            resource.value
+        ),
     }
+    resource
+}
 
-    exit_code_for_compare(passed)
+async fn run_semantic_analysis(
+    pixel_metric: &mut PixelMetric,
+    ref_view: &NormalizedView,
+    impl_view: &NormalizedView,
+    semantic: &SemanticOptions<'_>,
+    verbose: bool,
+) {
+    if verbose {
+        eprintln!(
+            "Running semantic analysis on {} diff regions...",
+            pixel_metric.diff_regions.len()
+        );
+    }
+
+    let Some(analyzer) = SemanticAnalyzer::from_config(semantic.config) else {
+        eprintln!(
+            "Warning: --semantic-analysis requires an API key. Set DPC_VISION_API_KEY, OPENAI_API_KEY, or add [semantic] api_key to config"
+        );
+        return;
+    };
+
+    // Use image-aware clustering to separate different UI components
+    match analyzer
+        .analyze_diff_regions(
+            &ref_view.screenshot_path,
+            &impl_view.screenshot_path,
+            &pixel_metric.diff_regions,
+            semantic.context,
+        )
+        .await
+    {
+        Ok(semantic_diffs) => {
+            if verbose {
+                eprintln!(
+                    "Semantic analysis found {} diff types",
+                    semantic_diffs.len()
+                );
+                for diff in &semantic_diffs {
+                    eprintln!("  - {}: {}", diff.diff_type, diff.description);
+                }
+            }
+            // Convert to the types expected by PixelMetric
+            let typed_diffs: Vec<dpc_lib::types::SemanticDiff> = semantic_diffs
+                .into_iter()
+                .map(|d| dpc_lib::types::SemanticDiff {
+                    x: d.x,
+                    y: d.y,
+                    width: d.width,
+                    height: d.height,
+                    severity: d.severity,
+                    diff_type: match d.diff_type {
+                        dpc_lib::SemanticDiffType::TextContent => {
+                            dpc_lib::types::SemanticDiffType::TextContent
+                        }
+                        dpc_lib::SemanticDiffType::TextReflow => {
+                            dpc_lib::types::SemanticDiffType::TextReflow
+                        }
+                        dpc_lib::SemanticDiffType::Typography => {
+                            dpc_lib::types::SemanticDiffType::Typography
+                        }
+                        dpc_lib::SemanticDiffType::Layout => {
+                            dpc_lib::types::SemanticDiffType::Layout
+                        }
+                        dpc_lib::SemanticDiffType::Color => dpc_lib::types::SemanticDiffType::Color,
+                        dpc_lib::SemanticDiffType::MissingElement => {
+                            dpc_lib::types::SemanticDiffType::MissingElement
+                        }
+                        dpc_lib::SemanticDiffType::ExtraElement => {
+                            dpc_lib::types::SemanticDiffType::ExtraElement
+                        }
+                        dpc_lib::SemanticDiffType::Spacing => {
+                            dpc_lib::types::SemanticDiffType::Spacing
+                        }
+                        dpc_lib::SemanticDiffType::ImageChange => {
+                            dpc_lib::types::SemanticDiffType::ImageChange
+                        }
+                        dpc_lib::SemanticDiffType::Decoration => {
+                            dpc_lib::types::SemanticDiffType::Decoration
+                        }
+                        dpc_lib::SemanticDiffType::Other => dpc_lib::types::SemanticDiffType::Other,
+                    },
+                    description: d.description,
+                    confidence: d.confidence,
+                })
+                .collect();
+            pixel_metric.semantic_diffs = Some(typed_diffs);
+        }
+        Err(e) => {
+            eprintln!("Warning: Semantic analysis failed: {}", e);
+        }
+    }
 }
 
-fn resource_kind_from_cli(rt: crate::cli::ResourceType) -> ResourceKind {
+pub(crate) fn resource_kind_from_cli(rt: crate::cli::ResourceType) -> ResourceKind {
     match rt {
         crate::cli::ResourceType::Url => ResourceKind::Url,
         crate::cli::ResourceType::Image => ResourceKind::Image,
         crate::cli::ResourceType::Figma => ResourceKind::Figma,
+        crate::cli::ResourceType::View => ResourceKind::View,
+        crate::cli::ResourceType::Html => ResourceKind::Html,
+        crate::cli::ResourceType::FigmaExport => ResourceKind::FigmaExport,
+    }
+}
+
+/// Selectors a state target captures with on each side: its side-specific selector, else
+/// its shared one, else the job's. Only URL and HTML inputs take a selector.
+fn state_selectors(job: &CompareJob, target: &StateTarget) -> (Option<String>, Option<String>) {
+    let pick = |resource: &ParsedResource, own: &Option<String>, fallback: &Option<String>| {
+        renders_page(resource)
+            .then(|| {
+                own.as_ref()
+                    .or(target.selector.as_ref())
+                    .or(fallback.as_ref())
+                    .cloned()
+            })
+            .flatten()
+    };
+    (
+        pick(&job.ref_res, &target.ref_selector, &job.ref_selector),
+        pick(&job.impl_res, &target.impl_selector, &job.impl_selector),
+    )
+}
+
+/// Hover, focus and active need an element to act on, so every URL or HTML side of a
+/// state target must end up with a non-empty selector.
+pub(crate) fn check_state_targets(
+    job: &CompareJob,
+    targets: &[StateTarget],
+) -> Result<(), DpcError> {
+    for target in targets {
+        if target
+            .states
+            .iter()
+            .all(|&state| state == InteractiveState::Rest)
+        {
+            continue;
+        }
+        let (ref_selector, impl_selector) = state_selectors(job, target);
+        for (resource, selector, side) in [
+            (&job.ref_res, ref_selector, "ref"),
+            (&job.impl_res, impl_selector, "impl"),
+        ] {
+            if !renders_page(resource) {
+                continue;
+            }
+            if selector.is_none_or(|s| s.trim().is_empty()) {
+                return Err(DpcError::Config(format!(
+                    "States for {}: {} is rendered, so it needs the element to hover, focus or press; set --{side}-selector or a [[states]] selector",
+                    target.label(),
+                    resource.value
+                )));
+            }
+        }
+    }
+    Ok(())
+}
+
+fn renders_page(resource: &ParsedResource) -> bool {
+    matches!(resource.kind, ResourceKind::Url | ResourceKind::Html)
+}
+
+/// Element selectors only make sense for rendered pages; reject them for other inputs.
+pub(crate) fn check_capture_selector(
+    resource: &ParsedResource,
+    selector: Option<&str>,
+    flag: &str,
+) -> Result<(), DpcError> {
+    match selector {
+        Some(_) if !matches!(resource.kind, ResourceKind::Url | ResourceKind::Html) => {
+            Err(DpcError::Config(format!(
+                "{flag} only applies to URL and HTML inputs ({} is {:?})",
+                resource.value, resource.kind
+            )))
+        }
+        Some(selector) if selector.trim().is_empty() => {
+            Err(DpcError::Config(format!("{flag} must not be empty")))
+        }
+        _ => Ok(()),
     }
 }
 
-fn parse_metric_kinds(
+pub(crate) fn parse_metric_kinds(
     kinds: Option<&[String]>,
 ) -> Result<Vec<MetricKind>, Box<dyn std::error::Error>> {
     use std::io;
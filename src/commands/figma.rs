@@ -0,0 +1,91 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use dpc_lib::output::DPC_OUTPUT_VERSION;
+use dpc_lib::types::ResourceKind;
+use dpc_lib::{
+    parse_resource, pull_figma_export, DpcError, DpcOutput, FigmaAuth, FigmaClient, FigmaPullOutput,
+};
+
+use crate::cli::FigmaCommand;
+use crate::formatting::{render_error, write_output};
+use crate::settings::load_config;
+
+/// Export directories are created under this root unless `--out-dir` is given.
+const DEFAULT_FIGMA_EXPORT_DIR: &str = ".dpc/figma";
+
+/// Run a `figma` subcommand.
+pub async fn run_figma(
+    config_path: Option<PathBuf>,
+    verbose: bool,
+    command: FigmaCommand,
+) -> ExitCode {
+    match command {
+        FigmaCommand::Pull {
+            url,
+            out_dir,
+            scale,
+            format,
+            output,
+        } => {
+            let body = match pull(config_path.as_deref(), &url, out_dir, scale, verbose).await {
+                Ok(out) => DpcOutput::FigmaPull(out),
+                Err(err) => return render_error(err, format, output),
+            };
+            if let Err(err) = write_output(&body, format, output.clone()) {
+                return render_error(DpcError::Config(err.to_string()), format, output);
+            }
+            ExitCode::SUCCESS
+        }
+    }
+}
+
+async fn pull(
+    config_path: Option<&Path>,
+    url: &str,
+    out_dir: Option<PathBuf>,
+    scale: f32,
+    verbose: bool,
+) -> Result<FigmaPullOutput, DpcError> {
+    let resource = parse_resource(url, None).map_err(|err| DpcError::Config(err.to_string()))?;
+    let info = resource
+        .figma_info
+        .filter(|_| resource.kind == ResourceKind::Figma)
+        .ok_or_else(|| DpcError::Config(format!("{url} is not a Figma file URL")))?;
+    let node_id = info.node_id.ok_or_else(|| {
+        DpcError::Config(format!(
+            "Figma URL {url} has no node-id; copy the link to the frame to pull"
+        ))
+    })?;
+    let config = load_config(config_path)?;
+    let auth = FigmaAuth::from_env().ok_or_else(|| {
+        DpcError::Config("Figma token missing; set FIGMA_TOKEN or FIGMA_OAUTH_TOKEN".to_string())
+    })?;
+    let client = FigmaClient::from_auth(auth)
+        .map_err(|err| DpcError::Config(format!("Figma client error: {err}")))?
+        .with_retry(config.figma.retry);
+
+    let dir = out_dir.unwrap_or_else(|| {
+        PathBuf::from(DEFAULT_FIGMA_EXPORT_DIR)
+            .join(&info.file_key)
+            .join(node_id.replace(':', "-"))
+    });
+    if verbose {
+        eprintln!(
+            "Pulling Figma node {node_id} of {} into {}\u{2026}",
+            info.file_key,
+            dir.display()
+        );
+    }
+    let source = pull_figma_export(&client, &info.file_key, &node_id, scale, &dir).await?;
+
+    Ok(FigmaPullOutput {
+        version: DPC_OUTPUT_VERSION.to_string(),
+        path: dir,
+        file_key: source.file_key,
+        node_id: source.node_id,
+        scale: source.scale,
+        file_version: source.version,
+        last_modified: source.last_modified,
+    })
+}
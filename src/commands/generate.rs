@@ -7,14 +7,22 @@ use base64::Engine;
 use dpc_lib::output::DPC_OUTPUT_VERSION;
 use dpc_lib::types::ResourceKind;
 use dpc_lib::{
-    parse_resource, DpcError, DpcOutput, GenerateCodeOutput, ResourceDescriptor, Summary, Viewport,
+    parse_resource, DpcError, DpcOutput, GenerateCodeOutput, ProgressCallback, ResourceDescriptor,
+    Summary, Viewport,
 };
 use serde::{Deserialize, Serialize};
 
-use crate::cli::OutputFormat;
+use crate::cli::{
+    AuthArgs, BrowserEngine, CaptureArgs, DeviceArgs, FigmaCacheMode, HarArgs, OutputFormat,
+};
 use crate::formatting::{render_error, write_output};
-use crate::pipeline::{resolve_artifacts_dir, resource_to_normalized_view};
-use crate::settings::{flag_present, load_config};
+use crate::pipeline::{
+    browser_manager, resolve_artifacts_dir, resource_error, resource_to_normalized_view,
+};
+use crate::settings::{
+    flag_present, load_config, with_browser_arg, with_capture_args, with_device_args,
+    with_figma_cache_arg,
+};
 
 /// Run the generate-code command.
 #[allow(clippy::too_many_arguments)]
@@ -22,6 +30,13 @@ pub async fn run_generate_code(
     raw_args: &[String],
     config_path: Option<PathBuf>,
     verbose: bool,
+    auth: &AuthArgs,
+    deterministic: bool,
+    har: &HarArgs,
+    device: &DeviceArgs,
+    browser: Option<BrowserEngine>,
+    figma_cache: Option<FigmaCacheMode>,
+    capture: &CaptureArgs,
     input: String,
     input_type: Option<crate::cli::ResourceType>,
     viewport: Viewport,
@@ -29,7 +44,12 @@ pub async fn run_generate_code(
     output: Option<PathBuf>,
     format: OutputFormat,
 ) -> ExitCode {
-    let config = match load_config(config_path.as_deref()) {
+    let config = match load_config(config_path.as_deref())
+        .and_then(|cfg| with_device_args(cfg, device))
+        .map(|cfg| with_browser_arg(cfg, browser))
+        .map(|cfg| with_capture_args(cfg, capture))
+        .map(|cfg| with_figma_cache_arg(cfg, figma_cache))
+    {
         Ok(cfg) => cfg,
         Err(err) => return render_error(err, format, None),
     };
@@ -38,10 +58,20 @@ pub async fn run_generate_code(
     } else {
         config.viewport
     };
-    let timeouts = config.timeouts;
-    let nav_timeout = timeouts.navigation.as_secs();
-    let network_idle_timeout = timeouts.network_idle.as_secs();
-    let process_timeout = timeouts.process.as_secs();
+    let timeouts = &config.timeouts;
+    let browser = match browser_manager(
+        timeouts.navigation.as_secs(),
+        timeouts.network_idle.as_secs(),
+        timeouts.process.as_secs(),
+        1,
+        &config,
+        auth,
+        deterministic,
+        har,
+    ) {
+        Ok(browser) => browser,
+        Err(err) => return render_error(err, format, None),
+    };
 
     let normalized_stack = match normalize_stack(&stack) {
         Ok(s) => s,
@@ -102,7 +132,7 @@ pub async fn run_generate_code(
             artifacts_dir.display()
         );
     }
-    let progress_logger: Option<Arc<dyn Fn(&str) + Send + Sync>> = if verbose {
+    let progress_logger: Option<ProgressCallback> = if verbose {
         Some(Arc::new(|msg: &str| eprintln!("{msg}")))
     } else {
         None
@@ -112,21 +142,16 @@ pub async fn run_generate_code(
         &viewport,
         &artifacts_dir,
         "input",
+        None,
+        None,
+        &browser,
+        &config.figma,
         progress_logger,
-        nav_timeout,
-        network_idle_timeout,
-        process_timeout,
     )
     .await
     {
         Ok(view) => view,
-        Err(err) => {
-            return render_error(
-                DpcError::Config(format!("Failed to process input: {err}")),
-                format,
-                None,
-            )
-        }
+        Err(err) => return render_error(resource_error(err, "input"), format, None),
     };
 
     if verbose {
@@ -190,6 +215,9 @@ fn resource_kind_from_cli(rt: crate::cli::ResourceType) -> ResourceKind {
         crate::cli::ResourceType::Url => ResourceKind::Url,
         crate::cli::ResourceType::Image => ResourceKind::Image,
         crate::cli::ResourceType::Figma => ResourceKind::Figma,
+        crate::cli::ResourceType::View => ResourceKind::View,
+        crate::cli::ResourceType::Html => ResourceKind::Html,
+        crate::cli::ResourceType::FigmaExport => ResourceKind::FigmaExport,
     }
 }
 
@@ -1,7 +1,15 @@
+mod baseline;
+mod batch;
 mod compare;
+mod figma;
 mod generate;
 mod quality;
+mod report;
 
+pub use baseline::run_baseline;
+pub use batch::run_batch;
 pub use compare::run_compare;
+pub use figma::run_figma;
 pub use generate::run_generate_code;
 pub use quality::run_quality;
+pub use report::run_report;
@@ -8,15 +8,23 @@ use dpc_lib::output::DPC_OUTPUT_VERSION;
 use dpc_lib::types::{BoundingBox, DomNode, FigmaNode, FigmaPaintKind, NormalizedView, ResourceKind};
 use dpc_lib::QualityFindingType;
 use dpc_lib::{
-    parse_resource, DpcError, DpcOutput, FindingSeverity, QualityFinding, QualityOutput,
-    ResourceDescriptor, Viewport,
+    parse_resource, DpcError, DpcOutput, FindingSeverity, ProgressCallback, QualityFinding,
+    QualityOutput, ResourceDescriptor, Viewport,
 };
 use image::{DynamicImage, GenericImageView};
 
-use crate::cli::OutputFormat;
+use crate::cli::{
+    AuthArgs, BrowserEngine, CaptureArgs, DeviceArgs, FigmaCacheMode, HarArgs, OutputFormat,
+};
 use crate::formatting::{render_error, write_output};
-use crate::pipeline::{resolve_artifacts_dir, resource_to_normalized_view};
-use crate::settings::{flag_present, load_config};
+use crate::pipeline::{
+    browser_manager, page_problems, resolve_artifacts_dir, resource_error,
+    resource_to_normalized_view, PageProblem,
+};
+use crate::settings::{
+    flag_present, load_config, with_browser_arg, with_capture_args, with_device_args,
+    with_figma_cache_arg,
+};
 
 /// Run the quality command.
 #[allow(clippy::too_many_arguments)]
@@ -24,13 +32,25 @@ pub async fn run_quality(
     raw_args: &[String],
     config_path: Option<PathBuf>,
     verbose: bool,
+    auth: &AuthArgs,
+    deterministic: bool,
+    har: &HarArgs,
+    device: &DeviceArgs,
+    browser: Option<BrowserEngine>,
+    figma_cache: Option<FigmaCacheMode>,
+    capture: &CaptureArgs,
     input: String,
     input_type: Option<crate::cli::ResourceType>,
     viewport: Viewport,
     format: OutputFormat,
     output: Option<PathBuf>,
 ) -> ExitCode {
-    let config = match load_config(config_path.as_deref()) {
+    let config = match load_config(config_path.as_deref())
+        .and_then(|cfg| with_device_args(cfg, device))
+        .map(|cfg| with_browser_arg(cfg, browser))
+        .map(|cfg| with_capture_args(cfg, capture))
+        .map(|cfg| with_figma_cache_arg(cfg, figma_cache))
+    {
         Ok(cfg) => cfg,
         Err(err) => return render_error(err, format, output.clone()),
     };
@@ -39,10 +59,20 @@ pub async fn run_quality(
     } else {
         config.viewport
     };
-    let timeouts = config.timeouts;
-    let nav_timeout = timeouts.navigation.as_secs();
-    let network_idle_timeout = timeouts.network_idle.as_secs();
-    let process_timeout = timeouts.process.as_secs();
+    let timeouts = &config.timeouts;
+    let browser = match browser_manager(
+        timeouts.navigation.as_secs(),
+        timeouts.network_idle.as_secs(),
+        timeouts.process.as_secs(),
+        1,
+        &config,
+        auth,
+        deterministic,
+        har,
+    ) {
+        Ok(browser) => browser,
+        Err(err) => return render_error(err, format, output),
+    };
 
     if verbose {
         eprintln!("Parsing input resource…");
@@ -63,7 +93,7 @@ pub async fn run_quality(
             artifacts_dir.display()
         );
     }
-    let progress_logger: Option<Arc<dyn Fn(&str) + Send + Sync>> = if verbose {
+    let progress_logger: Option<ProgressCallback> = if verbose {
         Some(Arc::new(|msg: &str| eprintln!("{msg}")))
     } else {
         None
@@ -73,21 +103,16 @@ pub async fn run_quality(
         &viewport,
         &artifacts_dir,
         "input",
+        None,
+        None,
+        &browser,
+        &config.figma,
         progress_logger,
-        nav_timeout,
-        network_idle_timeout,
-        process_timeout,
     )
     .await
     {
         Ok(view) => view,
-        Err(err) => {
-            return render_error(
-                DpcError::Config(format!("Failed to process input: {err}")),
-                format,
-                output.clone(),
-            )
-        }
+        Err(err) => return render_error(resource_error(err, "input"), format, output.clone()),
     };
 
     if verbose {
@@ -116,6 +141,9 @@ fn resource_kind_from_cli(rt: crate::cli::ResourceType) -> ResourceKind {
         crate::cli::ResourceType::Url => ResourceKind::Url,
         crate::cli::ResourceType::Image => ResourceKind::Image,
         crate::cli::ResourceType::Figma => ResourceKind::Figma,
+        crate::cli::ResourceType::View => ResourceKind::View,
+        crate::cli::ResourceType::Html => ResourceKind::Html,
+        crate::cli::ResourceType::FigmaExport => ResourceKind::FigmaExport,
     }
 }
 
@@ -206,9 +234,34 @@ fn score_quality(view: &NormalizedView, viewport: &Viewport) -> (f32, Vec<Qualit
         score += 0.02;
     }
 
+    if let Some((finding, penalty)) = evaluate_box_model(view) {
+        findings.push(finding);
+        score -= penalty;
+    }
+
+    if let Some(diagnostics) = &view.diagnostics {
+        findings.extend(page_problems(diagnostics).into_iter().map(page_finding));
+    }
+
     (score.clamp(0.0, 1.0), findings)
 }
 
+/// A problem the page reported while rendering, as a finding. Uncaught exceptions are
+/// errors; everything else is a warning, as a stray 404 rarely breaks a page.
+fn page_finding((problem, message): (PageProblem, String)) -> QualityFinding {
+    let (severity, finding_type) = match problem {
+        PageProblem::PageError => (FindingSeverity::Error, QualityFindingType::ScriptError),
+        PageProblem::ConsoleError => (FindingSeverity::Warning, QualityFindingType::ScriptError),
+        PageProblem::FailedRequest => (FindingSeverity::Warning, QualityFindingType::FailedRequest),
+        PageProblem::BrokenImage => (FindingSeverity::Warning, QualityFindingType::BrokenImage),
+    };
+    QualityFinding {
+        severity,
+        finding_type,
+        message: format!("Page reported {message}"),
+    }
+}
+
 fn alignment_heuristic(
     view: &NormalizedView,
     viewport: &Viewport,
@@ -572,13 +625,13 @@ fn parse_css_color(value: &str) -> Option<[f32; 4]> {
     if let Some(body) = v.strip_prefix("rgba(").or_else(|| v.strip_prefix("rgb(")) {
         let cleaned = body.trim_end_matches(')').replace('/', " ");
         let parts: Vec<_> = cleaned
-            .split(|c| c == ',' || c == ' ')
+            .split([',', ' '])
             .filter(|p| !p.trim().is_empty())
             .collect();
         if parts.len() < 3 {
             return None;
         }
-        let r: f32 = parts.get(0)?.trim().parse::<f32>().ok()? / 255.0;
+        let r: f32 = parts.first()?.trim().parse::<f32>().ok()? / 255.0;
         let g: f32 = parts.get(1)?.trim().parse::<f32>().ok()? / 255.0;
         let b: f32 = parts.get(2)?.trim().parse::<f32>().ok()? / 255.0;
         let a: f32 = if let Some(alpha) = parts.get(3) {
@@ -636,6 +689,13 @@ fn sample_background_color(
     view: &NormalizedView,
 ) -> Option<[f32; 3]> {
     let (x0, y0, w, h) = bbox_to_pixels(bbox, view.width, view.height)?;
+    // The box is in view (CSS) pixels; map it onto the possibly high-DPI screenshot.
+    let (img_w, img_h) = img.dimensions();
+    let (scale_x, scale_y) = view.pixel_scale(img_w, img_h);
+    let x0 = ((x0 as f32 * scale_x) as u32).min(img_w.saturating_sub(1));
+    let y0 = ((y0 as f32 * scale_y) as u32).min(img_h.saturating_sub(1));
+    let w = ((w as f32 * scale_x).round() as u32).min(img_w - x0);
+    let h = ((h as f32 * scale_y).round() as u32).min(img_h - y0);
     if w == 0 || h == 0 {
         return None;
     }
@@ -821,10 +881,75 @@ fn evaluate_spacing(gaps: &[f32]) -> Option<(QualityFinding, f32)> {
     Some((finding, penalty))
 }
 
+/// Checks computed padding, vertical margins and small border radii against a 4px grid.
+/// Horizontal margins are skipped (auto-centering resolves them to arbitrary widths), as
+/// are radii above 32px (pills and circles).
+fn evaluate_box_model(view: &NormalizedView) -> Option<(QualityFinding, f32)> {
+    const GRID: f32 = 4.0;
+    const MAX_RADIUS: f32 = 32.0;
+
+    let dom = view.dom.as_ref()?;
+    let values: Vec<f32> = dom
+        .nodes
+        .iter()
+        .filter_map(|n| n.computed_style.as_ref())
+        .flat_map(|style| {
+            let padding = style.padding.into_iter().flat_map(|p| p.values());
+            let margin = style.margin.into_iter().flat_map(|m| [m.top, m.bottom]);
+            let radii = style
+                .border_radius
+                .into_iter()
+                .flat_map(|r| r.values())
+                .filter(|r| *r <= MAX_RADIUS);
+            padding.chain(margin).chain(radii)
+        })
+        .map(f32::abs)
+        .filter(|v| *v >= 1.0)
+        .collect();
+    if values.len() < 6 {
+        return None;
+    }
+
+    let mut off_grid: Vec<i32> = values
+        .iter()
+        .filter(|v| {
+            let rem = *v % GRID;
+            rem > 0.5 && rem < GRID - 0.5
+        })
+        .map(|v| v.round() as i32)
+        .collect();
+    let ratio = off_grid.len() as f32 / values.len() as f32;
+    if ratio <= 0.3 {
+        return None;
+    }
+
+    let off_count = off_grid.len();
+    off_grid.sort_unstable();
+    off_grid.dedup();
+    let examples: Vec<String> = off_grid.iter().take(3).map(|v| format!("{v}px")).collect();
+    let penalty = (0.02 + ratio * 0.06).min(0.08);
+    let finding = QualityFinding {
+        severity: FindingSeverity::Warning,
+        finding_type: QualityFindingType::SpacingInconsistent,
+        message: format!(
+            "{} of {} padding/margin/radius values fall off a {}px grid (e.g. {}); consider a spacing scale.",
+            off_count,
+            values.len(),
+            GRID,
+            examples.join(", ")
+        ),
+    };
+
+    Some((finding, penalty))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use dpc_lib::types::{BoundingBox, ComputedStyle, DomSnapshot, ResourceKind};
+    use dpc_lib::types::{
+        BoundingBox, BoxCorners, BoxSides, BrokenImage, ComputedStyle, DomSnapshot, FailedRequest,
+        PageDiagnostics, ResourceKind,
+    };
     use image::{ImageBuffer, Rgba};
     use std::collections::HashMap;
 
@@ -841,6 +966,8 @@ mod tests {
                 text: None,
                 bounding_box: bbox,
                 computed_style: None,
+                shadow_host: None,
+                frame: None,
             })
             .collect();
 
@@ -856,6 +983,7 @@ mod tests {
             }),
             figma_tree: None,
             ocr_blocks: None,
+            diagnostics: None,
         }
     }
 
@@ -974,6 +1102,8 @@ mod tests {
                     font_size: Some(*size),
                     ..ComputedStyle::default()
                 }),
+                shadow_host: None,
+                frame: None,
             })
             .collect();
 
@@ -989,9 +1119,59 @@ mod tests {
             }),
             figma_tree: None,
             ocr_blocks: None,
+            diagnostics: None,
         }
     }
 
+    #[test]
+    fn flags_padding_and_radii_off_the_spacing_grid() {
+        let styled = |padding: f32, radius: f32| {
+            let mut view = view_with_font_sizes(&[16.0, 16.0]);
+            for node in &mut view.dom.as_mut().unwrap().nodes {
+                let style = node.computed_style.as_mut().unwrap();
+                style.padding = Some(BoxSides {
+                    top: padding,
+                    right: padding * 2.0,
+                    bottom: padding,
+                    left: padding * 2.0,
+                });
+                style.border_radius = Some(BoxCorners {
+                    top_left: radius,
+                    top_right: radius,
+                    bottom_right: radius,
+                    bottom_left: radius,
+                });
+            }
+            view
+        };
+        let box_model_finding = |view: &NormalizedView| {
+            score_quality(
+                view,
+                &Viewport {
+                    width: 800,
+                    height: 600,
+                },
+            )
+            .1
+            .into_iter()
+            .find(|f| f.message.contains("spacing scale"))
+        };
+
+        assert!(box_model_finding(&styled(8.0, 4.0)).is_none());
+        // Pill radii are ignored even though 9999 is off the grid.
+        assert!(box_model_finding(&styled(8.0, 9999.0)).is_none());
+        let finding = box_model_finding(&styled(7.0, 5.0)).expect("box model finding");
+        assert!(matches!(
+            finding.finding_type,
+            QualityFindingType::SpacingInconsistent
+        ));
+        assert!(
+            finding.message.contains("5px, 7px, 14px"),
+            "{}",
+            finding.message
+        );
+    }
+
     #[test]
     fn hierarchy_scores_tiered_text_higher() {
         let tiered = view_with_font_sizes(&[32.0, 20.0, 16.0]);
@@ -1056,7 +1236,10 @@ mod tests {
                 display: None,
                 visibility: None,
                 opacity: Some(1.0),
+                ..ComputedStyle::default()
             }),
+            shadow_host: None,
+            frame: None,
         };
 
         let view = NormalizedView {
@@ -1071,6 +1254,7 @@ mod tests {
             }),
             figma_tree: None,
             ocr_blocks: None,
+            diagnostics: None,
         };
 
         let (_score, findings) = score_quality(&view, &Viewport { width: 120, height: 80 });
@@ -1123,7 +1307,10 @@ mod tests {
                 display: None,
                 visibility: None,
                 opacity: Some(1.0),
+                ..ComputedStyle::default()
             }),
+            shadow_host: None,
+            frame: None,
         };
 
         let view = NormalizedView {
@@ -1138,6 +1325,7 @@ mod tests {
             }),
             figma_tree: None,
             ocr_blocks: None,
+            diagnostics: None,
         };
 
         let (_score, findings) = score_quality(&view, &Viewport { width: 100, height: 60 });
@@ -1151,4 +1339,54 @@ mod tests {
             finding.severity
         );
     }
+
+    #[test]
+    fn reports_page_errors_failed_requests_and_broken_images() {
+        let mut view = view_with_boxes(vec![BoundingBox {
+            x: 0.0,
+            y: 0.0,
+            width: 100.0,
+            height: 20.0,
+        }]);
+        view.diagnostics = Some(PageDiagnostics {
+            page_errors: vec!["TypeError: items is undefined".to_string()],
+            failed_requests: vec![FailedRequest {
+                url: "https://fonts.example/inter.woff2".to_string(),
+                resource_type: Some("font".to_string()),
+                status: None,
+                error: Some("net::ERR_NAME_NOT_RESOLVED".to_string()),
+            }],
+            broken_images: vec![BrokenImage {
+                src: "https://example.com/hero.png".to_string(),
+                alt: None,
+            }],
+            ..PageDiagnostics::default()
+        });
+
+        let (_score, findings) = score_quality(
+            &view,
+            &Viewport {
+                width: 800,
+                height: 600,
+            },
+        );
+        let page: Vec<_> = findings
+            .iter()
+            .filter(|f| {
+                matches!(
+                    f.finding_type,
+                    QualityFindingType::ScriptError
+                        | QualityFindingType::FailedRequest
+                        | QualityFindingType::BrokenImage
+                )
+            })
+            .collect();
+        assert_eq!(page.len(), 3);
+        assert!(matches!(page[0].severity, FindingSeverity::Error));
+        assert_eq!(
+            page[1].message,
+            "Page reported 1 failed request: net::ERR_NAME_NOT_RESOLVED font https://fonts.example/inter.woff2"
+        );
+        assert!(page[2].message.contains("hero.png"));
+    }
 }
@@ -0,0 +1,35 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use dpc_lib::{DpcError, DpcOutput};
+
+use crate::cli::OutputFormat;
+use crate::formatting::{render_error, write_output};
+
+/// Run the report command: re-render a saved JSON result as HTML.
+///
+/// Screenshots are embedded from the artifact paths recorded in the result, so the
+/// compare must have been run with `--keep-artifacts` or `--artifacts-dir` for the
+/// image views to appear.
+pub fn run_report(input: PathBuf, output: Option<PathBuf>) -> ExitCode {
+    let body = match load_result(&input) {
+        Ok(body) => body,
+        Err(err) => return render_error(err, OutputFormat::Json, None),
+    };
+    if let Err(err) = write_output(&body, OutputFormat::Html, output) {
+        return render_error(DpcError::Config(err.to_string()), OutputFormat::Json, None);
+    }
+    ExitCode::SUCCESS
+}
+
+fn load_result(path: &Path) -> Result<DpcOutput, DpcError> {
+    let data = std::fs::read(path).map_err(|err| {
+        DpcError::Config(format!("Failed to read result {}: {err}", path.display()))
+    })?;
+    serde_json::from_slice(&data).map_err(|err| {
+        DpcError::Config(format!(
+            "Invalid result {} (expected `--format json` output from dpc): {err}",
+            path.display()
+        ))
+    })
+}
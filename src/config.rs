@@ -1,9 +1,18 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::time::Duration;
 
-use crate::Viewport;
+use crate::browser::{
+    device_preset, load_cookie_file, resolve_secret, AutoScroll, ColorScheme, DeviceEmulation,
+    DevicePreset, InteractiveState, ReducedMotion, DEFAULT_SCROLL_MAX_HEIGHT,
+    DEFAULT_SCROLL_SETTLE, DEFAULT_SCROLL_STEP, DEVICE_PRESETS,
+};
+use crate::figma::{FigmaCache, FigmaCacheMode, FigmaRetryPolicy};
+use crate::{
+    AuthOptions, BasicAuth, BrowserBackend, BrowserEngine, DpcError, InteractionStep, Viewport,
+};
 use serde::de::{self, Deserializer};
 use serde::{Deserialize, Serialize};
 
@@ -19,6 +28,308 @@ pub struct Config {
     pub metric_weights: MetricWeights,
     pub timeouts: Timeouts,
     pub semantic: SemanticConfig,
+    /// Viewports to compare in one run (`[[breakpoints]]`); empty means a single `viewport` run.
+    pub breakpoints: Vec<Breakpoint>,
+    pub browser: BrowserSettings,
+    pub auth: AuthConfig,
+    /// Interactions run on URL inputs before capture (`[[steps]]`, e.g. `click = "#menu"`).
+    pub steps: Vec<InteractionStep>,
+    /// Device emulation for URL renders (`[device]`, e.g. `preset = "iphone-14"`).
+    pub device: DeviceConfig,
+    /// What URL renders capture (`[capture]`, e.g. `full_page = true`).
+    pub capture: CaptureConfig,
+    /// Elements compared in hover/focus/active states (`[[states]]`); empty means the page
+    /// is compared as it loads.
+    pub states: Vec<StateTarget>,
+    /// How Figma inputs use the disk cache and retry API requests (`[figma]`, e.g.
+    /// `cache = "offline"`).
+    pub figma: FigmaConfig,
+}
+
+/// Credentials for rendering pages behind a login (`[auth]`).
+///
+/// Header values and the basic-auth password may be `env:NAME` to read them from the
+/// environment instead of the file.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuthConfig {
+    /// Extra HTTP headers sent with every request.
+    pub headers: BTreeMap<String, String>,
+    /// Netscape `cookies.txt` or JSON cookie export.
+    pub cookies: Option<PathBuf>,
+    /// HTTP basic auth as `USER:PASSWORD`.
+    pub basic_auth: Option<String>,
+    /// Playwright `storageState` JSON, e.g. saved by a login script.
+    pub storage_state: Option<PathBuf>,
+}
+
+impl AuthConfig {
+    /// Read cookie files and `env:` secrets into browser [`AuthOptions`].
+    pub fn resolve(&self) -> Result<AuthOptions, DpcError> {
+        let mut headers = BTreeMap::new();
+        for (name, value) in &self.headers {
+            headers.insert(
+                name.clone(),
+                resolve_secret(value, &format!("header {name}"))?,
+            );
+        }
+        let cookies = match &self.cookies {
+            Some(path) => load_cookie_file(path)?,
+            None => Vec::new(),
+        };
+        let basic_auth = self
+            .basic_auth
+            .as_deref()
+            .map(BasicAuth::parse)
+            .transpose()?;
+        if let Some(path) = &self.storage_state {
+            if !path.is_file() {
+                return Err(DpcError::Config(format!(
+                    "Storage state file {} not found",
+                    path.display()
+                )));
+            }
+        }
+        Ok(AuthOptions {
+            headers,
+            cookies,
+            basic_auth,
+            storage_state: self.storage_state.clone(),
+        })
+    }
+}
+
+/// How URLs are rendered (`[browser]`).
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BrowserSettings {
+    /// `node` (Playwright via Node.js) or `cdp` (native, needs the `playwright` cargo feature).
+    pub backend: BrowserBackend,
+    /// `chromium`, `firefox` or `webkit`; only `chromium` uses the `cdp` backend.
+    pub engine: BrowserEngine,
+    /// Chrome/Chromium executable for the `cdp` backend; auto-detected when unset.
+    pub chrome_path: Option<PathBuf>,
+    /// Freeze animations, caret, media, clock and randomness before capture.
+    pub deterministic: bool,
+}
+
+/// Capture extent for URL renders (`[capture]`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CaptureConfig {
+    /// Screenshot and DOM cover the whole document height instead of the viewport.
+    pub full_page: bool,
+    /// Scroll to the bottom and back before capture so lazy content loads.
+    pub auto_scroll: bool,
+    /// Pixels per scroll step (default 800).
+    pub scroll_step: Option<u32>,
+    /// Stop scrolling this far down (default 20000), for pages that never end.
+    pub scroll_max_height: Option<u32>,
+    /// Pause after each scroll step (default 250ms).
+    #[serde(with = "humantime_serde")]
+    pub scroll_settle: Option<Duration>,
+}
+
+impl CaptureConfig {
+    /// Auto-scroll settings, or `None` when `auto_scroll` is off.
+    pub fn auto_scroll(&self) -> Option<AutoScroll> {
+        self.auto_scroll.then(|| AutoScroll {
+            step: self.scroll_step.unwrap_or(DEFAULT_SCROLL_STEP),
+            max_height: self.scroll_max_height.unwrap_or(DEFAULT_SCROLL_MAX_HEIGHT),
+            settle: self.scroll_settle.unwrap_or(DEFAULT_SCROLL_SETTLE),
+        })
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.scroll_step == Some(0) {
+            return Err("capture scroll_step must be greater than zero".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Figma API settings (`[figma]`).
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FigmaConfig {
+    /// `off`, `read` (default), `refresh` or `offline`.
+    pub cache: FigmaCacheMode,
+    /// Cache root; defaults to `dpc/figma` under the user cache directory.
+    pub cache_dir: Option<PathBuf>,
+    /// Retries of failed API requests and polls of pending exports (`[figma.retry]`).
+    pub retry: FigmaRetryPolicy,
+}
+
+impl FigmaConfig {
+    /// The cache Figma renders should use.
+    pub fn cache(&self) -> FigmaCache {
+        FigmaCache::new(self.cache, self.cache_dir.clone())
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if self.retry.max_attempts == 0 {
+            return Err("figma retry max_attempts must be at least 1".to_string());
+        }
+        Ok(())
+    }
+}
+
+/// Device emulation for URL renders (`[device]`): a named preset and/or explicit
+/// settings, which override the preset's.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DeviceConfig {
+    /// Built-in device name (e.g. `iphone-14`); its viewport replaces `viewport`.
+    pub preset: Option<String>,
+    pub device_scale_factor: Option<f64>,
+    pub is_mobile: Option<bool>,
+    pub has_touch: Option<bool>,
+    pub user_agent: Option<String>,
+    pub color_scheme: Option<ColorScheme>,
+    pub reduced_motion: Option<ReducedMotion>,
+}
+
+impl DeviceConfig {
+    /// The selected preset, if any (`None` for unknown names too; see [`Self::validate`]).
+    pub fn preset(&self) -> Option<&'static DevicePreset> {
+        self.preset.as_deref().and_then(device_preset)
+    }
+
+    /// Preset settings (or a 1x desktop) with the explicit fields applied on top.
+    pub fn emulation(&self) -> DeviceEmulation {
+        let mut emulation = self
+            .preset()
+            .map(DevicePreset::emulation)
+            .unwrap_or_default();
+        if let Some(scale) = self.device_scale_factor {
+            emulation.device_scale_factor = scale;
+        }
+        if let Some(mobile) = self.is_mobile {
+            emulation.is_mobile = mobile;
+        }
+        if let Some(touch) = self.has_touch {
+            emulation.has_touch = touch;
+        }
+        if let Some(agent) = &self.user_agent {
+            emulation.user_agent = Some(agent.clone());
+        }
+        emulation.color_scheme = self.color_scheme.or(emulation.color_scheme);
+        emulation.reduced_motion = self.reduced_motion.or(emulation.reduced_motion);
+        emulation
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(name) = &self.preset {
+            if device_preset(name).is_none() {
+                let known: Vec<&str> = DEVICE_PRESETS.iter().map(|preset| preset.name).collect();
+                return Err(format!(
+                    "unknown device preset '{name}' (known: {})",
+                    known.join(", ")
+                ));
+            }
+        }
+        if let Some(scale) = self.device_scale_factor {
+            if !(scale > 0.0 && scale <= 10.0) {
+                return Err("device_scale_factor must be greater than 0 and at most 10".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A named viewport for responsive compares.
+///
+/// Figma inputs can point at a different frame per breakpoint, since designs usually keep
+/// Mobile/Tablet/Desktop as separate nodes.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Breakpoint {
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(deserialize_with = "deserialize_viewport")]
+    pub viewport: Viewport,
+    /// Figma node-id to use for the reference at this breakpoint (`1-2` or `1:2`).
+    #[serde(default)]
+    pub ref_node_id: Option<String>,
+    /// Figma node-id to use for the implementation at this breakpoint.
+    #[serde(default)]
+    pub impl_node_id: Option<String>,
+}
+
+impl Breakpoint {
+    /// Breakpoint with no name or node overrides.
+    pub fn from_viewport(viewport: Viewport) -> Self {
+        Self {
+            name: None,
+            viewport,
+            ref_node_id: None,
+            impl_node_id: None,
+        }
+    }
+
+    /// Display name: the configured name, or `WIDTHxHEIGHT`.
+    pub fn label(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("{}x{}", self.viewport.width, self.viewport.height))
+    }
+}
+
+/// An element compared once per interactive state.
+///
+/// Designs keep hover, focus and pressed looks as variants of one component, so each state
+/// can point at its own Figma node on either side.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct StateTarget {
+    #[serde(default)]
+    pub name: Option<String>,
+    /// CSS selector of the element on URL and HTML inputs, for both sides.
+    #[serde(default)]
+    pub selector: Option<String>,
+    /// Selector for the reference only; overrides `selector`.
+    #[serde(default)]
+    pub ref_selector: Option<String>,
+    /// Selector for the implementation only; overrides `selector`.
+    #[serde(default)]
+    pub impl_selector: Option<String>,
+    /// States to capture, in order (default: rest, hover, focus, active).
+    #[serde(default = "all_states")]
+    pub states: Vec<InteractiveState>,
+    /// Figma node-id of the reference variant per state (e.g. `hover = "12-40"`).
+    #[serde(default)]
+    pub ref_node_ids: BTreeMap<InteractiveState, String>,
+    /// Figma node-id of the implementation variant per state.
+    #[serde(default)]
+    pub impl_node_ids: BTreeMap<InteractiveState, String>,
+}
+
+fn all_states() -> Vec<InteractiveState> {
+    InteractiveState::ALL.to_vec()
+}
+
+impl StateTarget {
+    /// Target with the given states and no selectors or node overrides.
+    pub fn from_states(states: Vec<InteractiveState>) -> Self {
+        Self {
+            name: None,
+            selector: None,
+            ref_selector: None,
+            impl_selector: None,
+            states,
+            ref_node_ids: BTreeMap::new(),
+            impl_node_ids: BTreeMap::new(),
+        }
+    }
+
+    /// Display name: the configured name, else the first selector set, else `element`.
+    pub fn label(&self) -> String {
+        self.name
+            .as_ref()
+            .or(self.selector.as_ref())
+            .or(self.impl_selector.as_ref())
+            .or(self.ref_selector.as_ref())
+            .cloned()
+            .unwrap_or_else(|| "element".to_string())
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
@@ -85,11 +396,20 @@ impl Default for Config {
             metric_weights: MetricWeights::default(),
             timeouts: Timeouts::default(),
             semantic: SemanticConfig::default(),
+            breakpoints: Vec::new(),
+            browser: BrowserSettings::default(),
+            auth: AuthConfig::default(),
+            steps: Vec::new(),
+            device: DeviceConfig::default(),
+            capture: CaptureConfig::default(),
+            states: Vec::new(),
+            figma: FigmaConfig::default(),
         }
     }
 }
 
-fn deserialize_viewport<'de, D>(deserializer: D) -> Result<Viewport, D::Error>
+/// Deserialize a viewport from either `"WIDTHxHEIGHT"` or `{ width, height }`.
+pub fn deserialize_viewport<'de, D>(deserializer: D) -> Result<Viewport, D::Error>
 where
     D: Deserializer<'de>,
 {
@@ -113,6 +433,17 @@ where
     }
 }
 
+/// Optional counterpart of [`deserialize_viewport`] for fields that fall back to another default.
+pub fn deserialize_optional_viewport<'de, D>(deserializer: D) -> Result<Option<Viewport>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Wrapper(#[serde(deserialize_with = "deserialize_viewport")] Viewport);
+
+    Option::<Wrapper>::deserialize(deserializer).map(|w| w.map(|Wrapper(v)| v))
+}
+
 impl Config {
     /// Returns the path to the central config file (~/.config/dpc/config.toml).
     /// Returns None if the home directory cannot be determined.
@@ -227,14 +558,38 @@ impl Config {
         if self.viewport.width == 0 || self.viewport.height == 0 {
             return Err("viewport width and height must be greater than zero".to_string());
         }
+        self.device.validate()?;
+        self.capture.validate()?;
+        self.figma.validate()?;
+        let mut labels = std::collections::HashSet::new();
+        for bp in &self.breakpoints {
+            if !labels.insert(bp.label()) {
+                return Err(format!("duplicate breakpoint '{}'", bp.label()));
+            }
+        }
+        let mut labels = std::collections::HashSet::new();
+        for target in &self.states {
+            if target.states.is_empty() {
+                return Err(format!("states entry '{}' lists no states", target.label()));
+            }
+            if !labels.insert(target.label()) {
+                return Err(format!("duplicate states entry '{}'", target.label()));
+            }
+        }
         Ok(())
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{Config, MetricWeights, SemanticConfig, Timeouts};
-    use crate::Viewport;
+    use super::{
+        AuthConfig, Breakpoint, BrowserSettings, Config, DeviceConfig, MetricWeights,
+        SemanticConfig, StateTarget, Timeouts,
+    };
+    use crate::browser::{ColorScheme, InteractiveState, ReducedMotion, DEFAULT_SCROLL_STEP};
+    use crate::figma::{FigmaCacheMode, FigmaRetryPolicy};
+    use crate::{BrowserBackend, BrowserEngine, Viewport};
+    use std::path::{Path, PathBuf};
     use std::time::Duration;
 
     #[test]
@@ -272,6 +627,14 @@ mod tests {
                 process: Duration::from_secs(60),
             },
             semantic: SemanticConfig::default(),
+            breakpoints: Vec::new(),
+            browser: BrowserSettings::default(),
+            auth: Default::default(),
+            steps: Vec::new(),
+            device: DeviceConfig::default(),
+            capture: Default::default(),
+            states: Vec::new(),
+            figma: Default::default(),
         };
 
         assert_eq!(cfg.viewport.width, 1280);
@@ -286,8 +649,10 @@ mod tests {
 
     #[test]
     fn validate_rejects_bad_threshold_and_weights() {
-        let mut cfg = Config::default();
-        cfg.threshold = -0.1;
+        let mut cfg = Config {
+            threshold: -0.1,
+            ..Config::default()
+        };
         assert!(cfg.validate().is_err());
 
         cfg.threshold = 0.9;
@@ -366,4 +731,239 @@ process = "15s"
 
         assert!(cfg.validate().is_err());
     }
+
+    #[test]
+    fn load_from_toml_reads_breakpoints() {
+        let tmp = tempfile::Builder::new()
+            .suffix(".toml")
+            .tempfile()
+            .expect("temp file");
+        std::fs::write(
+            tmp.path(),
+            r#"
+[[breakpoints]]
+name = "mobile"
+viewport = "375x812"
+ref_node_id = "1-2"
+
+[[breakpoints]]
+viewport = { width = 1440, height = 900 }
+"#,
+        )
+        .unwrap();
+
+        let cfg = Config::from_toml_file(tmp.path()).expect("load config");
+        assert_eq!(cfg.breakpoints.len(), 2);
+        assert_eq!(cfg.breakpoints[0].label(), "mobile");
+        assert_eq!(cfg.breakpoints[0].ref_node_id.as_deref(), Some("1-2"));
+        assert_eq!(cfg.breakpoints[1].label(), "1440x900");
+        assert!(cfg.validate().is_ok());
+    }
+
+    #[test]
+    fn validate_rejects_duplicate_breakpoints() {
+        let viewport = Viewport {
+            width: 375,
+            height: 812,
+        };
+        let cfg = Config {
+            breakpoints: vec![
+                Breakpoint::from_viewport(viewport),
+                Breakpoint::from_viewport(viewport),
+            ],
+            ..Config::default()
+        };
+
+        assert!(cfg.validate().is_err());
+    }
+
+    #[test]
+    fn load_from_toml_reads_state_targets() {
+        let tmp = tempfile::Builder::new()
+            .suffix(".toml")
+            .tempfile()
+            .expect("temp file");
+        std::fs::write(
+            tmp.path(),
+            r#"
+[[states]]
+name = "primary-button"
+impl_selector = ".btn-primary"
+states = ["hover", "focus"]
+ref_node_ids = { hover = "12-40", focus = "12-41" }
+
+[[states]]
+selector = "a.nav-link"
+"#,
+        )
+        .unwrap();
+
+        let cfg = Config::from_toml_file(tmp.path()).expect("load config");
+        assert_eq!(cfg.states.len(), 2);
+        let button = &cfg.states[0];
+        assert_eq!(button.label(), "primary-button");
+        assert_eq!(
+            button.states,
+            vec![InteractiveState::Hover, InteractiveState::Focus]
+        );
+        assert_eq!(
+            button
+                .ref_node_ids
+                .get(&InteractiveState::Focus)
+                .map(String::as_str),
+            Some("12-41")
+        );
+        assert_eq!(cfg.states[1].label(), "a.nav-link");
+        assert_eq!(cfg.states[1].states, InteractiveState::ALL.to_vec());
+        assert!(cfg.validate().is_ok());
+
+        let cfg = Config {
+            states: vec![StateTarget::from_states(Vec::new())],
+            ..Config::default()
+        };
+        assert!(cfg.validate().is_err());
+    }
+
+    #[test]
+    fn load_from_toml_reads_figma_cache() {
+        let tmp = tempfile::Builder::new()
+            .suffix(".toml")
+            .tempfile()
+            .expect("temp file");
+        std::fs::write(
+            tmp.path(),
+            "[figma]\ncache = \"offline\"\ncache_dir = \"/tmp/dpc-figma\"\n",
+        )
+        .unwrap();
+
+        let cfg = Config::from_toml_file(tmp.path()).expect("load config");
+        let cache = cfg.figma.cache();
+        assert_eq!(cache.mode, FigmaCacheMode::Offline);
+        assert_eq!(cache.dir, Some(PathBuf::from("/tmp/dpc-figma")));
+        assert_eq!(Config::default().figma.cache, FigmaCacheMode::Read);
+    }
+
+    #[test]
+    fn load_from_toml_reads_figma_retry() {
+        let tmp = tempfile::Builder::new()
+            .suffix(".toml")
+            .tempfile()
+            .expect("temp file");
+        std::fs::write(
+            tmp.path(),
+            "[figma.retry]\nmax_attempts = 6\nbackoff = \"500ms\"\nexport_poll_interval = \"5s\"\n",
+        )
+        .unwrap();
+
+        let cfg = Config::from_toml_file(tmp.path()).expect("load config");
+        let retry = &cfg.figma.retry;
+        assert_eq!(retry.max_attempts, 6);
+        assert_eq!(retry.backoff, Duration::from_millis(500));
+        assert_eq!(retry.export_poll_interval, Duration::from_secs(5));
+        assert_eq!(retry.max_backoff, FigmaRetryPolicy::default().max_backoff);
+        cfg.validate().expect("valid retry policy");
+
+        let mut bad = cfg.clone();
+        bad.figma.retry.max_attempts = 0;
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn load_from_toml_reads_browser_backend() {
+        let tmp = tempfile::Builder::new()
+            .suffix(".toml")
+            .tempfile()
+            .expect("temp file");
+        std::fs::write(
+            tmp.path(),
+            r#"
+[browser]
+backend = "cdp"
+engine = "webkit"
+chrome_path = "/usr/bin/chromium"
+deterministic = true
+"#,
+        )
+        .unwrap();
+
+        let cfg = Config::from_toml_file(tmp.path()).expect("load config");
+        assert_eq!(cfg.browser.backend, BrowserBackend::Cdp);
+        assert_eq!(cfg.browser.engine, BrowserEngine::Webkit);
+        assert_eq!(
+            cfg.browser.chrome_path.as_deref(),
+            Some(Path::new("/usr/bin/chromium"))
+        );
+        assert!(cfg.browser.deterministic);
+        assert_eq!(Config::default().browser.backend, BrowserBackend::Node);
+        assert!(!Config::default().browser.deterministic);
+    }
+
+    #[test]
+    fn device_section_applies_overrides_over_the_preset() {
+        let cfg: Config = toml::from_str(
+            r#"
+[device]
+preset = "iphone-14"
+device_scale_factor = 2.0
+color_scheme = "dark"
+reduced_motion = "reduce"
+"#,
+        )
+        .expect("parse device");
+        cfg.validate().expect("valid device");
+        let emulation = cfg.device.emulation();
+        assert_eq!(emulation.device_scale_factor, 2.0);
+        assert!(emulation.is_mobile);
+        assert_eq!(emulation.color_scheme, Some(ColorScheme::Dark));
+        assert_eq!(emulation.reduced_motion, Some(ReducedMotion::Reduce));
+
+        let bad: Config = toml::from_str("[device]\ndevice_scale_factor = 0.0\n").unwrap();
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn capture_section_enables_auto_scroll_with_defaults() {
+        let cfg: Config = toml::from_str(
+            r#"
+[capture]
+full_page = true
+auto_scroll = true
+scroll_settle = "100ms"
+"#,
+        )
+        .expect("parse capture");
+        cfg.validate().expect("valid capture");
+        assert!(cfg.capture.full_page);
+        let scroll = cfg.capture.auto_scroll().expect("auto-scroll on");
+        assert_eq!(scroll.step, DEFAULT_SCROLL_STEP);
+        assert_eq!(scroll.settle, Duration::from_millis(100));
+        assert!(Config::default().capture.auto_scroll().is_none());
+
+        let bad: Config = toml::from_str("[capture]\nscroll_step = 0\n").unwrap();
+        assert!(bad.validate().is_err());
+    }
+
+    #[test]
+    fn auth_section_resolves_env_secrets() {
+        let cfg: Config = toml::from_str(
+            r#"
+[auth]
+headers = { Authorization = "env:DPC_TEST_CONFIG_TOKEN", "X-Env" = "staging" }
+basic_auth = "admin:env:DPC_TEST_CONFIG_TOKEN"
+"#,
+        )
+        .unwrap();
+        std::env::set_var("DPC_TEST_CONFIG_TOKEN", "t0k3n");
+
+        let auth = cfg.auth.resolve().expect("resolve auth");
+        assert_eq!(auth.headers["Authorization"], "t0k3n");
+        assert_eq!(auth.headers["X-Env"], "staging");
+        assert_eq!(auth.basic_auth.unwrap().password, "t0k3n");
+
+        let missing_state = AuthConfig {
+            storage_state: Some("missing-state.json".into()),
+            ..AuthConfig::default()
+        };
+        assert!(missing_state.resolve().is_err());
+    }
 }
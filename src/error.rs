@@ -33,6 +33,15 @@ pub enum DpcError {
     #[error("Configuration error: {0}")]
     Config(String),
 
+    #[error("Interaction step {index} ({step}) failed on {url}: {message}")]
+    InteractionStep {
+        /// 1-based position in the step list.
+        index: usize,
+        step: String,
+        url: String,
+        message: String,
+    },
+
     #[error("Unexpected error: {0}")]
     Unknown(String),
 }
@@ -100,6 +109,24 @@ impl DpcError {
                         msg.to_string(),
                         "Run `npx playwright install chromium` (or `playwright install chromium`) to download the browser.",
                     )
+                } else if lower.contains("firefox executable") {
+                    ErrorPayload::new(
+                        ErrorCategory::Config,
+                        msg.to_string(),
+                        "Run `npx playwright install firefox` to download Playwright's Firefox build; a system Firefox cannot be used.",
+                    )
+                } else if lower.contains("webkit executable") {
+                    ErrorPayload::new(
+                        ErrorCategory::Config,
+                        msg.to_string(),
+                        "Run `npx playwright install webkit`; on Linux use `npx playwright install --with-deps webkit` to also install the system libraries WebKit needs.",
+                    )
+                } else if lower.contains("browser executable is missing") {
+                    ErrorPayload::new(
+                        ErrorCategory::Config,
+                        msg.to_string(),
+                        "Run `npx playwright install` to download the browsers, or only the one passed to --browser.",
+                    )
                 } else if lower.contains("figma_token") || lower.contains("figma token") {
                     ErrorPayload::new(
                         ErrorCategory::Config,
@@ -140,14 +167,14 @@ impl DpcError {
                     ErrorPayload::new(
                         ErrorCategory::Config,
                         msg.to_string(),
-                        "Use a supported image type (png, jpg, jpeg, webp, gif) or override type with --ref-type/--impl-type.",
+                        "Use a supported image type (png, jpg, jpeg, webp, gif), an HTML file (html, htm) or a directory with index.html, or override type with --ref-type/--impl-type.",
                     )
                 } else if lower.contains("local file not found") || lower.contains("file not found")
                 {
                     ErrorPayload::new(
                         ErrorCategory::Config,
                         msg.to_string(),
-                        "Verify the file exists; use an absolute path or run from the working directory, and ensure the extension is supported (png, jpg, jpeg, webp, gif).",
+                        "Verify the file exists; use an absolute path or run from the working directory, and ensure the extension is supported (png, jpg, jpeg, webp, gif, html, htm).",
                     )
                 } else {
                     ErrorPayload::new(
@@ -157,6 +184,11 @@ impl DpcError {
                     )
                 }
             }
+            DpcError::InteractionStep { .. } => ErrorPayload::new(
+                ErrorCategory::Interaction,
+                self.to_string(),
+                "Check the selector matches after navigation; add a wait_for_selector step before it, and rerun with --verbose for details.",
+            ),
             DpcError::Unknown(msg) => ErrorPayload::new(
                 ErrorCategory::Unknown,
                 msg.to_string(),
@@ -189,6 +221,7 @@ pub enum ErrorCategory {
     Figma,
     Image,
     Metric,
+    Interaction,
     Unknown,
 }
 
@@ -318,13 +351,36 @@ mod tests {
     #[test]
     fn config_payload_lists_supported_extensions_for_unsupported_extension() {
         let err = DpcError::Config(
-            "Unsupported file extension 'bmp'. Supported image extensions: png, jpg, jpeg, webp, gif.".to_string(),
+            "Unsupported file extension 'bmp'. Supported extensions: png, jpg, jpeg, webp, gif, html, htm, or a directory with index.html.".to_string(),
         );
         let remediation = err.to_payload().remediation.unwrap_or_default();
         assert!(
             remediation.contains("png") && remediation.contains("gif"),
             "expected remediation to list supported extensions, got: {remediation}"
         );
+        assert!(
+            remediation.contains("index.html"),
+            "expected remediation to mention HTML inputs, got: {remediation}"
+        );
+    }
+
+    #[test]
+    fn config_payload_includes_per_engine_install_hints() {
+        let webkit = DpcError::Config(
+            "Playwright webkit executable is missing; install it with `npx playwright install webkit`."
+                .to_string(),
+        );
+        let remediation = webkit.to_payload().remediation.unwrap_or_default();
+        assert!(
+            remediation.contains("--with-deps webkit"),
+            "expected webkit install hint, got: {remediation}"
+        );
+        let firefox = DpcError::Config("firefox executable is missing".to_string());
+        let remediation = firefox.to_payload().remediation.unwrap_or_default();
+        assert!(
+            remediation.contains("playwright install firefox"),
+            "expected firefox install hint, got: {remediation}"
+        );
     }
 
     #[test]
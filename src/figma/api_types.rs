@@ -33,6 +33,16 @@ pub struct FigmaFile {
     pub document: FigmaDocument,
 }
 
+/// Version stamp of a Figma file, from the files endpoint at `depth=1`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FigmaFileVersion {
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub last_modified: String,
+}
+
 /// The root document of a Figma file.
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -115,13 +125,13 @@ impl FigmaColor {
 }
 
 /// Response from the nodes endpoint.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct FigmaNodesResponse {
     pub nodes: HashMap<String, FigmaNodeWrapper>,
 }
 
 /// Wrapper containing the document for a node.
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct FigmaNodeWrapper {
     pub document: FigmaNodeData,
 }
@@ -129,5 +139,6 @@ pub struct FigmaNodeWrapper {
 /// Response from the images export endpoint.
 #[derive(Debug, Deserialize)]
 pub struct FigmaImageExport {
-    pub images: HashMap<String, String>,
+    /// Image URL per node id; `None` while the render is still pending.
+    pub images: HashMap<String, Option<String>>,
 }
@@ -0,0 +1,303 @@
+//! On-disk cache of Figma node documents and exported PNGs.
+//!
+//! Entries live under `<dir>/<file key>/<file version>/<node id>@<scale>x/` as `node.json`
+//! (the node document), `frame.png` (the export) and `meta.json` (what the entry is for).
+//! Because the file version is part of the path, any edit to the design yields new entries
+//! instead of stale hits; finding out the current version costs one light request.
+
+use super::api_types::{FigmaFileVersion, FigmaNodeData};
+use crate::{DpcError, Result};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// How Figma renders use the cache.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum FigmaCacheMode {
+    /// Always fetch; never read or write the cache.
+    Off,
+    /// Check the file version, reuse a cached export of that version, fetch and store
+    /// otherwise.
+    #[default]
+    Read,
+    /// Always fetch and overwrite the cached entry.
+    Refresh,
+    /// Never touch the network: use the newest cached version of the node, and fail when
+    /// there is none. No Figma token is needed.
+    Offline,
+}
+
+impl FigmaCacheMode {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            FigmaCacheMode::Off => "off",
+            FigmaCacheMode::Read => "read",
+            FigmaCacheMode::Refresh => "refresh",
+            FigmaCacheMode::Offline => "offline",
+        }
+    }
+}
+
+/// Where the cache lives and how it is used.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FigmaCache {
+    pub mode: FigmaCacheMode,
+    /// Cache root; `None` when no cache directory could be determined.
+    pub dir: Option<PathBuf>,
+}
+
+impl Default for FigmaCache {
+    /// No caching, so library callers opt in to disk writes.
+    fn default() -> Self {
+        Self {
+            mode: FigmaCacheMode::Off,
+            dir: None,
+        }
+    }
+}
+
+/// A node document and its exported PNG, as fetched or read from the cache.
+#[derive(Debug)]
+pub(crate) struct FigmaFrame {
+    pub node: FigmaNodeData,
+    pub png: Vec<u8>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct EntryMeta {
+    file_key: String,
+    node_id: String,
+    scale: f32,
+    version: String,
+    last_modified: String,
+}
+
+impl FigmaCache {
+    /// `dpc/figma` under the user's cache directory (e.g. `~/.cache/dpc/figma`).
+    pub fn default_dir() -> Option<PathBuf> {
+        dirs::cache_dir().map(|dir| dir.join("dpc").join("figma"))
+    }
+
+    /// Cache in `mode` at `dir`, or at [`Self::default_dir`] when `dir` is `None`.
+    pub fn new(mode: FigmaCacheMode, dir: Option<PathBuf>) -> Self {
+        Self {
+            mode,
+            dir: dir.or_else(Self::default_dir),
+        }
+    }
+
+    /// Whether fetches should check the file version and consult the cache.
+    pub(crate) fn is_online(&self) -> bool {
+        matches!(self.mode, FigmaCacheMode::Read | FigmaCacheMode::Refresh) && self.dir.is_some()
+    }
+
+    /// The cached entry for `version`, in [`FigmaCacheMode::Read`] only.
+    pub(crate) fn load(
+        &self,
+        file_key: &str,
+        node_id: &str,
+        scale: f32,
+        version: &FigmaFileVersion,
+    ) -> Option<FigmaFrame> {
+        if self.mode != FigmaCacheMode::Read {
+            return None;
+        }
+        let dir = self.file_dir(file_key)?.join(version_dir(version));
+        read_entry(&dir.join(entry_name(node_id, scale)))
+    }
+
+    /// The newest cached version of a node, for [`FigmaCacheMode::Offline`]; misses are
+    /// errors naming the cache directory.
+    pub(crate) fn load_offline(
+        &self,
+        file_key: &str,
+        node_id: &str,
+        scale: f32,
+    ) -> Result<FigmaFrame> {
+        let miss = || {
+            DpcError::Config(format!(
+                "Figma node {node_id} of file {file_key} (scale {scale}) is not in the cache{}; offline mode never fetches, so run once with --figma-cache read to fill it",
+                self.dir
+                    .as_ref()
+                    .map(|dir| format!(" at {}", dir.display()))
+                    .unwrap_or_default()
+            ))
+        };
+        let file_dir = self.file_dir(file_key).ok_or_else(miss)?;
+        let name = entry_name(node_id, scale);
+        let newest = fs::read_dir(&file_dir)
+            .into_iter()
+            .flatten()
+            .flatten()
+            .map(|version| version.path().join(&name))
+            .filter_map(|entry| {
+                let meta: EntryMeta =
+                    serde_json::from_slice(&fs::read(entry.join("meta.json")).ok()?).ok()?;
+                Some((meta.last_modified, entry))
+            })
+            .max_by(|a, b| a.0.cmp(&b.0));
+        newest
+            .and_then(|(_, entry)| read_entry(&entry))
+            .ok_or_else(miss)
+    }
+
+    /// Save a fetched frame. Failures are returned for the caller to ignore: a cache that
+    /// cannot be written only costs the next run a fetch.
+    pub(crate) fn store(
+        &self,
+        file_key: &str,
+        node_id: &str,
+        scale: f32,
+        version: &FigmaFileVersion,
+        frame: &FigmaFrame,
+    ) -> Result<()> {
+        let Some(file_dir) = self.file_dir(file_key) else {
+            return Ok(());
+        };
+        let entry = file_dir
+            .join(version_dir(version))
+            .join(entry_name(node_id, scale));
+        fs::create_dir_all(&entry)?;
+        let meta = EntryMeta {
+            file_key: file_key.to_string(),
+            node_id: node_id.to_string(),
+            scale,
+            version: version.version.clone(),
+            last_modified: version.last_modified.clone(),
+        };
+        // meta.json goes last: entries without it are incomplete and never read.
+        write_atomic(&entry.join("node.json"), &serde_json::to_vec(&frame.node)?)?;
+        write_atomic(&entry.join("frame.png"), &frame.png)?;
+        write_atomic(&entry.join("meta.json"), &serde_json::to_vec_pretty(&meta)?)?;
+        Ok(())
+    }
+
+    fn file_dir(&self, file_key: &str) -> Option<PathBuf> {
+        self.dir
+            .as_ref()
+            .map(|dir| dir.join(path_segment(file_key)))
+    }
+}
+
+fn read_entry(entry: &Path) -> Option<FigmaFrame> {
+    if !entry.join("meta.json").is_file() {
+        return None;
+    }
+    let node = serde_json::from_slice(&fs::read(entry.join("node.json")).ok()?).ok()?;
+    let png = fs::read(entry.join("frame.png")).ok()?;
+    Some(FigmaFrame { node, png })
+}
+
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    let partial = path.with_extension("partial");
+    fs::write(&partial, bytes)?;
+    fs::rename(&partial, path)?;
+    Ok(())
+}
+
+/// The file's version id, or its last-modified time for files that report no version.
+fn version_dir(version: &FigmaFileVersion) -> String {
+    if version.version.is_empty() {
+        path_segment(&version.last_modified)
+    } else {
+        path_segment(&version.version)
+    }
+}
+
+fn entry_name(node_id: &str, scale: f32) -> String {
+    format!("{}@{scale}x", path_segment(&node_id.replace(':', "-")))
+}
+
+fn path_segment(value: &str) -> String {
+    value
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn frame(name: &str) -> FigmaFrame {
+        FigmaFrame {
+            node: serde_json::from_value(serde_json::json!({
+                "id": "1:2",
+                "name": name,
+                "type": "FRAME"
+            }))
+            .unwrap(),
+            png: vec![1, 2, 3],
+        }
+    }
+
+    fn version(version: &str, last_modified: &str) -> FigmaFileVersion {
+        FigmaFileVersion {
+            version: version.to_string(),
+            last_modified: last_modified.to_string(),
+        }
+    }
+
+    #[test]
+    fn entries_are_keyed_by_version_node_and_scale() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = FigmaCache::new(FigmaCacheMode::Read, Some(dir.path().to_path_buf()));
+        let v1 = version("101", "2024-05-01T10:00:00Z");
+        cache
+            .store("FILE", "1:2", 1.0, &v1, &frame("Home"))
+            .unwrap();
+
+        let hit = cache.load("FILE", "1:2", 1.0, &v1).expect("cache hit");
+        assert_eq!((hit.node.name.as_str(), hit.png), ("Home", vec![1, 2, 3]));
+        assert!(cache.load("FILE", "1:2", 2.0, &v1).is_none());
+        assert!(cache.load("FILE", "1:3", 1.0, &v1).is_none());
+        assert!(cache
+            .load("FILE", "1:2", 1.0, &version("102", "2024-05-02T10:00:00Z"))
+            .is_none());
+        assert!(dir.path().join("FILE/101/1-2@1x/frame.png").is_file());
+
+        let refresh = FigmaCache {
+            mode: FigmaCacheMode::Refresh,
+            ..cache.clone()
+        };
+        assert!(refresh.load("FILE", "1:2", 1.0, &v1).is_none());
+    }
+
+    #[test]
+    fn offline_reads_the_newest_version_and_reports_misses() {
+        let dir = tempfile::tempdir().unwrap();
+        let cache = FigmaCache::new(FigmaCacheMode::Offline, Some(dir.path().to_path_buf()));
+        cache
+            .store(
+                "FILE",
+                "1:2",
+                1.0,
+                &version("9", "2024-05-01T10:00:00Z"),
+                &frame("Old"),
+            )
+            .unwrap();
+        cache
+            .store(
+                "FILE",
+                "1:2",
+                1.0,
+                &version("10", "2024-06-01T10:00:00Z"),
+                &frame("New"),
+            )
+            .unwrap();
+
+        let newest = cache.load_offline("FILE", "1:2", 1.0).unwrap();
+        assert_eq!(newest.node.name, "New");
+
+        let err = cache.load_offline("FILE", "7:7", 1.0).unwrap_err();
+        assert!(err.to_string().contains("--figma-cache read"), "got: {err}");
+    }
+}
@@ -1,12 +1,17 @@
 //! Figma API client for fetching file data and exporting images.
+//!
+//! Requests are retried according to the client's [`FigmaRetryPolicy`].
 
 use crate::figma_client::FigmaAuth;
 use crate::DpcError;
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION};
+use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, RETRY_AFTER};
+use reqwest::StatusCode;
 use serde::Deserialize;
+use std::time::Duration;
 use thiserror::Error;
 
-use super::api_types::{FigmaImageExport, FigmaNodesResponse, ImageFormat};
+use super::api_types::{FigmaFileVersion, FigmaImageExport, FigmaNodesResponse, ImageFormat};
+use super::retry::FigmaRetryPolicy;
 
 #[derive(Debug, Error)]
 pub enum FigmaError {
@@ -22,6 +27,8 @@ pub enum FigmaError {
     NodeNotFound(String),
     #[error("Rate limited, retry after {0} seconds")]
     RateLimited(u64),
+    #[error("Image export for node {0} is still rendering")]
+    ExportPending(String),
 }
 
 pub fn map_figma_error(e: FigmaError) -> DpcError {
@@ -49,6 +56,13 @@ pub fn map_figma_error(e: FigmaError) -> DpcError {
             status: Some(reqwest::StatusCode::TOO_MANY_REQUESTS),
             message: format!("Rate limited, retry after {} seconds", secs),
         },
+        FigmaError::ExportPending(id) => DpcError::FigmaApi {
+            status: None,
+            message: format!(
+                "Figma did not finish rendering node {} in time; try again or raise figma.retry.export_polls",
+                id
+            ),
+        },
     }
 }
 
@@ -57,6 +71,7 @@ pub struct FigmaClient {
     client: reqwest::Client,
     access_token: String,
     base_url: String,
+    retry: FigmaRetryPolicy,
 }
 
 impl FigmaClient {
@@ -107,15 +122,34 @@ impl FigmaClient {
             client,
             access_token: token,
             base_url: base_url.into(),
+            retry: FigmaRetryPolicy::default(),
         })
     }
 
+    /// Replace the default retry policy.
+    pub fn with_retry(mut self, retry: FigmaRetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
     pub async fn get_file(
         &self,
         file_key: &str,
     ) -> std::result::Result<super::api_types::FigmaFile, FigmaError> {
         let url = format!("{}/files/{}", self.base_url, file_key);
-        let response = self.client.get(&url).send().await?;
+        let response = self.get(&url).await?;
+
+        self.handle_response(response).await
+    }
+
+    /// The file's current version, without its node tree (`depth=1` keeps the response
+    /// small); used to key the export cache.
+    pub async fn get_file_version(
+        &self,
+        file_key: &str,
+    ) -> std::result::Result<FigmaFileVersion, FigmaError> {
+        let url = format!("{}/files/{}?depth=1", self.base_url, file_key);
+        let response = self.get(&url).await?;
 
         self.handle_response(response).await
     }
@@ -127,7 +161,7 @@ impl FigmaClient {
     ) -> std::result::Result<FigmaNodesResponse, FigmaError> {
         let ids = node_ids.join(",");
         let url = format!("{}/files/{}/nodes?ids={}", self.base_url, file_key, ids);
-        let response = self.client.get(&url).send().await?;
+        let response = self.get(&url).await?;
 
         self.handle_response(response).await
     }
@@ -148,18 +182,25 @@ impl FigmaClient {
             scale
         );
 
-        let response = self.client.get(&url).send().await?;
-        let export: FigmaImageExport = self.handle_response(response).await?;
-
-        export
-            .images
-            .get(node_id)
-            .cloned()
-            .ok_or_else(|| FigmaError::NodeNotFound(node_id.to_string()))
+        // The URL is null until the render finishes; ask again until it is there.
+        let mut polls = 0;
+        loop {
+            let response = self.get(&url).await?;
+            let export: FigmaImageExport = self.handle_response(response).await?;
+            match export.images.get(node_id) {
+                Some(Some(image_url)) => return Ok(image_url.clone()),
+                Some(None) if polls < self.retry.export_polls => {
+                    polls += 1;
+                    tokio::time::sleep(self.retry.export_poll_interval).await;
+                }
+                Some(None) => return Err(FigmaError::ExportPending(node_id.to_string())),
+                None => return Err(FigmaError::NodeNotFound(node_id.to_string())),
+            }
+        }
     }
 
     pub async fn download_image(&self, url: &str) -> std::result::Result<Vec<u8>, FigmaError> {
-        let response = self.client.get(url).send().await?;
+        let response = self.get(url).await?;
         if !response.status().is_success() {
             return Err(FigmaError::Api {
                 status: response.status().as_u16(),
@@ -169,19 +210,41 @@ impl FigmaClient {
         Ok(response.bytes().await?.to_vec())
     }
 
+    /// GET `url`, retrying rate limits, server errors, connection failures and timeouts
+    /// per the retry policy. The last response is returned as is once retries run out.
+    async fn get(&self, url: &str) -> std::result::Result<reqwest::Response, FigmaError> {
+        let mut attempt = 1;
+        loop {
+            let (retry_after, outcome) = match self.client.get(url).send().await {
+                Ok(response)
+                    if response.status() == StatusCode::TOO_MANY_REQUESTS
+                        || response.status().is_server_error() =>
+                {
+                    (
+                        retry_after_secs(response.headers()).map(Duration::from_secs),
+                        Ok(response),
+                    )
+                }
+                Ok(response) => return Ok(response),
+                Err(err) if err.is_connect() || err.is_timeout() => (None, Err(err)),
+                Err(err) => return Err(err.into()),
+            };
+            match self.retry.delay(attempt, retry_after) {
+                Some(wait) => tokio::time::sleep(wait).await,
+                None => return Ok(outcome?),
+            }
+            attempt += 1;
+        }
+    }
+
     async fn handle_response<T: for<'de> Deserialize<'de>>(
         &self,
         response: reqwest::Response,
     ) -> std::result::Result<T, FigmaError> {
         let status = response.status();
 
-        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
-            let retry_after = response
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(60);
+        if status == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = retry_after_secs(response.headers()).unwrap_or(60);
             return Err(FigmaError::RateLimited(retry_after));
         }
 
@@ -200,3 +263,11 @@ impl FigmaClient {
         &self.access_token
     }
 }
+
+/// `Retry-After` in seconds; the HTTP-date form is not used by Figma and is ignored.
+fn retry_after_secs(headers: &HeaderMap) -> Option<u64> {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|s| s.trim().parse().ok())
+}
@@ -0,0 +1,292 @@
+//! Offline Figma exports: a node document and its PNG saved to disk, so design snapshots
+//! can be versioned next to the code and compared without a token.
+//!
+//! An export is a directory holding `nodes.json` (a nodes endpoint response, the shape of
+//! [`FigmaNodesResponse`]), `frame.png` (the node's PNG export) and, when written by
+//! `dpc figma pull`, `source.json` (which file, node, scale and version it came from). A
+//! nodes JSON file can also be given on its own, with its PNG next to it under the same
+//! stem (`home.json` and `home.png`).
+
+use super::api_types::{FigmaNodeData, FigmaNodeWrapper, FigmaNodesResponse, ImageFormat};
+use super::cache::FigmaFrame;
+use super::client::{map_figma_error, FigmaClient};
+use super::{frame_to_normalized_view, FigmaRenderOptions};
+use crate::types::{NormalizedView, Viewport};
+use crate::{DpcError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+/// Nodes response file name inside an export directory.
+pub const NODES_FILE: &str = "nodes.json";
+/// PNG export file name inside an export directory.
+pub const IMAGE_FILE: &str = "frame.png";
+/// Provenance file name inside an export directory.
+pub const SOURCE_FILE: &str = "source.json";
+
+/// Where an export was pulled from (`source.json`).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FigmaExportSource {
+    pub file_key: String,
+    pub node_id: String,
+    pub scale: f32,
+    /// File version at pull time.
+    #[serde(default)]
+    pub version: String,
+    #[serde(default)]
+    pub last_modified: String,
+}
+
+/// True when `path` is a directory holding an export's `nodes.json`, or a `.json` file
+/// whose top-level object has a nodes response's `nodes` map.
+pub fn is_figma_export(path: &Path) -> bool {
+    if path.is_dir() {
+        return path.join(NODES_FILE).is_file();
+    }
+    let is_json = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    is_json
+        && fs::read(path)
+            .ok()
+            .and_then(|data| serde_json::from_slice::<serde_json::Value>(&data).ok())
+            .is_some_and(|value| value.get("nodes").is_some_and(serde_json::Value::is_object))
+}
+
+/// Build a NormalizedView from an export directory or nodes JSON file, exactly as a live
+/// Figma render of the same node would, writing the final screenshot to `output_path`.
+///
+/// The node is the one named in `source.json`, or the only node in the response.
+pub fn figma_export_to_normalized_view(
+    path: &Path,
+    output_path: &Path,
+    viewport: Option<Viewport>,
+) -> Result<NormalizedView> {
+    let (nodes_path, image_path, source_path) = if path.is_dir() {
+        (
+            path.join(NODES_FILE),
+            path.join(IMAGE_FILE),
+            Some(path.join(SOURCE_FILE)),
+        )
+    } else {
+        (path.to_path_buf(), path.with_extension("png"), None)
+    };
+
+    let data = fs::read(&nodes_path).map_err(|err| {
+        DpcError::Config(format!(
+            "Failed to read Figma export {}: {err}",
+            nodes_path.display()
+        ))
+    })?;
+    let response: FigmaNodesResponse = serde_json::from_slice(&data).map_err(|err| {
+        DpcError::Config(format!(
+            "Invalid Figma export {} (expected a Figma nodes response): {err}",
+            nodes_path.display()
+        ))
+    })?;
+    let source = match source_path.filter(|p| p.is_file()) {
+        Some(source_path) => Some(read_source(&source_path)?),
+        None => None,
+    };
+
+    let (node_id, node) = select_node(response.nodes, source.as_ref(), &nodes_path)?;
+    let png = fs::read(&image_path).map_err(|err| {
+        DpcError::Config(format!(
+            "Figma export {} is missing its PNG ({}): {err}",
+            nodes_path.display(),
+            image_path.display()
+        ))
+    })?;
+
+    let options = FigmaRenderOptions {
+        file_key: source
+            .as_ref()
+            .map(|s| s.file_key.clone())
+            .unwrap_or_default(),
+        node_id,
+        output_path: output_path.to_path_buf(),
+        viewport,
+        scale: source.as_ref().map_or(1.0, |s| s.scale),
+        ..Default::default()
+    };
+    frame_to_normalized_view(FigmaFrame { node, png }, &options)
+}
+
+/// Fetch a node and its PNG export at `scale` and write them as an export directory at
+/// `dir`, replacing files of an earlier pull.
+pub async fn pull_figma_export(
+    client: &FigmaClient,
+    file_key: &str,
+    node_id: &str,
+    scale: f32,
+    dir: &Path,
+) -> Result<FigmaExportSource> {
+    // The images endpoint rejects scales outside this range.
+    if !(0.01..=4.0).contains(&scale) {
+        return Err(DpcError::Config(format!(
+            "Figma export scale must be between 0.01 and 4, got {scale}"
+        )));
+    }
+    let version = client
+        .get_file_version(file_key)
+        .await
+        .map_err(map_figma_error)?;
+    let mut nodes = client
+        .get_file_nodes(file_key, &[node_id])
+        .await
+        .map_err(map_figma_error)?
+        .nodes;
+    let node = nodes.remove(node_id).ok_or_else(|| DpcError::FigmaApi {
+        status: None,
+        message: format!("Node {node_id} not found in Figma response"),
+    })?;
+    let image_url = client
+        .export_image(file_key, node_id, ImageFormat::Png, scale)
+        .await
+        .map_err(map_figma_error)?;
+    let png = client
+        .download_image(&image_url)
+        .await
+        .map_err(map_figma_error)?;
+
+    let source = FigmaExportSource {
+        file_key: file_key.to_string(),
+        node_id: node_id.to_string(),
+        scale,
+        version: version.version,
+        last_modified: version.last_modified,
+    };
+    let response = FigmaNodesResponse {
+        nodes: HashMap::from([(node_id.to_string(), node)]),
+    };
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(NODES_FILE), serde_json::to_vec_pretty(&response)?)?;
+    fs::write(dir.join(IMAGE_FILE), png)?;
+    fs::write(dir.join(SOURCE_FILE), serde_json::to_vec_pretty(&source)?)?;
+    Ok(source)
+}
+
+fn read_source(path: &Path) -> Result<FigmaExportSource> {
+    let data = fs::read(path)?;
+    serde_json::from_slice(&data).map_err(|err| {
+        DpcError::Config(format!(
+            "Invalid Figma export source {}: {err}",
+            path.display()
+        ))
+    })
+}
+
+fn select_node(
+    mut nodes: HashMap<String, FigmaNodeWrapper>,
+    source: Option<&FigmaExportSource>,
+    nodes_path: &Path,
+) -> Result<(String, FigmaNodeData)> {
+    let wanted = match source {
+        Some(source) => source.node_id.clone(),
+        None if nodes.len() == 1 => nodes.keys().next().cloned().unwrap_or_default(),
+        None => {
+            let mut ids: Vec<&str> = nodes.keys().map(String::as_str).collect();
+            ids.sort_unstable();
+            return Err(DpcError::Config(format!(
+                "Figma export {} must hold exactly one node, found {}{}",
+                nodes_path.display(),
+                ids.len(),
+                if ids.is_empty() {
+                    String::new()
+                } else {
+                    format!(" ({})", ids.join(", "))
+                }
+            )));
+        }
+    };
+    match nodes.remove(&wanted) {
+        Some(node) => Ok((wanted, node.document)),
+        None => Err(DpcError::Config(format!(
+            "Figma export {} has no node {wanted}",
+            nodes_path.display()
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbaImage;
+
+    fn write_nodes(path: &Path, ids: &[&str]) {
+        let nodes: serde_json::Map<String, serde_json::Value> = ids
+            .iter()
+            .map(|id| {
+                let document = serde_json::json!({
+                    "id": id,
+                    "name": format!("Frame {id}"),
+                    "type": "FRAME",
+                    "absoluteBoundingBox": { "x": 10.0, "y": 10.0, "width": 40.0, "height": 20.0 },
+                    "children": [{
+                        "id": "9:1",
+                        "name": "Title",
+                        "type": "TEXT",
+                        "characters": "Hello",
+                        "absoluteBoundingBox": { "x": 10.0, "y": 10.0, "width": 40.0, "height": 10.0 }
+                    }]
+                });
+                (id.to_string(), serde_json::json!({ "document": document }))
+            })
+            .collect();
+        let response = serde_json::json!({ "name": "Design", "version": "7", "nodes": nodes });
+        fs::write(path, serde_json::to_vec(&response).unwrap()).unwrap();
+    }
+
+    #[test]
+    fn exports_load_from_a_directory_or_a_nodes_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let export = dir.path().join("home");
+        fs::create_dir(&export).unwrap();
+        write_nodes(&export.join(NODES_FILE), &["1:2"]);
+        RgbaImage::new(80, 40)
+            .save(export.join(IMAGE_FILE))
+            .unwrap();
+        assert!(is_figma_export(&export));
+
+        let view = figma_export_to_normalized_view(
+            &export,
+            &dir.path().join("ref_figma.png"),
+            Some(Viewport {
+                width: 40,
+                height: 20,
+            }),
+        )
+        .unwrap();
+        assert_eq!((view.width, view.height), (40, 20));
+        let tree = view.figma_tree.expect("figma tree");
+        assert_eq!(tree.node_id, "1:2");
+        assert!(tree
+            .nodes
+            .iter()
+            .any(|node| node.text.as_deref() == Some("Hello")));
+
+        write_nodes(&dir.path().join("two.json"), &["1:2", "1:3"]);
+        RgbaImage::new(80, 40)
+            .save(dir.path().join("two.png"))
+            .unwrap();
+        let err = figma_export_to_normalized_view(
+            &dir.path().join("two.json"),
+            &dir.path().join("out.png"),
+            None,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("1:2, 1:3"), "got: {err}");
+
+        fs::write(
+            export.join(SOURCE_FILE),
+            r#"{"fileKey":"FILE","nodeId":"5:5","scale":1.0}"#,
+        )
+        .unwrap();
+        let err = figma_export_to_normalized_view(&export, &dir.path().join("out.png"), None)
+            .unwrap_err();
+        assert!(err.to_string().contains("no node 5:5"), "got: {err}");
+    }
+}
@@ -4,28 +4,41 @@
 //! - [`FigmaClient`] - HTTP client for the Figma REST API
 //! - [`figma_to_normalized_view`] - Main conversion function
 //! - [`FigmaRenderOptions`] - Configuration for Figma exports
+//! - [`FigmaCache`] - On-disk cache of node documents and exports
+//! - [`FigmaRetryPolicy`] - Retries and export polling for API requests
+//! - [`figma_export_to_normalized_view`] - Offline input from a saved node JSON and PNG
 //! - API types for parsing Figma JSON responses
 
 pub mod api_types;
+pub mod cache;
 pub mod client;
 pub mod conversion;
+pub mod export;
+pub mod retry;
 pub mod transform;
 
 #[cfg(test)]
 mod tests;
 
 // Re-export primary public API
+pub use cache::{FigmaCache, FigmaCacheMode};
 pub use client::{map_figma_error, FigmaClient, FigmaError};
+pub use export::{
+    figma_export_to_normalized_view, is_figma_export, pull_figma_export, FigmaExportSource,
+};
+pub use retry::FigmaRetryPolicy;
 pub use transform::FigmaRenderOptions;
 
 // Re-export API types that may be needed externally
 pub use api_types::{
-    FigmaBoundingBox, FigmaColor, FigmaDocument, FigmaFile, FigmaImageExport, FigmaNodeData,
-    FigmaNodeWrapper, FigmaNodesResponse, FigmaPaintData, FigmaTypeStyle, ImageFormat,
+    FigmaBoundingBox, FigmaColor, FigmaDocument, FigmaFile, FigmaFileVersion, FigmaImageExport,
+    FigmaNodeData, FigmaNodeWrapper, FigmaNodesResponse, FigmaPaintData, FigmaTypeStyle,
+    ImageFormat,
 };
 
 use crate::types::{NormalizedView, ResourceKind};
 use crate::{DpcError, Result};
+use cache::FigmaFrame;
 use image::{load_from_memory, GenericImageView};
 
 /// Convert a Figma frame to a NormalizedView.
@@ -35,10 +48,28 @@ use image::{load_from_memory, GenericImageView};
 /// 2. Exports the frame as a PNG image
 /// 3. Optionally resizes to the target viewport with letterboxing
 /// 4. Normalizes bounding boxes to match the final image coordinates
+///
+/// Steps 1 and 2 are served from `options.cache` when it holds the file's current version.
 pub async fn figma_to_normalized_view(
     client: &FigmaClient,
     options: &FigmaRenderOptions,
 ) -> Result<NormalizedView> {
+    validate_options(options)?;
+    let frame = load_frame(client, options).await?;
+    frame_to_normalized_view(frame, options)
+}
+
+/// Convert a Figma frame to a NormalizedView from the cache alone, as in
+/// [`FigmaCacheMode::Offline`]: no client or token, and a cache miss is an error.
+pub fn figma_to_normalized_view_offline(options: &FigmaRenderOptions) -> Result<NormalizedView> {
+    validate_options(options)?;
+    let frame = options
+        .cache
+        .load_offline(&options.file_key, &options.node_id, options.scale)?;
+    frame_to_normalized_view(frame, options)
+}
+
+fn validate_options(options: &FigmaRenderOptions) -> Result<()> {
     if options.scale <= 0.0 {
         return Err(DpcError::Config(
             "Figma export scale must be greater than zero".to_string(),
@@ -59,23 +90,52 @@ pub async fn figma_to_normalized_view(
             "Figma export output_path is required".to_string(),
         ));
     }
+    Ok(())
+}
+
+/// The node document and PNG export, from the cache when it allows, else from the API.
+async fn load_frame(client: &FigmaClient, options: &FigmaRenderOptions) -> Result<FigmaFrame> {
+    let cache = &options.cache;
+    if cache.mode == FigmaCacheMode::Offline {
+        return cache.load_offline(&options.file_key, &options.node_id, options.scale);
+    }
+    if !cache.is_online() {
+        return fetch_frame(client, options).await;
+    }
+
+    let version = client
+        .get_file_version(&options.file_key)
+        .await
+        .map_err(map_figma_error)?;
+    if let Some(frame) = cache.load(&options.file_key, &options.node_id, options.scale, &version) {
+        return Ok(frame);
+    }
+    let frame = fetch_frame(client, options).await?;
+    // A cache that cannot be written only costs the next run a fetch.
+    let _ = cache.store(
+        &options.file_key,
+        &options.node_id,
+        options.scale,
+        &version,
+        &frame,
+    );
+    Ok(frame)
+}
 
-    let nodes_response = client
+async fn fetch_frame(client: &FigmaClient, options: &FigmaRenderOptions) -> Result<FigmaFrame> {
+    let mut nodes_response = client
         .get_file_nodes(&options.file_key, &[&options.node_id])
         .await
         .map_err(map_figma_error)?;
 
     let node = nodes_response
         .nodes
-        .get(&options.node_id)
+        .remove(&options.node_id)
         .ok_or_else(|| DpcError::FigmaApi {
             status: None,
             message: format!("Node {} not found in Figma response", options.node_id),
         })?;
 
-    let figma_snapshot =
-        conversion::build_figma_snapshot(&options.file_key, &options.node_id, &node.document);
-
     let image_url = client
         .export_image(
             &options.file_key,
@@ -86,18 +146,31 @@ pub async fn figma_to_normalized_view(
         .await
         .map_err(map_figma_error)?;
 
-    let bytes = client
+    let png = client
         .download_image(&image_url)
         .await
         .map_err(map_figma_error)?;
 
-    let decoded_image = load_from_memory(&bytes)?;
+    Ok(FigmaFrame {
+        node: node.document,
+        png,
+    })
+}
+
+fn frame_to_normalized_view(
+    frame: FigmaFrame,
+    options: &FigmaRenderOptions,
+) -> Result<NormalizedView> {
+    let figma_snapshot =
+        conversion::build_figma_snapshot(&options.file_key, &options.node_id, &frame.node);
+
+    let decoded_image = load_from_memory(&frame.png)?;
     let source_dimensions = decoded_image.dimensions();
     let (width, height, letterbox) =
         transform::finalize_figma_image(decoded_image, &options.output_path, options.viewport)?;
 
-    let root_bb = node
-        .document
+    let root_bb = frame
+        .node
         .absolute_bounding_box
         .as_ref()
         .map(|bb| conversion::map_bounding_box(Some(bb)));
@@ -112,5 +185,6 @@ pub async fn figma_to_normalized_view(
         dom: None,
         figma_tree: Some(figma_snapshot),
         ocr_blocks: None,
+        diagnostics: None,
     })
 }
@@ -0,0 +1,99 @@
+//! Retry policy for Figma API requests.
+//!
+//! Rate limits (429), server errors (5xx), connection failures and timeouts are retried
+//! with exponential backoff and jitter; a `Retry-After` header replaces the computed delay.
+//! Image exports are polled on top of that, because the images endpoint answers with a
+//! null URL while a render is still pending.
+
+use serde::{Deserialize, Serialize};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::Duration;
+
+/// How a [`FigmaClient`](super::FigmaClient) retries failed requests and waits for pending
+/// image exports (`[figma.retry]`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FigmaRetryPolicy {
+    /// Attempts per request, the first included; 1 disables retries.
+    pub max_attempts: u32,
+    /// Delay before the first retry, doubled for each retry after it.
+    #[serde(with = "humantime_serde")]
+    pub backoff: Duration,
+    /// Longest wait between attempts. A `Retry-After` asking for more fails the request
+    /// at once rather than stalling the run.
+    #[serde(with = "humantime_serde")]
+    pub max_backoff: Duration,
+    /// How many more times to ask for an image export whose render is still pending.
+    pub export_polls: u32,
+    /// Wait between those polls.
+    #[serde(with = "humantime_serde")]
+    pub export_poll_interval: Duration,
+}
+
+impl Default for FigmaRetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+            export_polls: 10,
+            export_poll_interval: Duration::from_secs(2),
+        }
+    }
+}
+
+impl FigmaRetryPolicy {
+    /// How long to wait after failed attempt number `attempt` (1-based), or `None` when the
+    /// request should fail: attempts are used up, or the server's `retry_after` is longer
+    /// than `max_backoff`.
+    pub(crate) fn delay(&self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        if let Some(wait) = retry_after {
+            return (wait <= self.max_backoff).then_some(wait);
+        }
+        let factor = 1u32.checked_shl(attempt - 1).unwrap_or(u32::MAX);
+        let delay = self.backoff.saturating_mul(factor).min(self.max_backoff);
+        // Half fixed, half random, so clients that failed together come back apart.
+        let half = delay / 2;
+        Some(half + half.mul_f64(jitter()))
+    }
+}
+
+/// A fraction in `[0, 1)`. Every `RandomState` is seeded differently, which is all the
+/// randomness backoff needs.
+fn jitter() -> f64 {
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u8(0);
+    (hasher.finish() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn delays_grow_are_capped_and_honor_retry_after() {
+        let policy = FigmaRetryPolicy {
+            max_attempts: 6,
+            backoff: Duration::from_secs(2),
+            max_backoff: Duration::from_secs(10),
+            ..Default::default()
+        };
+        for (attempt, full) in [(1, 2), (2, 4), (3, 8), (4, 10), (5, 10)] {
+            let delay = policy.delay(attempt, None).expect("retry");
+            let full = Duration::from_secs(full);
+            assert!(
+                delay >= full / 2 && delay <= full,
+                "attempt {attempt}: {delay:?}"
+            );
+        }
+        assert_eq!(policy.delay(6, None), None);
+
+        let wait = Duration::from_secs(7);
+        assert_eq!(policy.delay(1, Some(wait)), Some(wait));
+        assert_eq!(policy.delay(1, Some(Duration::from_secs(11))), None);
+    }
+}
@@ -1,18 +1,22 @@
 //! Tests for Figma conversion and transform logic.
 
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 mod tests {
     use crate::figma::api_types::{
         FigmaBoundingBox, FigmaColor, FigmaNodeData, FigmaPaintData, FigmaTypeStyle, ImageFormat,
     };
     use crate::figma::client::{FigmaClient, FigmaError};
     use crate::figma::conversion::collect_figma_nodes;
+    use crate::figma::retry::FigmaRetryPolicy;
     use crate::figma::transform::{
         compute_letterbox_transform, finalize_figma_image, normalize_figma_snapshot,
     };
+    use crate::figma_client::FigmaAuth;
     use crate::types::{BoundingBox, FigmaNode, FigmaSnapshot, TypographyStyle};
     use crate::Viewport;
     use image::{DynamicImage, GenericImageView, RgbaImage};
+    use std::time::Duration;
     use tempfile::TempDir;
 
     #[test]
@@ -52,7 +56,9 @@ mod tests {
 
     #[tokio::test]
     async fn download_image_propagates_request_error() {
-        let client = FigmaClient::new("token").expect("client");
+        let client = FigmaClient::new("token")
+            .expect("client")
+            .with_retry(fast_retry(1));
 
         let result = client
             .download_image("http://127.0.0.1:1/nonexistent")
@@ -234,4 +240,210 @@ mod tests {
         assert!((child.bounding_box.width - 40.0).abs() < f32::EPSILON);
         assert!((child.bounding_box.height - 20.0).abs() < f32::EPSILON);
     }
+
+    #[test]
+    fn offline_render_uses_the_cached_frame() {
+        use crate::figma::api_types::FigmaFileVersion;
+        use crate::figma::cache::{FigmaCache, FigmaCacheMode, FigmaFrame};
+        use crate::figma::{figma_to_normalized_view_offline, FigmaRenderOptions};
+        use std::io::Cursor;
+
+        let tmp = TempDir::new().expect("temp dir");
+        let cache = FigmaCache::new(FigmaCacheMode::Offline, Some(tmp.path().join("cache")));
+        let mut png = Vec::new();
+        DynamicImage::ImageRgba8(RgbaImage::new(200, 100))
+            .write_to(&mut Cursor::new(&mut png), image::ImageOutputFormat::Png)
+            .expect("encode png");
+        let node: FigmaNodeData = serde_json::from_value(serde_json::json!({
+            "id": "1:2",
+            "name": "Home",
+            "type": "FRAME",
+            "absoluteBoundingBox": { "x": 0.0, "y": 0.0, "width": 200.0, "height": 100.0 }
+        }))
+        .expect("node");
+        let version = FigmaFileVersion {
+            version: "42".to_string(),
+            last_modified: "2024-05-01T10:00:00Z".to_string(),
+        };
+        cache
+            .store("FILE", "1:2", 1.0, &version, &FigmaFrame { node, png })
+            .expect("store");
+
+        let options = FigmaRenderOptions {
+            file_key: "FILE".to_string(),
+            node_id: "1:2".to_string(),
+            output_path: tmp.path().join("figma.png"),
+            viewport: Some(Viewport {
+                width: 100,
+                height: 50,
+            }),
+            scale: 1.0,
+            cache: cache.clone(),
+        };
+        let view = figma_to_normalized_view_offline(&options).expect("offline view");
+        assert_eq!((view.width, view.height), (100, 50));
+        let tree = view.figma_tree.expect("figma tree");
+        assert_eq!(tree.name.as_deref(), Some("Home"));
+        assert!(options.output_path.is_file());
+
+        let missing = FigmaRenderOptions {
+            node_id: "9:9".to_string(),
+            ..options
+        };
+        assert!(figma_to_normalized_view_offline(&missing).is_err());
+    }
+
+    /// Answer one connection per scripted response, in order; `responses` gets the server's
+    /// base URL and the handle yields the request line of each request.
+    async fn mock_figma(
+        responses: impl FnOnce(&str) -> Vec<String>,
+    ) -> (String, tokio::task::JoinHandle<Vec<String>>) {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind(("127.0.0.1", 0))
+            .await
+            .expect("bind");
+        let base_url = format!("http://{}", listener.local_addr().expect("addr"));
+        let responses = responses(&base_url);
+        let handle = tokio::spawn(async move {
+            let mut requests = Vec::new();
+            for response in responses {
+                let (mut stream, _) = listener.accept().await.expect("accept");
+                let mut head = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !head.windows(4).any(|w| w == b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).await.expect("read");
+                    if n == 0 {
+                        break;
+                    }
+                    head.extend_from_slice(&buf[..n]);
+                }
+                let head = String::from_utf8_lossy(&head);
+                requests.push(head.lines().next().unwrap_or_default().to_string());
+                stream.write_all(response.as_bytes()).await.expect("write");
+            }
+            requests
+        });
+        (base_url, handle)
+    }
+
+    const JSON: &str = "Content-Type: application/json\r\n";
+
+    fn http_response(status: &str, headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {status}\r\n{headers}Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len()
+        )
+    }
+
+    fn mock_client(base_url: &str, retry: FigmaRetryPolicy) -> FigmaClient {
+        FigmaClient::with_base_url(FigmaAuth::PersonalAccessToken("token".into()), base_url)
+            .expect("client")
+            .with_retry(retry)
+    }
+
+    fn fast_retry(max_attempts: u32) -> FigmaRetryPolicy {
+        FigmaRetryPolicy {
+            max_attempts,
+            backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_secs(1),
+            export_polls: 2,
+            export_poll_interval: Duration::from_millis(1),
+        }
+    }
+
+    #[tokio::test]
+    async fn transient_failures_are_retried_honoring_retry_after() {
+        let version = r#"{"version":"7","lastModified":"2024-05-01T10:00:00Z"}"#;
+        let (base_url, server) = mock_figma(|_| {
+            vec![
+                http_response("429 Too Many Requests", "Retry-After: 0\r\n", ""),
+                http_response("503 Service Unavailable", "", "busy"),
+                http_response("200 OK", JSON, version),
+            ]
+        })
+        .await;
+        let client = mock_client(&base_url, fast_retry(3));
+
+        let version = client.get_file_version("FILE").await.expect("version");
+        assert_eq!(version.version, "7");
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 3);
+        assert!(requests[2].starts_with("GET /files/FILE?depth=1"));
+    }
+
+    #[tokio::test]
+    async fn retries_stop_when_attempts_run_out_or_retry_after_is_too_long() {
+        let (base_url, server) = mock_figma(|_| {
+            vec![
+                http_response("500 Internal Server Error", "", "oops"),
+                http_response("502 Bad Gateway", "", "still down"),
+            ]
+        })
+        .await;
+        let err = mock_client(&base_url, fast_retry(2))
+            .get_file_version("FILE")
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, FigmaError::Api { status: 502, .. }),
+            "got {err:?}"
+        );
+        assert_eq!(server.await.unwrap().len(), 2);
+
+        let (base_url, server) = mock_figma(|_| {
+            vec![http_response(
+                "429 Too Many Requests",
+                "Retry-After: 120\r\n",
+                "",
+            )]
+        })
+        .await;
+        let err = mock_client(&base_url, fast_retry(4))
+            .get_file_version("FILE")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, FigmaError::RateLimited(120)), "got {err:?}");
+        assert_eq!(server.await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn image_exports_are_polled_until_the_render_is_ready() {
+        let pending = r#"{"images":{"1:2":null}}"#;
+        let (base_url, server) = mock_figma(|base_url| {
+            let ready = format!(r#"{{"images":{{"1:2":"{base_url}/render.png"}}}}"#);
+            vec![
+                http_response("200 OK", JSON, pending),
+                http_response("200 OK", JSON, pending),
+                http_response("200 OK", JSON, &ready),
+                http_response("200 OK", "Content-Type: image/png\r\n", "png"),
+            ]
+        })
+        .await;
+        let client = mock_client(&base_url, fast_retry(1));
+
+        let url = client
+            .export_image("FILE", "1:2", ImageFormat::Png, 2.0)
+            .await
+            .expect("image url");
+        assert_eq!(url, format!("{base_url}/render.png"));
+        let png = client.download_image(&url).await.expect("download");
+        assert_eq!(png, b"png");
+        let requests = server.await.unwrap();
+        assert_eq!(requests.len(), 4);
+        assert!(requests[0].starts_with("GET /images/FILE?ids=1:2&format=png&scale=2 "));
+        assert!(requests[3].starts_with("GET /render.png "));
+
+        let (base_url, server) =
+            mock_figma(|_| vec![http_response("200 OK", JSON, pending); 3]).await;
+        let err = mock_client(&base_url, fast_retry(1))
+            .export_image("FILE", "1:2", ImageFormat::Png, 1.0)
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, FigmaError::ExportPending(ref id) if id == "1:2"),
+            "got {err:?}"
+        );
+        assert_eq!(server.await.unwrap().len(), 3);
+    }
 }
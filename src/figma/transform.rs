@@ -1,5 +1,6 @@
 //! Letterbox transforms and image finalization for Figma exports.
 
+use super::cache::FigmaCache;
 use crate::image_loader::resize_with_letterbox;
 use crate::types::{BoundingBox, FigmaNode, FigmaSnapshot};
 use crate::{Result, Viewport};
@@ -15,6 +16,8 @@ pub struct FigmaRenderOptions {
     pub output_path: PathBuf,
     pub viewport: Option<Viewport>,
     pub scale: f32,
+    /// Disk cache for the node document and export (default: off).
+    pub cache: FigmaCache,
 }
 
 impl Default for FigmaRenderOptions {
@@ -25,6 +28,7 @@ impl Default for FigmaRenderOptions {
             output_path: PathBuf::new(),
             viewport: None,
             scale: 1.0,
+            cache: FigmaCache::default(),
         }
     }
 }
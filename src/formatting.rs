@@ -4,9 +4,15 @@ use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 
 use dpc_lib::output::DPC_OUTPUT_VERSION;
-use dpc_lib::{DpcError, DpcOutput, ErrorOutput, QualityFindingType};
+use dpc_lib::{
+    BaselineAction, BatchOutput, CompareOutput, DpcError, DpcOutput, ErrorOutput, MetricScores,
+    QualityFindingType,
+};
 
 use crate::cli::OutputFormat;
+use crate::junit::render_junit;
+use crate::report::{esc, render_html_report};
+use crate::sarif::render_sarif;
 
 /// Write output in the requested format.
 pub fn write_output(
@@ -17,6 +23,16 @@ pub fn write_output(
     match format {
         OutputFormat::Json => write_json_output(body, output.as_deref())?,
         OutputFormat::Pretty => write_pretty_output(body, output.as_deref())?,
+        OutputFormat::Html => write_text_output(&render_html_report(body), output.as_deref())?,
+        OutputFormat::Junit => write_text_output(&render_junit(body), output.as_deref())?,
+        OutputFormat::Sarif => write_text_output(&render_sarif(body), output.as_deref())?,
+        OutputFormat::Markdown => {
+            let link_base = output
+                .as_deref()
+                .and_then(Path::parent)
+                .unwrap_or(Path::new("."));
+            write_text_output(&format_markdown(body, link_base), output.as_deref())?
+        }
     };
     Ok(())
 }
@@ -43,8 +59,8 @@ pub fn render_error(err: DpcError, format: OutputFormat, output: Option<PathBuf>
                 println!("{content}");
             }
         }
-        OutputFormat::Pretty => {
-            if let Err(write_err) = write_pretty_output(&payload, output.as_deref()) {
+        _ => {
+            if let Err(write_err) = write_output(&payload, format, output) {
                 eprintln!("Failed to write error output: {}", write_err);
             }
         }
@@ -68,6 +84,16 @@ fn write_json_output(
     Ok(())
 }
 
+/// Write an already-rendered document (HTML, JUnit XML, SARIF) to file or stdout.
+fn write_text_output(content: &str, output: Option<&Path>) -> io::Result<()> {
+    if let Some(path) = output {
+        std::fs::write(path, content)?;
+    } else {
+        print!("{content}");
+    }
+    Ok(())
+}
+
 /// Write pretty output to file or stdout.
 fn write_pretty_output(body: &DpcOutput, output: Option<&Path>) -> io::Result<()> {
     let stdout_is_tty = std::io::stdout().is_terminal();
@@ -136,22 +162,7 @@ pub fn format_pretty(body: &DpcOutput, colorize: bool) -> String {
                 }
             }
 
-            let mut metrics: Vec<(&str, f32)> = Vec::new();
-            if let Some(pixel) = &out.metrics.pixel {
-                metrics.push(("pixel", pixel.score));
-            }
-            if let Some(layout) = &out.metrics.layout {
-                metrics.push(("layout", layout.score));
-            }
-            if let Some(typography) = &out.metrics.typography {
-                metrics.push(("typography", typography.score));
-            }
-            if let Some(color_metric) = &out.metrics.color {
-                metrics.push(("color", color_metric.score));
-            }
-            if let Some(content) = &out.metrics.content {
-                metrics.push(("content", content.score));
-            }
+            let metrics = metric_score_list(&out.metrics);
             if !metrics.is_empty() {
                 writeln!(buf, "Metrics:").ok();
                 for (name, score) in metrics {
@@ -178,6 +189,12 @@ pub fn format_pretty(body: &DpcOutput, colorize: bool) -> String {
                 if let Some(p) = &art.impl_dom_snapshot {
                     paths.push(("implDomSnapshot", p.clone()));
                 }
+                if let Some(p) = &art.ref_view {
+                    paths.push(("refView", p.clone()));
+                }
+                if let Some(p) = &art.impl_view {
+                    paths.push(("implView", p.clone()));
+                }
                 if !paths.is_empty() {
                     writeln!(buf, "Artifacts:").ok();
                     for (label, path) in paths {
@@ -207,6 +224,53 @@ pub fn format_pretty(body: &DpcOutput, colorize: bool) -> String {
 
             buf
         }
+        DpcOutput::ResponsiveCompare(out) => {
+            let mut buf = String::new();
+            let status = if out.passed { "PASS" } else { "FAIL" };
+            let status_colored = color(status, if out.passed { "32" } else { "31" }, colorize);
+            let similarity = format_score(out.similarity, Some(out.threshold));
+            let threshold = format!("{:.1}%", out.threshold * 100.0);
+            writeln!(
+                buf,
+                "{} Design parity check ({} breakpoints)",
+                status_colored,
+                out.breakpoints.len()
+            )
+            .ok();
+            writeln!(
+                buf,
+                "Combined similarity: {similarity} (threshold {threshold})"
+            )
+            .ok();
+            writeln!(buf, "Breakpoints:").ok();
+            for bp in &out.breakpoints {
+                let result = &bp.result;
+                let label = if result.passed { "PASS" } else { "FAIL" };
+                let label = color(label, if result.passed { "32" } else { "31" }, colorize);
+                writeln!(
+                    buf,
+                    "- {} {} ({}x{}): {}",
+                    label,
+                    bp.name,
+                    result.viewport.width,
+                    result.viewport.height,
+                    format_score(result.similarity, Some(result.threshold))
+                )
+                .ok();
+                if !result.passed {
+                    // Skip the leading pass/fail line; it repeats the score above.
+                    let issues = result
+                        .summary
+                        .as_ref()
+                        .map(|s| s.top_issues.iter().skip(1).take(3).collect::<Vec<_>>())
+                        .unwrap_or_default();
+                    for issue in issues {
+                        writeln!(buf, "    {issue}").ok();
+                    }
+                }
+            }
+            buf
+        }
         DpcOutput::GenerateCode(out) => {
             let mut buf = String::new();
             let header = color("[GENERATE]", "36", colorize);
@@ -257,24 +321,112 @@ pub fn format_pretty(body: &DpcOutput, colorize: bool) -> String {
                 writeln!(buf, "Findings:").ok();
                 for finding in &out.findings {
                     let severity = format!("{:?}", finding.severity).to_ascii_lowercase();
-                    let kind = match finding.finding_type {
-                        QualityFindingType::AlignmentInconsistent => "alignment_inconsistent",
-                        QualityFindingType::SpacingInconsistent => "spacing_inconsistent",
-                        QualityFindingType::LowContrast => "low_contrast",
-                        QualityFindingType::MissingHierarchy => "missing_hierarchy",
-                    };
+                    let kind = quality_finding_label(finding.finding_type);
                     writeln!(buf, "- [{}] {}: {}", severity, kind, finding.message).ok();
                 }
             }
             buf
         }
+        DpcOutput::Batch(out) => {
+            let mut buf = String::new();
+            let status = if out.passed { "PASS" } else { "FAIL" };
+            let status_colored = color(status, if out.passed { "32" } else { "31" }, colorize);
+            writeln!(
+                buf,
+                "{} Batch {} ({} entries)",
+                status_colored,
+                out.manifest.display(),
+                out.total
+            )
+            .ok();
+            writeln!(
+                buf,
+                "Passed: {}  Failed: {}  Errors: {}",
+                out.passed_count, out.failed_count, out.error_count
+            )
+            .ok();
+            for entry in &out.entries {
+                match (&entry.result, &entry.error) {
+                    (Some(result), _) => {
+                        let label = if result.passed { "PASS" } else { "FAIL" };
+                        let label = color(label, if result.passed { "32" } else { "31" }, colorize);
+                        let similarity = format_score(result.similarity, Some(result.threshold));
+                        writeln!(
+                            buf,
+                            "- {} {}: {} (threshold {:.1}%)",
+                            label,
+                            entry.name,
+                            similarity,
+                            result.threshold * 100.0
+                        )
+                        .ok();
+                    }
+                    (None, Some(error)) => {
+                        let label = color("ERROR", "31", colorize);
+                        writeln!(buf, "- {} {}: {}", label, entry.name, error.message).ok();
+                    }
+                    (None, None) => {
+                        writeln!(buf, "- {}: no result", entry.name).ok();
+                    }
+                }
+            }
+            buf
+        }
+        DpcOutput::Baseline(out) => {
+            let mut buf = String::new();
+            let header = color("[BASELINE]", "36", colorize);
+            let action = match out.action {
+                BaselineAction::Record => "Recorded",
+                BaselineAction::Approve => "Approved",
+            };
+            writeln!(
+                buf,
+                "{} {} scenario '{}' ({} viewport{})",
+                header,
+                action,
+                out.scenario,
+                out.entries.len(),
+                if out.entries.len() == 1 { "" } else { "s" }
+            )
+            .ok();
+            writeln!(buf, "Store: {}", out.store.display()).ok();
+            for entry in &out.entries {
+                writeln!(
+                    buf,
+                    "- {}: {} (source: {})",
+                    entry.viewport,
+                    entry.path.display(),
+                    entry.source.value
+                )
+                .ok();
+            }
+            buf
+        }
+        DpcOutput::FigmaPull(out) => {
+            let mut buf = String::new();
+            let header = color("[FIGMA]", "36", colorize);
+            writeln!(
+                buf,
+                "{} Pulled node {} of {} at {}x to {}",
+                header,
+                out.node_id,
+                out.file_key,
+                out.scale,
+                out.path.display()
+            )
+            .ok();
+            writeln!(
+                buf,
+                "File version: {} (last modified {})",
+                out.file_version, out.last_modified
+            )
+            .ok();
+            buf
+        }
         DpcOutput::Error(out) => {
             let mut buf = String::new();
             let header = color("[ERROR]", "31", colorize);
-            let message = out
-                .message
-                .as_deref()
-                .unwrap_or_else(|| out.error.message.as_str());
+            let message = out.message.as_deref().unwrap_or(out.error.message.as_str());
             writeln!(buf, "{} {}", header, message).ok();
             if let Some(remediation) = &out.error.remediation {
                 writeln!(buf, "Hint: {}", remediation).ok();
@@ -284,6 +436,334 @@ pub fn format_pretty(body: &DpcOutput, colorize: bool) -> String {
     }
 }
 
+/// Format output as a compact GitHub-flavoured Markdown summary (e.g. for PR comments).
+///
+/// Artifact images are linked relative to `link_base` (the directory the Markdown is
+/// written to) and only when artifacts were kept, since temp artifacts are removed.
+pub fn format_markdown(body: &DpcOutput, link_base: &Path) -> String {
+    let badge = |passed: bool| {
+        if passed {
+            "✅ **PASS**"
+        } else {
+            "❌ **FAIL**"
+        }
+    };
+    let pct = |score: f32| format!("{:.1}%", score * 100.0);
+
+    let mut buf = String::new();
+    match body {
+        DpcOutput::Compare(out) => {
+            writeln!(buf, "### {} Design parity check\n", badge(out.passed)).ok();
+            writeln!(
+                buf,
+                "**Similarity:** {} (threshold {})  ",
+                pct(out.similarity),
+                pct(out.threshold)
+            )
+            .ok();
+            writeln!(
+                buf,
+                "{} vs {} at {}\n",
+                markdown_code(&out.ref_resource.value),
+                markdown_code(&out.impl_resource.value),
+                out.viewport
+            )
+            .ok();
+            markdown_compare_details(&mut buf, out, link_base);
+        }
+        DpcOutput::ResponsiveCompare(out) => {
+            writeln!(
+                buf,
+                "### {} Design parity check ({} breakpoints)\n",
+                badge(out.passed),
+                out.breakpoints.len()
+            )
+            .ok();
+            writeln!(
+                buf,
+                "**Combined similarity:** {} (threshold {})\n",
+                pct(out.similarity),
+                pct(out.threshold)
+            )
+            .ok();
+            writeln!(buf, "| Breakpoint | Viewport | Status | Similarity |").ok();
+            writeln!(buf, "|---|---|---|---:|").ok();
+            for bp in &out.breakpoints {
+                writeln!(
+                    buf,
+                    "| {} | {} | {} | {} |",
+                    markdown_cell(&bp.name),
+                    bp.result.viewport,
+                    badge(bp.result.passed),
+                    pct(bp.result.similarity)
+                )
+                .ok();
+            }
+            for bp in out.breakpoints.iter().filter(|bp| !bp.result.passed) {
+                writeln!(buf, "\n<details><summary>{}</summary>\n", esc(&bp.name)).ok();
+                markdown_compare_details(&mut buf, &bp.result, link_base);
+                writeln!(buf, "</details>").ok();
+            }
+        }
+        DpcOutput::Batch(out) => {
+            writeln!(
+                buf,
+                "### {} Batch {}\n",
+                badge(out.passed),
+                markdown_code(&out.manifest.display().to_string())
+            )
+            .ok();
+            writeln!(
+                buf,
+                "{} entries: {} passed, {} failed, {} errors\n",
+                out.total, out.passed_count, out.failed_count, out.error_count
+            )
+            .ok();
+            writeln!(buf, "| Entry | Status | Similarity | Threshold |").ok();
+            writeln!(buf, "|---|---|---:|---:|").ok();
+            for entry in &out.entries {
+                match (&entry.result, &entry.error) {
+                    (Some(result), _) => writeln!(
+                        buf,
+                        "| {} | {} | {} | {} |",
+                        markdown_cell(&entry.name),
+                        badge(result.passed),
+                        pct(result.similarity),
+                        pct(result.threshold)
+                    ),
+                    (None, error) => writeln!(
+                        buf,
+                        "| {} | ⚠️ **ERROR** | {} | |",
+                        markdown_cell(&entry.name),
+                        markdown_cell(error.as_ref().map_or("no result", |e| e.message.as_str()))
+                    ),
+                }
+                .ok();
+            }
+        }
+        DpcOutput::Quality(out) => {
+            writeln!(buf, "### Quality score {:.1}\n", out.score * 100.0).ok();
+            writeln!(
+                buf,
+                "Input: {} at {}\n",
+                markdown_code(&out.input.value),
+                out.viewport
+            )
+            .ok();
+            if out.findings.is_empty() {
+                writeln!(buf, "No findings.").ok();
+            } else {
+                writeln!(buf, "| Severity | Type | Message |").ok();
+                writeln!(buf, "|---|---|---|").ok();
+                for finding in &out.findings {
+                    writeln!(
+                        buf,
+                        "| {} | {} | {} |",
+                        format!("{:?}", finding.severity).to_ascii_lowercase(),
+                        quality_finding_label(finding.finding_type),
+                        markdown_cell(&finding.message)
+                    )
+                    .ok();
+                }
+            }
+        }
+        DpcOutput::GenerateCode(out) => {
+            writeln!(buf, "### Code generated\n").ok();
+            writeln!(buf, "Input: {}  ", markdown_code(&out.input.value)).ok();
+            if let Some(stack) = &out.stack {
+                writeln!(buf, "Stack: {}  ", markdown_code(stack)).ok();
+            }
+            if let Some(path) = &out.output_path {
+                writeln!(
+                    buf,
+                    "Saved to: {}  ",
+                    markdown_code(&path.display().to_string())
+                )
+                .ok();
+            }
+            if let Some(summary) = &out.summary {
+                buf.push('\n');
+                for issue in &summary.top_issues {
+                    writeln!(buf, "- {issue}").ok();
+                }
+            }
+            if let Some(code) = &out.code {
+                let fence = "`".repeat(longest_backtick_run(code).max(2) + 1);
+                writeln!(
+                    buf,
+                    "\n<details><summary>Generated code ({} chars)</summary>\n\n{fence}html\n{}\n{fence}\n</details>",
+                    code.len(),
+                    code.trim_end()
+                )
+                .ok();
+            }
+        }
+        DpcOutput::Baseline(out) => {
+            let action = match out.action {
+                BaselineAction::Record => "Recorded",
+                BaselineAction::Approve => "Approved",
+            };
+            writeln!(
+                buf,
+                "### {action} baseline {}\n",
+                markdown_code(&out.scenario)
+            )
+            .ok();
+            for entry in &out.entries {
+                writeln!(
+                    buf,
+                    "- {}: {} (source: {})",
+                    entry.viewport,
+                    markdown_code(&entry.path.display().to_string()),
+                    markdown_code(&entry.source.value)
+                )
+                .ok();
+            }
+        }
+        DpcOutput::FigmaPull(out) => {
+            writeln!(
+                buf,
+                "### Pulled Figma node {} of {}\n\n- Export: {}\n- File version: {} (last modified {})",
+                markdown_code(&out.node_id),
+                markdown_code(&out.file_key),
+                markdown_code(&out.path.display().to_string()),
+                out.file_version,
+                out.last_modified
+            )
+            .ok();
+        }
+        DpcOutput::Error(out) => {
+            let message = out.message.as_deref().unwrap_or(out.error.message.as_str());
+            writeln!(buf, "### ⚠️ **ERROR** {message}").ok();
+            if let Some(remediation) = &out.error.remediation {
+                writeln!(buf, "\nHint: {remediation}").ok();
+            }
+        }
+    }
+    buf
+}
+
+/// Metric score table, top issues, and kept artifact images for one compare result.
+fn markdown_compare_details(buf: &mut String, out: &CompareOutput, link_base: &Path) {
+    let metrics = metric_score_list(&out.metrics);
+    if !metrics.is_empty() {
+        writeln!(buf, "| Metric | Score |").ok();
+        writeln!(buf, "|---|---:|").ok();
+        for (name, score) in metrics {
+            writeln!(buf, "| {name} | {:.1}% |", score * 100.0).ok();
+        }
+        buf.push('\n');
+    }
+
+    let issues = out
+        .summary
+        .as_ref()
+        .map(|s| s.top_issues.iter().take(5).collect::<Vec<_>>())
+        .unwrap_or_default();
+    if !issues.is_empty() {
+        writeln!(buf, "**Top issues**\n").ok();
+        for issue in issues {
+            writeln!(buf, "- {issue}").ok();
+        }
+        buf.push('\n');
+    }
+
+    let Some(art) = out.artifacts.as_ref().filter(|a| a.kept) else {
+        return;
+    };
+    let images: Vec<(&str, &PathBuf)> = [
+        ("Reference", art.ref_screenshot.as_ref()),
+        ("Implementation", art.impl_screenshot.as_ref()),
+        ("Diff", art.diff_image.as_ref()),
+    ]
+    .into_iter()
+    .filter_map(|(name, path)| path.map(|p| (name, p)))
+    .collect();
+    if images.is_empty() {
+        return;
+    }
+    let header: Vec<&str> = images.iter().map(|(name, _)| *name).collect();
+    writeln!(buf, "| {} |", header.join(" | ")).ok();
+    writeln!(buf, "|{}", "---|".repeat(images.len())).ok();
+    let cells: Vec<String> = images
+        .iter()
+        .map(|(name, path)| format!("![{name}]({})", relative_link(path, link_base)))
+        .collect();
+    writeln!(buf, "| {} |\n", cells.join(" | ")).ok();
+}
+
+/// Scores of the metrics that ran, in report order.
+fn metric_score_list(metrics: &MetricScores) -> Vec<(&'static str, f32)> {
+    [
+        ("pixel", metrics.pixel.as_ref().map(|m| m.score)),
+        ("layout", metrics.layout.as_ref().map(|m| m.score)),
+        ("typography", metrics.typography.as_ref().map(|m| m.score)),
+        ("color", metrics.color.as_ref().map(|m| m.score)),
+        ("content", metrics.content.as_ref().map(|m| m.score)),
+    ]
+    .into_iter()
+    .filter_map(|(name, score)| score.map(|s| (name, s)))
+    .collect()
+}
+
+fn quality_finding_label(kind: QualityFindingType) -> &'static str {
+    match kind {
+        QualityFindingType::AlignmentInconsistent => "alignment_inconsistent",
+        QualityFindingType::SpacingInconsistent => "spacing_inconsistent",
+        QualityFindingType::LowContrast => "low_contrast",
+        QualityFindingType::MissingHierarchy => "missing_hierarchy",
+        QualityFindingType::ScriptError => "script_error",
+        QualityFindingType::FailedRequest => "failed_request",
+        QualityFindingType::BrokenImage => "broken_image",
+    }
+}
+
+/// Escape characters that would break a Markdown table cell.
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+/// `text` as an inline code span, fenced with more backticks than it contains.
+fn markdown_code(text: &str) -> String {
+    let text = text.replace('\n', " ");
+    let fence = "`".repeat(longest_backtick_run(&text) + 1);
+    // A span starting or ending with a backtick needs a space between it and the fence.
+    let pad = if text.starts_with('`') || text.ends_with('`') {
+        " "
+    } else {
+        ""
+    };
+    format!("{fence}{pad}{text}{pad}{fence}")
+}
+
+fn longest_backtick_run(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
+
+/// `path` relative to `base` with forward slashes, for Markdown links.
+fn relative_link(path: &Path, base: &Path) -> String {
+    let (Ok(path), Ok(base)) = (std::path::absolute(path), std::path::absolute(base)) else {
+        return path.display().to_string();
+    };
+    let path_parts: Vec<_> = path.components().collect();
+    let base_parts: Vec<_> = base.components().collect();
+    let common = path_parts
+        .iter()
+        .zip(&base_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+    if common == 0 {
+        return path.display().to_string();
+    }
+    let mut parts: Vec<String> = vec!["..".to_string(); base_parts.len() - common];
+    parts.extend(
+        path_parts[common..]
+            .iter()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned()),
+    );
+    parts.join("/")
+}
+
 /// Apply ANSI color codes when enabled.
 fn color(text: &str, code: &str, colorize: bool) -> String {
     if colorize {
@@ -313,10 +793,22 @@ pub fn exit_code_for_compare(passed: bool) -> ExitCode {
     }
 }
 
+/// Determine exit code for batch command: errors outrank threshold failures.
+pub fn exit_code_for_batch(out: &BatchOutput) -> ExitCode {
+    if out.error_count > 0 {
+        ExitCode::from(2)
+    } else {
+        exit_code_for_compare(out.passed)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use dpc_lib::output::{CompareOutput, ResourceDescriptor, Summary};
+    use dpc_lib::output::{
+        BreakpointCompareOutput, CompareOutput, ResourceDescriptor, ResponsiveCompareOutput,
+        Summary,
+    };
     use dpc_lib::types::{
         ColorMetric, LayoutMetric, MetricScores, PixelMetric, ResourceKind, Viewport,
     };
@@ -368,6 +860,8 @@ mod tests {
             impl_dom_snapshot: None,
             ref_figma_snapshot: None,
             impl_figma_snapshot: None,
+            ref_view: None,
+            impl_view: None,
         };
         let output = DpcOutput::Compare(CompareOutput {
             version: DPC_OUTPUT_VERSION.to_string(),
@@ -387,6 +881,7 @@ mod tests {
             threshold: 0.95,
             passed: true,
             metrics,
+            rendering: None,
             summary: Some(Summary {
                 top_issues: vec!["Design parity check passed".into()],
             }),
@@ -434,6 +929,7 @@ mod tests {
                 color: None,
                 content: None,
             },
+            rendering: None,
             summary: Some(Summary {
                 top_issues: vec![
                     "Design parity check passed (96.0% similarity, threshold: 95.0%)".into(),
@@ -451,6 +947,219 @@ mod tests {
         assert!(pretty.contains("Top issues") || pretty.contains("Top issues (max 5):"));
     }
 
+    #[test]
+    fn format_markdown_renders_table_issues_and_relative_images() {
+        let output = DpcOutput::Compare(CompareOutput {
+            version: DPC_OUTPUT_VERSION.to_string(),
+            ref_resource: ResourceDescriptor {
+                kind: ResourceKind::Url,
+                value: "https://design".to_string(),
+            },
+            impl_resource: ResourceDescriptor {
+                kind: ResourceKind::Url,
+                value: "https://build".to_string(),
+            },
+            viewport: Viewport {
+                width: 1440,
+                height: 900,
+            },
+            similarity: 0.8,
+            threshold: 0.95,
+            passed: false,
+            metrics: MetricScores {
+                pixel: Some(PixelMetric {
+                    score: 0.7,
+                    diff_regions: Vec::new(),
+                    semantic_diffs: None,
+                }),
+                layout: None,
+                typography: None,
+                color: None,
+                content: None,
+            },
+            rendering: None,
+            summary: Some(Summary {
+                top_issues: vec!["1 major pixel difference region detected.".into()],
+            }),
+            artifacts: Some(CompareArtifacts {
+                directory: PathBuf::from("/work/artifacts"),
+                kept: true,
+                ref_screenshot: Some(PathBuf::from("/work/artifacts/ref_screenshot.png")),
+                impl_screenshot: Some(PathBuf::from("/work/artifacts/impl_screenshot.png")),
+                diff_image: None,
+                ref_dom_snapshot: None,
+                impl_dom_snapshot: None,
+                ref_figma_snapshot: None,
+                impl_figma_snapshot: None,
+                ref_view: None,
+                impl_view: None,
+            }),
+        });
+
+        let md = format_markdown(&output, Path::new("/work/reports"));
+        assert!(md.starts_with("### ❌ **FAIL** Design parity check"));
+        assert!(md.contains("**Similarity:** 80.0% (threshold 95.0%)"));
+        assert!(md.contains("| pixel | 70.0% |"));
+        assert!(md.contains("- 1 major pixel difference region detected."));
+        assert!(md.contains("| Reference | Implementation |"));
+        assert!(md.contains("![Reference](../artifacts/ref_screenshot.png)"));
+    }
+
+    #[test]
+    fn format_markdown_covers_quality_findings() {
+        let output = DpcOutput::Quality(dpc_lib::QualityOutput {
+            version: DPC_OUTPUT_VERSION.to_string(),
+            input: ResourceDescriptor {
+                kind: ResourceKind::Image,
+                value: "page.png".to_string(),
+            },
+            viewport: Viewport::default(),
+            score: 0.82,
+            findings: vec![dpc_lib::QualityFinding {
+                severity: dpc_lib::FindingSeverity::Warning,
+                finding_type: QualityFindingType::LowContrast,
+                message: "Text | button contrast is low".to_string(),
+            }],
+        });
+
+        let md = format_markdown(&output, Path::new("."));
+        assert!(md.contains("### Quality score 82.0"));
+        assert!(md.contains("| warning | low_contrast | Text \\| button contrast is low |"));
+    }
+
+    #[test]
+    fn format_markdown_fences_code_that_contains_backticks() {
+        assert_eq!(markdown_code("a.png"), "`a.png`");
+        assert_eq!(markdown_code("x`y``z"), "```x`y``z```");
+        assert_eq!(markdown_code("`tick"), "`` `tick ``");
+
+        let output = DpcOutput::GenerateCode(dpc_lib::GenerateCodeOutput {
+            version: DPC_OUTPUT_VERSION.to_string(),
+            input: ResourceDescriptor {
+                kind: ResourceKind::Image,
+                value: "mock`up.png".to_string(),
+            },
+            viewport: None,
+            stack: None,
+            output_path: None,
+            code: Some("<pre>\n```\n</pre>".to_string()),
+            summary: None,
+        });
+
+        let md = format_markdown(&output, Path::new("."));
+        assert!(md.contains("Input: ``mock`up.png``"));
+        assert!(md.contains("\n````html\n<pre>\n```\n</pre>\n````\n"));
+    }
+
+    #[test]
+    fn exit_code_for_batch_prefers_errors_over_failures() {
+        let mut out = BatchOutput {
+            version: DPC_OUTPUT_VERSION.to_string(),
+            manifest: PathBuf::from("batch.yaml"),
+            passed: true,
+            total: 2,
+            passed_count: 2,
+            failed_count: 0,
+            error_count: 0,
+            entries: vec![],
+        };
+        assert_eq!(exit_code_for_batch(&out), ExitCode::SUCCESS);
+
+        out.passed = false;
+        out.failed_count = 1;
+        assert_eq!(exit_code_for_batch(&out), ExitCode::from(1));
+
+        out.error_count = 1;
+        assert_eq!(exit_code_for_batch(&out), ExitCode::from(2));
+    }
+
+    #[test]
+    fn format_pretty_lists_breakpoints() {
+        let breakpoint = |name: &str, width: u32, similarity: f32| BreakpointCompareOutput {
+            name: name.to_string(),
+            browser: None,
+            state: None,
+            result: CompareOutput {
+                version: DPC_OUTPUT_VERSION.to_string(),
+                ref_resource: ResourceDescriptor {
+                    kind: ResourceKind::Image,
+                    value: "ref.png".to_string(),
+                },
+                impl_resource: ResourceDescriptor {
+                    kind: ResourceKind::Image,
+                    value: "impl.png".to_string(),
+                },
+                viewport: Viewport { width, height: 800 },
+                similarity,
+                threshold: 0.95,
+                passed: similarity >= 0.95,
+                metrics: MetricScores {
+                    pixel: None,
+                    layout: None,
+                    typography: None,
+                    color: None,
+                    content: None,
+                },
+                rendering: None,
+                summary: Some(Summary {
+                    top_issues: vec!["status".into(), "Header wraps on mobile".into()],
+                }),
+                artifacts: None,
+            },
+        };
+        let output = DpcOutput::ResponsiveCompare(ResponsiveCompareOutput {
+            version: DPC_OUTPUT_VERSION.to_string(),
+            ref_resource: ResourceDescriptor {
+                kind: ResourceKind::Image,
+                value: "ref.png".to_string(),
+            },
+            impl_resource: ResourceDescriptor {
+                kind: ResourceKind::Image,
+                value: "impl.png".to_string(),
+            },
+            similarity: 0.94,
+            threshold: 0.95,
+            passed: false,
+            breakpoints: vec![
+                breakpoint("mobile", 375, 0.9),
+                breakpoint("desktop", 1440, 0.98),
+            ],
+        });
+
+        let pretty = format_pretty(&output, false);
+        assert!(pretty.contains("FAIL Design parity check (2 breakpoints)"));
+        assert!(pretty.contains("FAIL mobile (375x800)"));
+        assert!(pretty.contains("PASS desktop (1440x800)"));
+        assert_eq!(pretty.matches("Header wraps on mobile").count(), 1);
+    }
+
+    #[test]
+    fn format_pretty_lists_baseline_entries() {
+        let output = DpcOutput::Baseline(dpc_lib::BaselineOutput {
+            version: DPC_OUTPUT_VERSION.to_string(),
+            action: BaselineAction::Record,
+            scenario: "home".to_string(),
+            store: PathBuf::from(".dpc/baselines"),
+            entries: vec![dpc_lib::BaselineEntryOutput {
+                viewport: Viewport {
+                    width: 375,
+                    height: 812,
+                },
+                path: PathBuf::from(".dpc/baselines/home/375x812"),
+                source: ResourceDescriptor {
+                    kind: ResourceKind::Url,
+                    value: "https://example.com".to_string(),
+                },
+            }],
+        });
+
+        let pretty = format_pretty(&output, false);
+        assert!(pretty.contains("[BASELINE] Recorded scenario 'home' (1 viewport)"));
+        assert!(
+            pretty.contains("- 375x812: .dpc/baselines/home/375x812 (source: https://example.com)")
+        );
+    }
+
     #[test]
     fn format_pretty_handles_errors() {
         let output = DpcOutput::Error(ErrorOutput {
@@ -65,6 +65,7 @@ pub fn image_to_normalized_view(
         dom: None,
         figma_tree: None,
         ocr_blocks: None,
+        diagnostics: None,
     })
 }
 
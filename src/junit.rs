@@ -0,0 +1,441 @@
+//! JUnit XML output (`--format junit`).
+//!
+//! Each compare becomes a `<testsuite>` with a `similarity` testcase plus one testcase
+//! per metric; responsive breakpoints and batch entries map to one suite each.
+
+use std::fmt::Write as FmtWrite;
+
+use dpc_lib::error::ErrorPayload;
+use dpc_lib::types::MetricScores;
+use dpc_lib::{generate_top_issues, CompareOutput, DpcOutput};
+
+use crate::report::{esc, label};
+
+/// Issues listed in a per-metric failure body.
+const MAX_FAILURE_ISSUES: usize = 10;
+
+struct Suite {
+    name: String,
+    properties: Vec<(&'static str, String)>,
+    cases: Vec<Case>,
+}
+
+struct Case {
+    classname: String,
+    name: String,
+    outcome: Outcome,
+    system_out: Option<String>,
+}
+
+enum Outcome {
+    Passed,
+    Failure {
+        message: String,
+        body: String,
+    },
+    Error {
+        kind: String,
+        message: String,
+        body: String,
+    },
+}
+
+/// Render any output payload as a JUnit XML document.
+pub fn render_junit(body: &DpcOutput) -> String {
+    let suites = match body {
+        DpcOutput::Compare(out) => vec![compare_suite("compare", out)],
+        DpcOutput::ResponsiveCompare(out) => out
+            .breakpoints
+            .iter()
+            .map(|bp| compare_suite(&bp.name, &bp.result))
+            .collect(),
+        DpcOutput::Batch(out) => out
+            .entries
+            .iter()
+            .map(|entry| match (&entry.result, &entry.error) {
+                (Some(result), _) => compare_suite(&entry.name, result),
+                (None, error) => error_suite(&entry.name, error.as_ref()),
+            })
+            .collect(),
+        DpcOutput::Error(out) => vec![error_suite("dpc", Some(&out.error))],
+        DpcOutput::Quality(out) => {
+            let findings: Vec<String> = out
+                .findings
+                .iter()
+                .map(|f| {
+                    format!(
+                        "[{}] {}: {}",
+                        label(&f.severity),
+                        label(&f.finding_type),
+                        f.message
+                    )
+                })
+                .collect();
+            vec![Suite {
+                name: "quality".to_string(),
+                properties: vec![("score", format!("{:.4}", out.score))],
+                cases: vec![Case {
+                    classname: "dpc.quality".to_string(),
+                    name: out.input.value.clone(),
+                    outcome: Outcome::Passed,
+                    system_out: (!findings.is_empty()).then(|| findings.join("\n")),
+                }],
+            }]
+        }
+        DpcOutput::GenerateCode(out) => vec![Suite {
+            name: "generate-code".to_string(),
+            properties: Vec::new(),
+            cases: vec![Case {
+                classname: "dpc.generate-code".to_string(),
+                name: out.input.value.clone(),
+                outcome: Outcome::Passed,
+                system_out: None,
+            }],
+        }],
+        DpcOutput::Baseline(out) => vec![Suite {
+            name: format!("baseline {}", out.scenario),
+            properties: vec![("action", label(&out.action))],
+            cases: out
+                .entries
+                .iter()
+                .map(|entry| Case {
+                    classname: format!("dpc.baseline.{}", out.scenario),
+                    name: entry.viewport.to_string(),
+                    outcome: Outcome::Passed,
+                    system_out: Some(entry.path.display().to_string()),
+                })
+                .collect(),
+        }],
+        DpcOutput::FigmaPull(out) => vec![Suite {
+            name: "figma-pull".to_string(),
+            properties: Vec::new(),
+            cases: vec![Case {
+                classname: "dpc.figma-pull".to_string(),
+                name: format!("{} {}", out.file_key, out.node_id),
+                outcome: Outcome::Passed,
+                system_out: Some(out.path.display().to_string()),
+            }],
+        }],
+    };
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let (tests, failures, errors) = totals(suites.iter().flat_map(|s| s.cases.iter()));
+    writeln!(
+        xml,
+        "<testsuites name=\"dpc\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\">"
+    )
+    .ok();
+    for suite in &suites {
+        write_suite(&mut xml, suite);
+    }
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+fn compare_suite(name: &str, out: &CompareOutput) -> Suite {
+    let classname = format!("dpc.{name}");
+    let issues = out
+        .summary
+        .as_ref()
+        .map(|s| s.top_issues.join("\n"))
+        .unwrap_or_default();
+    let mut cases = vec![Case {
+        classname: classname.clone(),
+        name: "similarity".to_string(),
+        outcome: if out.passed {
+            Outcome::Passed
+        } else {
+            Outcome::Failure {
+                message: format!(
+                    "Similarity {:.1}% is below threshold {:.1}%",
+                    out.similarity * 100.0,
+                    out.threshold * 100.0
+                ),
+                body: issues,
+            }
+        },
+        system_out: None,
+    }];
+
+    // The threshold is for overall similarity, so a weak metric only fails its testcase
+    // when the comparison itself failed; it then points at what dragged the score down.
+    for (metric, score, only) in per_metric(&out.metrics) {
+        let outcome = if out.passed || score >= out.threshold {
+            Outcome::Passed
+        } else {
+            Outcome::Failure {
+                message: format!(
+                    "{metric} score {:.1}% is below threshold {:.1}%",
+                    score * 100.0,
+                    out.threshold * 100.0
+                ),
+                body: generate_top_issues(&only, MAX_FAILURE_ISSUES).join("\n"),
+            }
+        };
+        cases.push(Case {
+            classname: classname.clone(),
+            name: metric.to_string(),
+            outcome,
+            system_out: None,
+        });
+    }
+
+    Suite {
+        name: name.to_string(),
+        properties: vec![
+            ("ref", out.ref_resource.value.clone()),
+            ("impl", out.impl_resource.value.clone()),
+            ("viewport", out.viewport.to_string()),
+            ("similarity", format!("{:.4}", out.similarity)),
+            ("threshold", format!("{:.4}", out.threshold)),
+        ],
+        cases,
+    }
+}
+
+/// Split metric scores so each metric's issues can be summarized on their own.
+fn per_metric(metrics: &MetricScores) -> Vec<(&'static str, f32, MetricScores)> {
+    let empty = || MetricScores {
+        pixel: None,
+        layout: None,
+        typography: None,
+        color: None,
+        content: None,
+    };
+    let mut out = Vec::new();
+    if let Some(m) = &metrics.pixel {
+        let mut only = empty();
+        only.pixel = Some(m.clone());
+        out.push(("pixel", m.score, only));
+    }
+    if let Some(m) = &metrics.layout {
+        let mut only = empty();
+        only.layout = Some(m.clone());
+        out.push(("layout", m.score, only));
+    }
+    if let Some(m) = &metrics.typography {
+        let mut only = empty();
+        only.typography = Some(m.clone());
+        out.push(("typography", m.score, only));
+    }
+    if let Some(m) = &metrics.color {
+        let mut only = empty();
+        only.color = Some(m.clone());
+        out.push(("color", m.score, only));
+    }
+    if let Some(m) = &metrics.content {
+        let mut only = empty();
+        only.content = Some(m.clone());
+        out.push(("content", m.score, only));
+    }
+    out
+}
+
+fn error_suite(name: &str, error: Option<&ErrorPayload>) -> Suite {
+    let (kind, message, body) = match error {
+        Some(err) => (
+            label(&err.category),
+            err.message.clone(),
+            err.remediation.clone().unwrap_or_default(),
+        ),
+        None => (
+            "unknown".to_string(),
+            "Entry did not run".to_string(),
+            String::new(),
+        ),
+    };
+    Suite {
+        name: name.to_string(),
+        properties: Vec::new(),
+        cases: vec![Case {
+            classname: format!("dpc.{name}"),
+            name: "run".to_string(),
+            outcome: Outcome::Error {
+                kind,
+                message,
+                body,
+            },
+            system_out: None,
+        }],
+    }
+}
+
+fn totals<'a>(cases: impl Iterator<Item = &'a Case>) -> (usize, usize, usize) {
+    cases.fold((0, 0, 0), |(tests, failures, errors), case| {
+        match case.outcome {
+            Outcome::Passed => (tests + 1, failures, errors),
+            Outcome::Failure { .. } => (tests + 1, failures + 1, errors),
+            Outcome::Error { .. } => (tests + 1, failures, errors + 1),
+        }
+    })
+}
+
+fn write_suite(xml: &mut String, suite: &Suite) {
+    let (tests, failures, errors) = totals(suite.cases.iter());
+    writeln!(
+        xml,
+        "  <testsuite name=\"{}\" tests=\"{tests}\" failures=\"{failures}\" errors=\"{errors}\">",
+        esc(&suite.name)
+    )
+    .ok();
+    if !suite.properties.is_empty() {
+        xml.push_str("    <properties>\n");
+        for (name, value) in &suite.properties {
+            writeln!(
+                xml,
+                "      <property name=\"{name}\" value=\"{}\"/>",
+                esc(value)
+            )
+            .ok();
+        }
+        xml.push_str("    </properties>\n");
+    }
+    for case in &suite.cases {
+        write!(
+            xml,
+            "    <testcase classname=\"{}\" name=\"{}\"",
+            esc(&case.classname),
+            esc(&case.name)
+        )
+        .ok();
+        if matches!(case.outcome, Outcome::Passed) && case.system_out.is_none() {
+            xml.push_str("/>\n");
+            continue;
+        }
+        xml.push_str(">\n");
+        match &case.outcome {
+            Outcome::Passed => {}
+            Outcome::Failure { message, body } => {
+                writeln!(
+                    xml,
+                    "      <failure message=\"{}\" type=\"threshold\">{}</failure>",
+                    esc(message),
+                    esc(body)
+                )
+                .ok();
+            }
+            Outcome::Error {
+                kind,
+                message,
+                body,
+            } => {
+                writeln!(
+                    xml,
+                    "      <error message=\"{}\" type=\"{}\">{}</error>",
+                    esc(message),
+                    esc(kind),
+                    esc(body)
+                )
+                .ok();
+            }
+        }
+        if let Some(out) = &case.system_out {
+            writeln!(xml, "      <system-out>{}</system-out>", esc(out)).ok();
+        }
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dpc_lib::error::ErrorCategory;
+    use dpc_lib::output::DPC_OUTPUT_VERSION;
+    use dpc_lib::types::{ColorMetric, PixelMetric, ResourceKind};
+    use dpc_lib::{BatchEntryOutput, BatchOutput, ResourceDescriptor, Summary, Viewport};
+    use std::path::PathBuf;
+
+    fn compare(similarity: f32, pixel: f32) -> CompareOutput {
+        CompareOutput {
+            version: DPC_OUTPUT_VERSION.to_string(),
+            ref_resource: ResourceDescriptor {
+                kind: ResourceKind::Image,
+                value: "ref.png".to_string(),
+            },
+            impl_resource: ResourceDescriptor {
+                kind: ResourceKind::Image,
+                value: "impl & co.png".to_string(),
+            },
+            viewport: Viewport::default(),
+            similarity,
+            threshold: 0.9,
+            passed: similarity >= 0.9,
+            metrics: MetricScores {
+                pixel: Some(PixelMetric {
+                    score: pixel,
+                    diff_regions: vec![],
+                    semantic_diffs: None,
+                }),
+                layout: None,
+                typography: None,
+                color: Some(ColorMetric {
+                    score: 1.0,
+                    diffs: vec![],
+                }),
+                content: None,
+            },
+            rendering: None,
+            summary: Some(Summary {
+                top_issues: vec!["Design parity check failed".to_string()],
+            }),
+            artifacts: None,
+        }
+    }
+
+    #[test]
+    fn compare_maps_to_similarity_and_metric_testcases() {
+        let xml = render_junit(&DpcOutput::Compare(compare(0.5, 0.2)));
+
+        assert!(xml.contains("<testsuites name=\"dpc\" tests=\"3\" failures=\"2\" errors=\"0\">"));
+        assert!(xml.contains("<testcase classname=\"dpc.compare\" name=\"similarity\">"));
+        assert!(xml.contains("Similarity 50.0% is below threshold 90.0%"));
+        assert!(xml.contains("pixel score 20.0% is below threshold 90.0%"));
+        assert!(xml.contains("<testcase classname=\"dpc.compare\" name=\"color\"/>"));
+        assert!(xml.contains("value=\"impl &amp; co.png\""));
+
+        let xml = render_junit(&DpcOutput::Compare(compare(0.95, 0.2)));
+        assert!(xml.contains("tests=\"3\" failures=\"0\" errors=\"0\">"));
+        assert!(xml.contains("<testcase classname=\"dpc.compare\" name=\"pixel\"/>"));
+    }
+
+    #[test]
+    fn batch_entries_become_suites_with_errors() {
+        let batch = BatchOutput {
+            version: DPC_OUTPUT_VERSION.to_string(),
+            manifest: PathBuf::from("batch.yaml"),
+            passed: false,
+            total: 2,
+            passed_count: 1,
+            failed_count: 0,
+            error_count: 1,
+            entries: vec![
+                BatchEntryOutput {
+                    name: "home".to_string(),
+                    passed: true,
+                    result: Some(compare(0.95, 0.95)),
+                    error: None,
+                },
+                BatchEntryOutput {
+                    name: "pricing".to_string(),
+                    passed: false,
+                    result: None,
+                    error: Some(ErrorPayload::new(
+                        ErrorCategory::Network,
+                        "timed out".to_string(),
+                        "raise --nav-timeout",
+                    )),
+                },
+            ],
+        };
+
+        let xml = render_junit(&DpcOutput::Batch(batch));
+
+        assert!(xml.contains("<testsuite name=\"home\" tests=\"3\" failures=\"0\" errors=\"0\">"));
+        assert!(
+            xml.contains("<testsuite name=\"pricing\" tests=\"1\" failures=\"0\" errors=\"1\">")
+        );
+        assert!(xml
+            .contains("<error message=\"timed out\" type=\"network\">raise --nav-timeout</error>"));
+    }
+}
@@ -6,6 +6,7 @@
 //!
 //! # Module Overview
 //!
+//! - [`baseline`] - Baseline snapshot store for visual regression
 //! - [`browser`] - Headless browser automation for URL capture
 //! - [`figma`] - Figma API integration and design extraction
 //! - [`image_loader`] - Local image loading and processing
@@ -13,6 +14,7 @@
 //! - [`config`] - Configuration file support
 //! - [`types`] - Core data types and structures
 //! - [`output`] - JSON output schemas
+//! - [`view_bundle`] - Serialized views for offline replay
 //!
 //! # Example
 //!
@@ -33,6 +35,7 @@
 //! # }
 //! ```
 
+pub mod baseline;
 pub mod browser;
 pub mod config;
 pub mod error;
@@ -44,16 +47,24 @@ pub mod metrics;
 pub mod output;
 pub mod resource;
 pub mod types;
+pub mod view_bundle;
 pub mod viewport;
 
+pub use baseline::{BaselineMetadata, BaselineRender, BaselineStore, DEFAULT_BASELINE_DIR};
 // Browser module re-exports
 pub use browser::{
-    url_to_normalized_view, BrowserManager, BrowserOptions, PageRenderResult, UrlToViewOptions,
-    DEFAULT_NAVIGATION_TIMEOUT, DEFAULT_NETWORK_IDLE_TIMEOUT, DEFAULT_PROCESS_TIMEOUT,
+    url_to_normalized_view, AuthOptions, AutoScroll, BasicAuth, BrowserBackend, BrowserEngine, BrowserManager,
+    BrowserOptions, DeviceEmulation, HarMode, HarUnmatched, InteractionStep, InteractiveState, PageRenderResult,
+    ProgressCallback, RenderTiming, StaticServer, UrlToViewOptions, DEFAULT_NAVIGATION_TIMEOUT,
+    DEFAULT_NETWORK_IDLE_TIMEOUT, DEFAULT_PROCESS_TIMEOUT,
 };
-pub use config::Config;
+pub use config::{Breakpoint, BrowserSettings, Config, DeviceConfig, FigmaConfig, StateTarget};
 pub use error::{DpcError, Result};
-pub use figma::{figma_to_normalized_view, FigmaClient, FigmaError, FigmaRenderOptions};
+pub use figma::{
+    figma_export_to_normalized_view, figma_to_normalized_view, figma_to_normalized_view_offline,
+    pull_figma_export, FigmaCache, FigmaCacheMode, FigmaClient, FigmaError, FigmaExportSource,
+    FigmaRenderOptions, FigmaRetryPolicy,
+};
 pub use figma_client::{
     FigmaApiClient, FigmaAuth, FigmaFileResponse, FigmaImageFormat, FigmaImageResponse,
     FigmaNodesResponse, ImageExportOptions,
@@ -93,13 +104,15 @@ pub use metrics::{
     TypographySimilarity,
 };
 pub use output::{
-    CompareArtifacts, CompareOutput, DpcOutput, ErrorOutput, FindingSeverity, GenerateCodeOutput,
-    QualityFinding, QualityFindingType, QualityOutput, ResourceDescriptor, Summary,
-    DPC_OUTPUT_VERSION,
+    BaselineAction, BaselineEntryOutput, BaselineOutput, BatchEntryOutput, BatchOutput,
+    BreakpointCompareOutput, CompareArtifacts, CompareOutput, DpcOutput, ErrorOutput,
+    FigmaPullOutput, FindingSeverity, GenerateCodeOutput, QualityFinding, QualityFindingType, QualityOutput,
+    RenderingInfo, ResourceDescriptor, ResponsiveCompareOutput, Summary, DPC_OUTPUT_VERSION,
 };
 pub use resource::{parse_resource, FigmaInfo, ParsedResource};
 pub use types::{
-    ColorMetric, ContentMetric, LayoutMetric, MetricScores, NormalizedView, PixelMetric,
-    ResourceKind, TypographyMetric,
+    ColorMetric, ContentMetric, LayoutMetric, MetricScores, NormalizedView, PageDiagnostics,
+    PixelMetric, ResourceKind, TypographyMetric,
 };
+pub use view_bundle::{read_view_bundle, write_view_bundle, write_view_json};
 pub use viewport::Viewport;
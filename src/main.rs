@@ -1,13 +1,18 @@
 mod cli;
 mod commands;
 mod formatting;
+mod junit;
 mod pipeline;
+mod report;
+mod sarif;
 mod settings;
 
 use std::process::ExitCode;
 
 use cli::Commands;
-use commands::{run_compare, run_generate_code, run_quality};
+use commands::{
+    run_baseline, run_batch, run_compare, run_figma, run_generate_code, run_quality, run_report,
+};
 
 #[tokio::main]
 async fn main() -> ExitCode {
@@ -25,6 +30,8 @@ async fn run() -> ExitCode {
             ref_type,
             impl_type,
             viewport,
+            viewports,
+            browsers,
             threshold,
             metrics,
             format,
@@ -32,6 +39,11 @@ async fn run() -> ExitCode {
             keep_artifacts,
             ignore_selectors,
             ignore_regions,
+            ref_selector,
+            impl_selector,
+            states,
+            state_nodes,
+            steps,
             artifacts_dir,
             nav_timeout,
             network_idle_timeout,
@@ -43,11 +55,20 @@ async fn run() -> ExitCode {
                 &raw_args,
                 args.config,
                 args.verbose,
+                &args.auth,
+                args.deterministic,
+                &args.har,
+                &args.device,
+                args.browser,
+                args.figma_cache,
+                &args.capture,
                 r#ref,
                 r#impl,
                 ref_type,
                 impl_type,
                 viewport,
+                viewports,
+                browsers,
                 threshold,
                 metrics,
                 format,
@@ -55,6 +76,11 @@ async fn run() -> ExitCode {
                 keep_artifacts,
                 ignore_selectors,
                 ignore_regions,
+                ref_selector,
+                impl_selector,
+                states,
+                state_nodes,
+                steps,
                 artifacts_dir,
                 nav_timeout,
                 network_idle_timeout,
@@ -76,6 +102,13 @@ async fn run() -> ExitCode {
                 &raw_args,
                 args.config,
                 args.verbose,
+                &args.auth,
+                args.deterministic,
+                &args.har,
+                &args.device,
+                args.browser,
+                args.figma_cache,
+                &args.capture,
                 input,
                 input_type,
                 viewport,
@@ -96,6 +129,13 @@ async fn run() -> ExitCode {
                 &raw_args,
                 args.config,
                 args.verbose,
+                &args.auth,
+                args.deterministic,
+                &args.har,
+                &args.device,
+                args.browser,
+                args.figma_cache,
+                &args.capture,
                 input,
                 input_type,
                 viewport,
@@ -104,5 +144,50 @@ async fn run() -> ExitCode {
             )
             .await
         }
+        Commands::Batch {
+            manifest,
+            concurrency,
+            format,
+            output,
+            keep_artifacts,
+            artifacts_dir,
+        } => {
+            run_batch(
+                args.config,
+                args.verbose,
+                &args.auth,
+                args.deterministic,
+                &args.har,
+                &args.device,
+                args.browser,
+                args.figma_cache,
+                &args.capture,
+                manifest,
+                concurrency,
+                format,
+                output,
+                keep_artifacts,
+                artifacts_dir,
+            )
+            .await
+        }
+        Commands::Baseline { command } => {
+            run_baseline(
+                &raw_args,
+                args.config,
+                args.verbose,
+                &args.auth,
+                args.deterministic,
+                &args.har,
+                &args.device,
+                args.browser,
+                args.figma_cache,
+                &args.capture,
+                command,
+            )
+            .await
+        }
+        Commands::Figma { command } => run_figma(args.config, args.verbose, command).await,
+        Commands::Report { input, output } => run_report(input, output),
     }
 }
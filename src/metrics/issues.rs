@@ -210,6 +210,9 @@ fn issues_from_typography(metric: &TypographyMetric) -> Vec<RankedIssue> {
                 TypographyIssue::FontSizeDiff => "font size",
                 TypographyIssue::FontWeightDiff => "font weight",
                 TypographyIssue::LineHeightDiff => "line height",
+                TypographyIssue::LetterSpacingDiff => "letter spacing",
+                TypographyIssue::TextTransformDiff => "text transform",
+                TypographyIssue::TextAlignDiff => "text alignment",
             })
             .collect();
 
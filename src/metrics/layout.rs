@@ -1,5 +1,8 @@
 use crate::error::DpcError;
-use crate::types::{BoundingBox, LayoutDiffKind, LayoutDiffRegion, LayoutMetric, NormalizedView};
+use crate::types::{
+    BoundingBox, DomNode, DomSnapshot, LayoutDiffKind, LayoutDiffRegion, LayoutMetric,
+    NormalizedView,
+};
 use crate::Result;
 
 use super::{Metric, MetricKind, MetricResult};
@@ -19,10 +22,12 @@ impl Default for LayoutSimilarity {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 struct LayoutElement {
     kind: ElementKind,
     bbox: BoundingBox,
+    /// Set for DOM nodes inside a shadow root or iframe, naming where they live.
+    label: Option<String>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -54,9 +59,13 @@ impl LayoutSimilarity {
             let elements = dom
                 .nodes
                 .iter()
-                .map(|node| LayoutElement {
-                    kind: element_kind_from_dom(node),
-                    bbox: node.bounding_box,
+                .map(|node| {
+                    let kind = element_kind_from_dom(node);
+                    LayoutElement {
+                        kind,
+                        bbox: node.bounding_box,
+                        label: scope_label(kind, node, dom),
+                    }
                 })
                 .collect::<Vec<_>>();
             if !elements.is_empty() {
@@ -71,6 +80,7 @@ impl LayoutSimilarity {
                 .map(|node| LayoutElement {
                     kind: element_kind_from_figma(node),
                     bbox: node.bounding_box,
+                    label: None,
                 })
                 .collect::<Vec<_>>();
             if !elements.is_empty() {
@@ -104,7 +114,7 @@ impl LayoutSimilarity {
                     height: ref_el.bbox.height,
                     kind: LayoutDiffKind::MissingElement,
                     element_type: Some(ref_el.kind.as_str().to_string()),
-                    label: None,
+                    label: ref_el.label.clone(),
                 })
                 .collect::<Vec<_>>();
 
@@ -126,8 +136,7 @@ impl LayoutSimilarity {
                 self.match_threshold,
                 self.iou_threshold,
             ) {
-                matches.push((ref_el, impl_elements[idx], iou));
-                impl_elements.remove(idx);
+                matches.push((ref_el, impl_elements.remove(idx), iou));
             }
         }
 
@@ -158,7 +167,7 @@ impl LayoutSimilarity {
                     height: ref_el.bbox.height,
                     kind: LayoutDiffKind::MissingElement,
                     element_type: Some(ref_el.kind.as_str().to_string()),
-                    label: None,
+                    label: ref_el.label.clone(),
                 });
             }
         }
@@ -171,7 +180,7 @@ impl LayoutSimilarity {
                 height: extra.bbox.height,
                 kind: LayoutDiffKind::ExtraElement,
                 element_type: Some(extra.kind.as_str().to_string()),
-                label: None,
+                label: extra.label.clone(),
             });
         }
 
@@ -184,7 +193,7 @@ impl LayoutSimilarity {
                     height: impl_el.bbox.height,
                     kind: LayoutDiffKind::PositionShift,
                     element_type: Some(impl_el.kind.as_str().to_string()),
-                    label: None,
+                    label: impl_el.label.clone(),
                 });
             }
         }
@@ -197,7 +206,7 @@ impl LayoutSimilarity {
                 height: impl_el.bbox.height,
                 kind: LayoutDiffKind::ExtraElement,
                 element_type: Some(impl_el.kind.as_str().to_string()),
-                label: None,
+                label: impl_el.label.clone(),
             });
         }
 
@@ -208,7 +217,7 @@ impl LayoutSimilarity {
     }
 }
 
-fn element_kind_from_dom(node: &crate::types::DomNode) -> ElementKind {
+fn element_kind_from_dom(node: &DomNode) -> ElementKind {
     let tag = node.tag.to_ascii_lowercase();
     match tag.as_str() {
         "button" => ElementKind::Button,
@@ -220,6 +229,22 @@ fn element_kind_from_dom(node: &crate::types::DomNode) -> ElementKind {
     }
 }
 
+/// `"<kind> in <host> shadow root"` or `"<kind> in iframe"` for nodes the page itself does
+/// not contain directly; `None` for ordinary nodes.
+fn scope_label(kind: ElementKind, node: &DomNode, dom: &DomSnapshot) -> Option<String> {
+    if let Some(host_id) = &node.shadow_host {
+        let host = dom
+            .nodes
+            .iter()
+            .find(|n| &n.id == host_id)
+            .map_or("host", |n| n.tag.as_str());
+        return Some(format!("{} in <{}> shadow root", kind.as_str(), host));
+    }
+    node.frame
+        .as_ref()
+        .map(|_| format!("{} in iframe", kind.as_str()))
+}
+
 fn element_kind_from_figma(node: &crate::types::FigmaNode) -> ElementKind {
     let kind = node.node_type.to_ascii_lowercase();
     match kind.as_str() {
@@ -473,6 +473,32 @@ fn layout_metric_reports_extra_elements() {
     assert!(layout.score < 1.0);
 }
 
+#[test]
+fn layout_metric_labels_shadow_and_frame_elements() {
+    let ref_view = view_with_dom(vec![("my-widget", bbox(0.0, 0.0, 0.5, 0.5))]);
+    let mut impl_view = view_with_dom(vec![
+        ("my-widget", bbox(0.0, 0.0, 0.5, 0.5)),
+        ("button", bbox(0.6, 0.1, 0.3, 0.3)),
+        ("img", bbox(0.1, 0.6, 0.3, 0.3)),
+    ]);
+    let nodes = &mut impl_view.dom.as_mut().unwrap().nodes;
+    nodes[1].shadow_host = Some("n0".to_string());
+    nodes[2].frame = Some("n0".to_string());
+
+    let metric = LayoutSimilarity::default();
+    let layout = match metric.compute(&ref_view, &impl_view).unwrap() {
+        MetricResult::Layout(m) => m,
+        _ => unreachable!(),
+    };
+    let labels: Vec<_> = layout
+        .diff_regions
+        .iter()
+        .filter_map(|d| d.label.as_deref())
+        .collect();
+    assert!(labels.contains(&"button in <my-widget> shadow root"));
+    assert!(labels.contains(&"image in iframe"));
+}
+
 #[test]
 fn layout_metric_missing_all_elements_scores_low() {
     let ref_view = view_with_dom(vec![
@@ -636,10 +662,55 @@ fn typography_metric_line_height_mismatch_penalized() {
     assert!(score < 1.0);
 }
 
+#[test]
+fn typography_metric_flags_letter_spacing_transform_and_alignment() {
+    let style = TypographyStyle {
+        font_family: Some("Inter".into()),
+        font_size: Some(14.0),
+        font_weight: Some("600".into()),
+        line_height: Some(20.0),
+    };
+    let set_text_style = |view: &mut NormalizedView, spacing: f32, transform: &str, align: &str| {
+        let computed = view.dom.as_mut().unwrap().nodes[0]
+            .computed_style
+            .as_mut()
+            .unwrap();
+        computed.letter_spacing = Some(spacing);
+        computed.text_transform = Some(transform.to_string());
+        computed.text_align = Some(align.to_string());
+    };
+    let mut ref_view = view_with_text("Sign up", style.clone());
+    set_text_style(&mut ref_view, 1.0, "uppercase", "start");
+    let mut impl_view = view_with_text("Sign up", style);
+    set_text_style(&mut impl_view, 1.2, "uppercase", "left");
+
+    let metric = TypographySimilarity::default();
+    let compare = |impl_view: &NormalizedView| match metric.compute(&ref_view, impl_view).unwrap() {
+        MetricResult::Typography(t) => (t.score, t.diffs),
+        _ => unreachable!(),
+    };
+    let (matching, diffs) = compare(&impl_view);
+    assert!((matching - 1.0).abs() < f32::EPSILON, "{diffs:?}");
+
+    set_text_style(&mut impl_view, 0.0, "none", "center");
+    let (score, diffs) = compare(&impl_view);
+    assert!(score < 1.0);
+    assert_eq!(
+        diffs[0].issues,
+        vec![
+            TypographyIssue::LetterSpacingDiff,
+            TypographyIssue::TextTransformDiff,
+            TypographyIssue::TextAlignDiff
+        ]
+    );
+}
+
 #[test]
 fn typography_metric_small_size_difference_within_tolerance_scores_high() {
-    let mut metric = TypographySimilarity::default();
-    metric.size_tolerance = 0.2;
+    let metric = TypographySimilarity {
+        size_tolerance: 0.2,
+        ..TypographySimilarity::default()
+    };
     let ref_view = view_with_text(
         "Hello",
         TypographyStyle {
@@ -803,6 +874,7 @@ fn dummy_view() -> NormalizedView {
         dom: None,
         figma_tree: None,
         ocr_blocks: None,
+        diagnostics: None,
     }
 }
 
@@ -815,6 +887,7 @@ fn view_from_file(path: &std::path::Path, width: u32, height: u32) -> Normalized
         dom: None,
         figma_tree: None,
         ocr_blocks: None,
+        diagnostics: None,
     }
 }
 
@@ -876,6 +949,8 @@ fn view_with_dom(nodes: Vec<(&str, crate::types::BoundingBox)>) -> NormalizedVie
                 text,
                 bounding_box: bbox,
                 computed_style: None,
+                shadow_host: None,
+                frame: None,
             }
         })
         .collect();
@@ -892,6 +967,7 @@ fn view_with_dom(nodes: Vec<(&str, crate::types::BoundingBox)>) -> NormalizedVie
         }),
         figma_tree: None,
         ocr_blocks: None,
+        diagnostics: None,
     }
 }
 
@@ -923,11 +999,15 @@ fn view_with_text(text: &str, style: TypographyStyle) -> NormalizedView {
                     display: None,
                     visibility: None,
                     opacity: None,
+                    ..ComputedStyle::default()
                 }),
+                shadow_host: None,
+                frame: None,
             }],
         }),
         figma_tree: None,
         ocr_blocks: None,
+        diagnostics: None,
     }
 }
 
@@ -940,6 +1020,7 @@ fn view_from_image(file: &NamedTempFile) -> NormalizedView {
         dom: None,
         figma_tree: None,
         ocr_blocks: None,
+        diagnostics: None,
     }
 }
 
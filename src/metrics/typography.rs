@@ -9,6 +9,8 @@ use super::{Metric, MetricKind, MetricResult};
 pub struct TypographySimilarity {
     pub size_tolerance: f32,
     pub line_height_tolerance: f32,
+    /// Absolute letter-spacing difference (CSS px) tolerated before flagging.
+    pub letter_spacing_tolerance: f32,
 }
 
 impl Default for TypographySimilarity {
@@ -16,6 +18,7 @@ impl Default for TypographySimilarity {
         Self {
             size_tolerance: 0.1,
             line_height_tolerance: 0.1,
+            letter_spacing_tolerance: 0.5,
         }
     }
 }
@@ -28,6 +31,10 @@ struct TypographyElement {
     size: Option<f32>,
     weight: Option<String>,
     line_height: Option<f32>,
+    // Only known for DOM text; Figma and OCR leave these unset and they are not compared.
+    letter_spacing: Option<f32>,
+    text_transform: Option<String>,
+    text_align: Option<String>,
 }
 
 impl TypographySimilarity {
@@ -44,6 +51,9 @@ impl TypographySimilarity {
                             size: style.font_size,
                             weight: style.font_weight.clone(),
                             line_height: style.line_height,
+                            letter_spacing: style.letter_spacing,
+                            text_transform: style.text_transform.clone(),
+                            text_align: style.text_align.clone(),
                         });
                     }
                 }
@@ -64,6 +74,9 @@ impl TypographySimilarity {
                         size: style.font_size,
                         weight: style.font_weight.clone(),
                         line_height: style.line_height,
+                        letter_spacing: None,
+                        text_transform: None,
+                        text_align: None,
                     });
                 }
             }
@@ -107,12 +120,7 @@ impl TypographySimilarity {
             let maybe_impl_list = impl_by_text.get_mut(&norm_text);
             if let Some(list) = maybe_impl_list {
                 if let Some(impl_el) = list.pop() {
-                    let (penalty, issues) = typography_penalty(
-                        ref_el,
-                        &impl_el,
-                        self.size_tolerance,
-                        self.line_height_tolerance,
-                    );
+                    let (penalty, issues) = typography_penalty(ref_el, &impl_el, self);
                     total_penalty += penalty;
                     if !issues.is_empty() {
                         diffs.push(TypographyDiff {
@@ -172,13 +180,15 @@ impl TypographySimilarity {
 fn typography_penalty(
     reference: &TypographyElement,
     implementation: &TypographyElement,
-    size_tolerance: f32,
-    line_height_tolerance: f32,
+    tolerances: &TypographySimilarity,
 ) -> (f32, Vec<TypographyIssue>) {
     const FAMILY_WEIGHT: f32 = 0.6;
     const SIZE_WEIGHT: f32 = 0.2;
     const WEIGHT_WEIGHT: f32 = 0.15;
     const LINE_WEIGHT: f32 = 0.05;
+    const SPACING_WEIGHT: f32 = 0.05;
+    const TRANSFORM_WEIGHT: f32 = 0.1;
+    const ALIGN_WEIGHT: f32 = 0.05;
 
     let mut penalty = 0.0f32;
     let mut issues = Vec::new();
@@ -193,7 +203,7 @@ fn typography_penalty(
     if let (Some(ref_size), Some(impl_size)) = (reference.size, implementation.size) {
         if ref_size > 0.0 {
             let diff = ((impl_size - ref_size) / ref_size).abs();
-            if diff > size_tolerance {
+            if diff > tolerances.size_tolerance {
                 penalty += SIZE_WEIGHT * diff.min(1.0);
                 issues.push(TypographyIssue::FontSizeDiff);
             }
@@ -210,16 +220,50 @@ fn typography_penalty(
     if let (Some(ref_lh), Some(impl_lh)) = (reference.line_height, implementation.line_height) {
         if ref_lh > 0.0 {
             let diff = ((impl_lh - ref_lh) / ref_lh).abs();
-            if diff > line_height_tolerance {
+            if diff > tolerances.line_height_tolerance {
                 penalty += LINE_WEIGHT * diff.min(1.0);
                 issues.push(TypographyIssue::LineHeightDiff);
             }
         }
     }
 
+    if let (Some(ref_ls), Some(impl_ls)) = (reference.letter_spacing, implementation.letter_spacing)
+    {
+        if (impl_ls - ref_ls).abs() > tolerances.letter_spacing_tolerance {
+            penalty += SPACING_WEIGHT;
+            issues.push(TypographyIssue::LetterSpacingDiff);
+        }
+    }
+
+    if let (Some(ref_tt), Some(impl_tt)) =
+        (&reference.text_transform, &implementation.text_transform)
+    {
+        if !ref_tt.eq_ignore_ascii_case(impl_tt) {
+            penalty += TRANSFORM_WEIGHT;
+            issues.push(TypographyIssue::TextTransformDiff);
+        }
+    }
+
+    if let (Some(ref_ta), Some(impl_ta)) = (&reference.text_align, &implementation.text_align) {
+        if canonical_align(ref_ta) != canonical_align(impl_ta) {
+            penalty += ALIGN_WEIGHT;
+            issues.push(TypographyIssue::TextAlignDiff);
+        }
+    }
+
     (penalty, issues)
 }
 
+/// Maps the logical `start`/`end` values to `left`/`right` (assuming left-to-right text).
+fn canonical_align(align: &str) -> String {
+    match align.trim().to_ascii_lowercase().as_str() {
+        "start" | "-webkit-left" => "left".to_string(),
+        "end" | "-webkit-right" => "right".to_string(),
+        "-webkit-center" => "center".to_string(),
+        other => other.to_string(),
+    }
+}
+
 fn normalize_label(input: &str) -> Option<String> {
     let lower = input.to_lowercase();
     let mut cleaned = String::new();
@@ -1,3 +1,6 @@
+use crate::browser::{
+    BrowserEngine, InteractiveState, FROZEN_TIME_MS, RANDOM_SEED, STABILIZATIONS,
+};
 use crate::error::ErrorPayload;
 use crate::types::{MetricScores, ResourceKind, Viewport};
 use serde::{Deserialize, Serialize};
@@ -8,10 +11,15 @@ pub const DPC_OUTPUT_VERSION: &str = "0.2.0";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "mode", rename_all = "kebab-case")]
+#[allow(clippy::large_enum_variant)]
 pub enum DpcOutput {
     Compare(CompareOutput),
+    ResponsiveCompare(ResponsiveCompareOutput),
     GenerateCode(GenerateCodeOutput),
     Quality(QualityOutput),
+    Batch(BatchOutput),
+    Baseline(BaselineOutput),
+    FigmaPull(FigmaPullOutput),
     Error(ErrorOutput),
 }
 
@@ -19,8 +27,12 @@ pub enum DpcOutput {
 #[serde(rename_all = "kebab-case")]
 pub enum OutputMode {
     Compare,
+    ResponsiveCompare,
     GenerateCode,
     Quality,
+    Batch,
+    Baseline,
+    FigmaPull,
     Error,
 }
 
@@ -48,6 +60,63 @@ pub struct CompareOutput {
     pub summary: Option<Summary>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub artifacts: Option<CompareArtifacts>,
+    /// Render stabilizations in effect, when `--deterministic` applied to a URL input.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rendering: Option<RenderingInfo>,
+}
+
+/// How URL inputs were stabilized before capture, so a run can be reproduced.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenderingInfo {
+    /// Stabilizations applied (e.g. `animations`, `clock`, `fonts`).
+    pub stabilizations: Vec<String>,
+    /// Epoch milliseconds every `Date` reported.
+    pub frozen_time_ms: i64,
+    /// Seed of the `Math.random` replacement.
+    pub random_seed: u32,
+}
+
+impl RenderingInfo {
+    /// The fixed stabilization set applied by deterministic rendering.
+    pub fn deterministic() -> Self {
+        Self {
+            stabilizations: STABILIZATIONS.iter().map(|s| s.to_string()).collect(),
+            frozen_time_ms: FROZEN_TIME_MS,
+            random_seed: RANDOM_SEED,
+        }
+    }
+}
+
+/// Compare run across several breakpoints and/or browser engines, one `CompareOutput`
+/// per viewport and engine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResponsiveCompareOutput {
+    pub version: String,
+    #[serde(rename = "ref")]
+    pub ref_resource: ResourceDescriptor,
+    #[serde(rename = "impl")]
+    pub impl_resource: ResourceDescriptor,
+    /// Mean similarity across breakpoints.
+    pub similarity: f32,
+    pub threshold: f32,
+    /// True only when every breakpoint meets the threshold.
+    pub passed: bool,
+    pub breakpoints: Vec<BreakpointCompareOutput>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BreakpointCompareOutput {
+    pub name: String,
+    /// Engine URL inputs were rendered in, for cross-browser runs (`--browsers`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub browser: Option<BrowserEngine>,
+    /// State the element was captured in, for interactive-state runs (`--states`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub state: Option<InteractiveState>,
+    pub result: CompareOutput,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -113,6 +182,11 @@ pub struct CompareArtifacts {
     pub ref_figma_snapshot: Option<PathBuf>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub impl_figma_snapshot: Option<PathBuf>,
+    /// Serialized views (pre-ignore) for replay via `--ref-type view`/`--impl-type view`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ref_view: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impl_view: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -130,6 +204,83 @@ pub enum QualityFindingType {
     SpacingInconsistent,
     LowContrast,
     MissingHierarchy,
+    /// Uncaught exception or `console.error` while rendering
+    ScriptError,
+    /// Request that failed or got a 4xx/5xx response while rendering
+    FailedRequest,
+    /// `<img>` that loaded no image data
+    BrokenImage,
+}
+
+/// Aggregated result of running every entry in a batch manifest.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchOutput {
+    pub version: String,
+    pub manifest: PathBuf,
+    /// True only when every entry ran and passed its threshold.
+    pub passed: bool,
+    pub total: usize,
+    pub passed_count: usize,
+    pub failed_count: usize,
+    pub error_count: usize,
+    pub entries: Vec<BatchEntryOutput>,
+}
+
+/// Outcome of a single manifest entry: either a compare result or an error.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchEntryOutput {
+    pub name: String,
+    pub passed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<CompareOutput>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorPayload>,
+}
+
+/// Result of `dpc baseline record` or `dpc baseline approve`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineOutput {
+    pub version: String,
+    pub action: BaselineAction,
+    pub scenario: String,
+    /// Root of the baseline store.
+    pub store: PathBuf,
+    pub entries: Vec<BaselineEntryOutput>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BaselineAction {
+    Record,
+    Approve,
+}
+
+/// One stored viewport of a scenario.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BaselineEntryOutput {
+    pub viewport: Viewport,
+    /// Bundle directory holding `screenshot.png`, `view.json` and `meta.json`.
+    pub path: PathBuf,
+    pub source: ResourceDescriptor,
+}
+
+/// Result of `dpc figma pull`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FigmaPullOutput {
+    pub version: String,
+    /// Export directory holding `nodes.json`, `frame.png` and `source.json`.
+    pub path: PathBuf,
+    pub file_key: String,
+    pub node_id: String,
+    pub scale: f32,
+    /// Version of the Figma file that was pulled.
+    pub file_version: String,
+    pub last_modified: String,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -172,6 +323,7 @@ mod tests {
                 color: None,
                 content: None,
             },
+            rendering: None,
             summary: Some(Summary {
                 top_issues: vec!["Minor color shift".into()],
             }),
@@ -181,6 +333,19 @@ mod tests {
         let json = serde_json::to_string(&output).expect("serialize compare output");
         assert!(json.contains("\"mode\":\"compare\""));
         assert!(json.contains("\"similarity\":0.93"));
+        assert!(!json.contains("\"rendering\""));
+    }
+
+    #[test]
+    fn rendering_info_records_stabilizations() {
+        let json = serde_json::to_value(RenderingInfo::deterministic()).expect("serialize");
+        assert_eq!(json["frozenTimeMs"], FROZEN_TIME_MS);
+        assert_eq!(json["randomSeed"], RANDOM_SEED);
+        let names = json["stabilizations"]
+            .as_array()
+            .expect("stabilizations array");
+        assert!(names.iter().any(|name| name == "animations"));
+        assert!(names.iter().any(|name| name == "fonts"));
     }
 
     #[test]
@@ -195,6 +360,8 @@ mod tests {
             impl_dom_snapshot: Some(PathBuf::from("/tmp/dpc-123/impl_dom.json")),
             ref_figma_snapshot: None,
             impl_figma_snapshot: None,
+            ref_view: Some(PathBuf::from("/tmp/dpc-123/ref_view.json")),
+            impl_view: None,
         };
 
         let output = DpcOutput::Compare(CompareOutput {
@@ -221,6 +388,7 @@ mod tests {
                 color: None,
                 content: None,
             },
+            rendering: None,
             summary: None,
             artifacts: Some(artifacts),
         });
@@ -228,6 +396,61 @@ mod tests {
         let json = serde_json::to_string(&output).expect("serialize compare output");
         assert!(json.contains("\"artifacts\""));
         assert!(json.contains("/tmp/dpc-123/ref.png"));
+        assert!(json.contains("\"refView\":\"/tmp/dpc-123/ref_view.json\""));
+        assert!(!json.contains("\"implView\""));
+    }
+
+    #[test]
+    fn responsive_compare_output_serializes() {
+        let breakpoint = CompareOutput {
+            version: DPC_OUTPUT_VERSION.to_string(),
+            ref_resource: ResourceDescriptor {
+                kind: ResourceKind::Image,
+                value: "ref.png".to_string(),
+            },
+            impl_resource: ResourceDescriptor {
+                kind: ResourceKind::Image,
+                value: "impl.png".to_string(),
+            },
+            viewport: Viewport {
+                width: 375,
+                height: 812,
+            },
+            similarity: 0.9,
+            threshold: 0.95,
+            passed: false,
+            metrics: MetricScores {
+                pixel: None,
+                layout: None,
+                typography: None,
+                color: None,
+                content: None,
+            },
+            rendering: None,
+            summary: None,
+            artifacts: None,
+        };
+        let output = DpcOutput::ResponsiveCompare(ResponsiveCompareOutput {
+            version: DPC_OUTPUT_VERSION.to_string(),
+            ref_resource: breakpoint.ref_resource.clone(),
+            impl_resource: breakpoint.impl_resource.clone(),
+            similarity: 0.9,
+            threshold: 0.95,
+            passed: false,
+            breakpoints: vec![BreakpointCompareOutput {
+                name: "mobile".to_string(),
+                browser: None,
+                state: None,
+                result: breakpoint,
+            }],
+        });
+
+        let json = serde_json::to_string(&output).expect("serialize responsive output");
+        assert!(json.contains("\"mode\":\"responsive-compare\""));
+        assert!(json.contains("\"breakpoints\":[{\"name\":\"mobile\""));
+        assert!(json.contains("\"width\":375"));
+        assert!(!json.contains("\"browser\""));
+        assert!(!json.contains("\"state\""));
     }
 
     #[test]
@@ -280,6 +503,58 @@ mod tests {
         assert!(json.contains("\"type\":\"alignment_inconsistent\""));
     }
 
+    #[test]
+    fn batch_output_serializes() {
+        let output = DpcOutput::Batch(BatchOutput {
+            version: DPC_OUTPUT_VERSION.to_string(),
+            manifest: PathBuf::from("dpc-batch.yaml"),
+            passed: false,
+            total: 1,
+            passed_count: 0,
+            failed_count: 0,
+            error_count: 1,
+            entries: vec![BatchEntryOutput {
+                name: "home".to_string(),
+                passed: false,
+                result: None,
+                error: Some(crate::error::ErrorPayload::new(
+                    crate::error::ErrorCategory::Config,
+                    "render failed".to_string(),
+                    "check the URL",
+                )),
+            }],
+        });
+
+        let json = serde_json::to_string(&output).expect("serialize batch output");
+        assert!(json.contains("\"mode\":\"batch\""));
+        assert!(json.contains("\"errorCount\":1"));
+        assert!(json.contains("\"name\":\"home\""));
+        assert!(!json.contains("\"result\""));
+    }
+
+    #[test]
+    fn baseline_output_serializes() {
+        let output = DpcOutput::Baseline(BaselineOutput {
+            version: DPC_OUTPUT_VERSION.to_string(),
+            action: BaselineAction::Approve,
+            scenario: "home".to_string(),
+            store: PathBuf::from(".dpc/baselines"),
+            entries: vec![BaselineEntryOutput {
+                viewport: Viewport::default(),
+                path: PathBuf::from(".dpc/baselines/home/1440x900"),
+                source: ResourceDescriptor {
+                    kind: ResourceKind::Url,
+                    value: "https://example.com".to_string(),
+                },
+            }],
+        });
+
+        let json = serde_json::to_string(&output).expect("serialize baseline output");
+        assert!(json.contains("\"mode\":\"baseline\""));
+        assert!(json.contains("\"action\":\"approve\""));
+        assert!(json.contains("\"scenario\":\"home\""));
+    }
+
     #[test]
     fn error_output_serializes() {
         let output = DpcOutput::Error(ErrorOutput {
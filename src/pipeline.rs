@@ -1,31 +1,88 @@
+use std::collections::HashSet;
 use std::fs::File;
 use std::io::BufWriter;
 use std::path::{Path, PathBuf};
-use std::sync::Arc;
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use image::{imageops::FilterType, GenericImageView, RgbaImage};
 use serde::{Deserialize, Serialize};
 
-use dpc_lib::types::{DomNode, MetricScores, ResourceKind, Viewport};
+use dpc_lib::types::{DomNode, MetricScores, PageDiagnostics, ResourceKind, Viewport};
 use dpc_lib::{
-    figma_to_normalized_view, generate_top_issues, image_to_normalized_view,
-    url_to_normalized_view, CompareArtifacts, DpcError, FigmaAuth, FigmaClient, FigmaRenderOptions,
-    ImageLoadOptions, NormalizedView, ParsedResource, Summary, UrlToViewOptions,
+    figma_export_to_normalized_view, figma_to_normalized_view, figma_to_normalized_view_offline,
+    generate_top_issues, image_to_normalized_view, read_view_bundle, write_view_json,
+    BrowserManager, BrowserOptions, CompareArtifacts, Config, DpcError, FigmaAuth, FigmaCacheMode,
+    FigmaClient, FigmaConfig, FigmaRenderOptions, ImageLoadOptions, InteractionStep,
+    InteractiveState, NormalizedView, ParsedResource, ProgressCallback, StaticServer, Summary,
+    UrlToViewOptions,
 };
 
+use crate::cli::{AuthArgs, HarArgs};
+use crate::settings::{resolve_auth, resolve_har};
+
+/// Build a browser manager from resolved timeouts (seconds), a session limit, the config's
+/// `[browser]` section (with `--deterministic` forcing deterministic rendering on), its
+/// `[auth]` section merged with the CLI auth flags, its interaction `steps` and `[device]`
+/// emulation, and the `--har-record`/`--har-replay` mode.
+///
+/// Fails when a cookie file, storage state, or `env:` secret cannot be read.
+#[allow(clippy::too_many_arguments)]
+pub fn browser_manager(
+    nav_timeout: u64,
+    network_idle_timeout: u64,
+    process_timeout: u64,
+    max_concurrent_sessions: usize,
+    config: &Config,
+    auth: &AuthArgs,
+    deterministic: bool,
+    har: &HarArgs,
+) -> Result<BrowserManager, DpcError> {
+    Ok(BrowserManager::new(BrowserOptions {
+        backend: config.browser.backend,
+        engine: config.browser.engine,
+        chrome_path: config.browser.chrome_path.clone(),
+        auth: resolve_auth(config, auth)?,
+        steps: config
+            .steps
+            .iter()
+            .cloned()
+            .map(InteractionStep::resolve_secrets)
+            .collect::<Result<_, _>>()?,
+        deterministic: deterministic || config.browser.deterministic,
+        har: resolve_har(har)?,
+        device: config.device.emulation(),
+        auto_scroll: config.capture.auto_scroll(),
+        full_page: config.capture.full_page,
+        navigation_timeout: Duration::from_secs(nav_timeout),
+        network_idle_timeout: Duration::from_secs(network_idle_timeout),
+        process_timeout: Duration::from_secs(process_timeout),
+        max_concurrent_sessions,
+        ..BrowserOptions::default()
+    }))
+}
+
 /// Convert a parsed resource to a NormalizedView.
+///
+/// URL renders go through `browser`, so callers sharing one manager share its session limit.
+/// `selector` scopes a URL capture to one element (see [`UrlToViewOptions::selector`]), and
+/// `state` puts that element in a hover, focus or active state first. Figma inputs go
+/// through the `figma` disk cache and retry policy.
+#[allow(clippy::too_many_arguments)]
 pub async fn resource_to_normalized_view(
     resource: &ParsedResource,
     viewport: &Viewport,
     artifacts_dir: &Path,
     prefix: &str,
-    progress: Option<Arc<dyn Fn(&str) + Send + Sync>>,
-    nav_timeout: u64,
-    network_idle_timeout: u64,
-    process_timeout: u64,
+    selector: Option<&str>,
+    state: Option<InteractiveState>,
+    browser: &BrowserManager,
+    figma: &FigmaConfig,
+    progress: Option<ProgressCallback>,
 ) -> Result<NormalizedView, Box<dyn std::error::Error + Send + Sync>> {
-    if matches!(resource.kind, ResourceKind::Url | ResourceKind::Figma) {
+    if matches!(
+        resource.kind,
+        ResourceKind::Url | ResourceKind::Html | ResourceKind::Figma
+    ) {
         if let Some(mock_path) = mock_render_image_path(prefix) {
             let screenshot_path = artifacts_dir.join(format!("{}_screenshot.png", prefix));
             let options = ImageLoadOptions {
@@ -59,17 +116,49 @@ pub async fn resource_to_normalized_view(
             .map_err(|e| format!("Image loading failed: {}", e))?;
             Ok(view)
         }
-        ResourceKind::Url => {
+        ResourceKind::Url | ResourceKind::Html => {
+            // Local HTML is served only for the length of its render.
+            let server = match resource.kind {
+                ResourceKind::Html => Some(
+                    StaticServer::start(Path::new(&resource.value))
+                        .await
+                        .map_err(|e| format!("Serving {} failed: {}", resource.value, e))?,
+                ),
+                _ => None,
+            };
+            let url = server
+                .as_ref()
+                .map_or_else(|| resource.value.clone(), StaticServer::url);
             let screenshot_path = artifacts_dir.join(format!("{}_screenshot.png", prefix));
-            let mut options = UrlToViewOptions::default();
-            options.viewport = *viewport;
-            options.progress = progress.clone();
-            options.navigation_timeout = Duration::from_secs(nav_timeout);
-            options.network_idle_timeout = Duration::from_secs(network_idle_timeout);
-            options.process_timeout = Duration::from_secs(process_timeout);
-            let view = url_to_normalized_view(resource.value.as_str(), &screenshot_path, options)
+            let options = UrlToViewOptions {
+                viewport: *viewport,
+                selector: selector.map(str::to_string),
+                state,
+                progress: progress.clone(),
+                ..browser.options().clone().into()
+            };
+            let view = browser
+                .render_url_with_options(&url, &screenshot_path, options)
                 .await
-                .map_err(|e| format!("URL rendering failed: {}", e))?;
+                .map_err(|e| -> Box<dyn std::error::Error + Send + Sync> {
+                    match e {
+                        // Step failures stay structured; see `resource_error`.
+                        DpcError::InteractionStep { .. } => Box::new(e),
+                        other => format!("URL rendering failed: {}", other).into(),
+                    }
+                })?;
+            Ok(view)
+        }
+        // Saved views keep their captured size; the viewport only applies to fresh renders.
+        ResourceKind::View => Ok(read_view_bundle(Path::new(&resource.value))?),
+        ResourceKind::FigmaExport => {
+            let output_path = artifacts_dir.join(format!("{}_figma.png", prefix));
+            let view = figma_export_to_normalized_view(
+                Path::new(&resource.value),
+                &output_path,
+                Some(*viewport),
+            )
+            .map_err(|e| format!("Figma export loading failed: {}", e))?;
             Ok(view)
         }
         ResourceKind::Figma => {
@@ -81,13 +170,6 @@ pub async fn resource_to_normalized_view(
                 .node_id
                 .clone()
                 .ok_or_else(|| DpcError::Config("Figma node-id is required".to_string()))?;
-            let auth = FigmaAuth::from_env().ok_or_else(|| {
-                DpcError::Config(
-                    "Figma token missing; set FIGMA_TOKEN or FIGMA_OAUTH_TOKEN".to_string(),
-                )
-            })?;
-            let client =
-                FigmaClient::from_auth(auth).map_err(|e| format!("Figma client error: {}", e))?;
             let output_path = artifacts_dir.join(format!("{}_figma.png", prefix));
             let options = FigmaRenderOptions {
                 file_key: figma_info.file_key.clone(),
@@ -95,7 +177,22 @@ pub async fn resource_to_normalized_view(
                 output_path,
                 viewport: Some(*viewport),
                 scale: 1.0,
+                cache: figma.cache(),
             };
+            // Offline renders never reach the API, so they need no token.
+            if figma.cache == FigmaCacheMode::Offline {
+                let view = figma_to_normalized_view_offline(&options)
+                    .map_err(|e| format!("Figma rendering failed: {}", e))?;
+                return Ok(view);
+            }
+            let auth = FigmaAuth::from_env().ok_or_else(|| {
+                DpcError::Config(
+                    "Figma token missing; set FIGMA_TOKEN or FIGMA_OAUTH_TOKEN, or use --figma-cache offline with a filled cache".to_string(),
+                )
+            })?;
+            let client = FigmaClient::from_auth(auth)
+                .map_err(|e| format!("Figma client error: {}", e))?
+                .with_retry(figma.retry.clone());
             let view = figma_to_normalized_view(&client, &options)
                 .await
                 .map_err(|e| format!("Figma rendering failed: {}", e))?;
@@ -104,6 +201,17 @@ pub async fn resource_to_normalized_view(
     }
 }
 
+/// Turn a [`resource_to_normalized_view`] failure into a `DpcError`, prefixed with which
+/// input (`what`) failed. Interaction step errors pass through unchanged so their payload
+/// keeps the `interaction` category.
+pub fn resource_error(err: Box<dyn std::error::Error + Send + Sync>, what: &str) -> DpcError {
+    match err.downcast::<DpcError>() {
+        Ok(err) if matches!(*err, DpcError::InteractionStep { .. }) => *err,
+        Ok(err) => DpcError::Config(format!("Failed to process {what}: {err}")),
+        Err(err) => DpcError::Config(format!("Failed to process {what}: {err}")),
+    }
+}
+
 /// Check for mock render image path from environment variables.
 fn mock_render_image_path(prefix: &str) -> Option<String> {
     let env_key = format!("DPC_MOCK_RENDER_{}", prefix.to_ascii_uppercase());
@@ -148,6 +256,19 @@ pub fn resolve_artifacts_dir(custom: Option<&Path>) -> (PathBuf, bool) {
     (dir, false)
 }
 
+/// Turn a batch entry or breakpoint name into a safe artifacts subdirectory name.
+pub fn artifact_subdir_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
 /// Load ignore regions from a JSON file.
 pub fn load_ignore_regions(path: &Path) -> Result<Vec<IgnoreRegion>, DpcError> {
     let data = std::fs::read_to_string(path)
@@ -182,6 +303,8 @@ pub fn apply_ignore_regions(
         .map_err(DpcError::from)?
         .to_rgba8();
     let (img_w, img_h) = image.dimensions();
+    // Absolute regions are in view (CSS) pixels; high-DPI screenshots are larger.
+    let (scale_x, scale_y) = view.pixel_scale(img_w, img_h);
 
     for region in regions {
         if region.width <= 0.0 || region.height <= 0.0 {
@@ -201,7 +324,12 @@ pub fn apply_ignore_regions(
                 region.height * img_h as f32,
             )
         } else {
-            (region.x, region.y, region.width, region.height)
+            (
+                region.x * scale_x,
+                region.y * scale_y,
+                region.width * scale_x,
+                region.height * scale_y,
+            )
         };
 
         let x0 = rx.max(0.0).floor() as u32;
@@ -249,6 +377,10 @@ pub fn parse_ignore_selectors(raw: Option<&str>) -> Vec<String> {
 }
 
 /// Apply DOM ignores by filtering nodes matching selectors.
+///
+/// A selector ending in `:shadow` or `:frame` only matches nodes inside a shadow root or
+/// an iframe (alone, it matches all of them). Ignoring a shadow host or an iframe also
+/// drops everything rendered inside it.
 pub fn apply_dom_ignores(view: &NormalizedView, selectors: &[String]) -> NormalizedView {
     if selectors.is_empty() {
         return view.clone();
@@ -256,10 +388,32 @@ pub fn apply_dom_ignores(view: &NormalizedView, selectors: &[String]) -> Normali
 
     let mut filtered = view.clone();
     if let Some(dom) = &view.dom {
+        let mut ignored: HashSet<&str> = dom
+            .nodes
+            .iter()
+            .filter(|n| matches_any_selector(n, selectors))
+            .map(|n| n.id.as_str())
+            .collect();
+        // Repeat until stable: hosts and frames can nest, and children come before parents.
+        loop {
+            let before = ignored.len();
+            for node in &dom.nodes {
+                let container_ignored = [&node.shadow_host, &node.frame]
+                    .into_iter()
+                    .flatten()
+                    .any(|id| ignored.contains(id.as_str()));
+                if container_ignored {
+                    ignored.insert(node.id.as_str());
+                }
+            }
+            if ignored.len() == before {
+                break;
+            }
+        }
         let nodes = dom
             .nodes
             .iter()
-            .filter(|n| !matches_any_selector(n, selectors))
+            .filter(|n| !ignored.contains(n.id.as_str()))
             .cloned()
             .collect();
         let mut dom_filtered = dom.clone();
@@ -274,6 +428,15 @@ fn matches_any_selector(node: &DomNode, selectors: &[String]) -> bool {
 }
 
 fn selector_matches(node: &DomNode, selector: &str) -> bool {
+    for (suffix, inside) in [
+        (":shadow", node.shadow_host.is_some()),
+        (":frame", node.frame.is_some()),
+    ] {
+        if let Some(base) = selector.strip_suffix(suffix) {
+            return inside && (base.is_empty() || selector_matches(node, base));
+        }
+    }
+
     if let Some(id) = selector.strip_prefix('#') {
         let id = id.to_ascii_lowercase();
         let attr_id = node
@@ -307,6 +470,15 @@ pub fn generate_diff_heatmap(
     impl_path: &Path,
     output_path: &Path,
 ) -> Result<(), DpcError> {
+    build_diff_heatmap(ref_path, impl_path)?
+        .save(output_path)
+        .map_err(|e| DpcError::Config(format!("Failed to save diff heatmap: {e}")))?;
+
+    Ok(())
+}
+
+/// Build the diff heatmap in memory, sized to the reference screenshot.
+pub fn build_diff_heatmap(ref_path: &Path, impl_path: &Path) -> Result<RgbaImage, DpcError> {
     let ref_img = image::open(ref_path).map_err(DpcError::from)?;
     let mut impl_img = image::open(impl_path).map_err(DpcError::from)?;
 
@@ -346,15 +518,17 @@ pub fn generate_diff_heatmap(
         }
     }
 
-    heat.save(output_path)
-        .map_err(|e| DpcError::Config(format!("Failed to save diff heatmap: {e}")))?;
-
-    Ok(())
+    Ok(heat)
 }
 
 /// Persist compare artifacts to disk.
+///
+/// `ref_raw`/`impl_raw` are the views before ignores were applied; they are saved as
+/// `ref_view.json`/`impl_view.json` so the run can be replayed with `--ref-type view`.
 pub fn persist_compare_artifacts(
     artifacts_dir: &Path,
+    ref_raw: &NormalizedView,
+    impl_raw: &NormalizedView,
     ref_view: &NormalizedView,
     impl_view: &NormalizedView,
     keep: bool,
@@ -369,6 +543,8 @@ pub fn persist_compare_artifacts(
         impl_dom_snapshot: None,
         ref_figma_snapshot: None,
         impl_figma_snapshot: None,
+        ref_view: None,
+        impl_view: None,
     };
 
     if keep {
@@ -404,6 +580,14 @@ pub fn persist_compare_artifacts(
             write_json_pretty(&path, figma_tree)?;
             artifacts.impl_figma_snapshot = Some(path);
         }
+
+        let path = artifacts_dir.join("ref_view.json");
+        write_view_json(&path, ref_raw)?;
+        artifacts.ref_view = Some(path);
+
+        let path = artifacts_dir.join("impl_view.json");
+        write_view_json(&path, impl_raw)?;
+        artifacts.impl_view = Some(path);
     }
 
     Ok(artifacts)
@@ -416,10 +600,24 @@ fn write_json_pretty<T: Serialize>(path: &Path, value: &T) -> Result<(), DpcErro
     Ok(())
 }
 
-/// Generate summary of metric scores.
-pub fn generate_summary(scores: &MetricScores, similarity: f32, threshold: f32) -> Summary {
+/// Generate summary of metric scores, led by what the rendered pages reported
+/// (`pages` pairs a label like "Implementation" with its diagnostics).
+pub fn generate_summary(
+    scores: &MetricScores,
+    similarity: f32,
+    threshold: f32,
+    pages: &[(&str, &PageDiagnostics)],
+) -> Summary {
     const MAX_SUMMARY_ISSUES: usize = 5;
-    let mut top_issues = generate_top_issues(scores, MAX_SUMMARY_ISSUES);
+    let mut top_issues: Vec<String> = pages
+        .iter()
+        .flat_map(|(label, diagnostics)| {
+            page_problems(diagnostics)
+                .into_iter()
+                .map(move |(_, line)| format!("{label} page: {line}"))
+        })
+        .collect();
+    top_issues.extend(generate_top_issues(scores, MAX_SUMMARY_ISSUES));
 
     // Add overall status
     let status = if similarity >= threshold {
@@ -440,12 +638,111 @@ pub fn generate_summary(scores: &MetricScores, similarity: f32, threshold: f32)
     Summary { top_issues }
 }
 
+/// Kind of problem a [`page_problems`] line describes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PageProblem {
+    PageError,
+    FailedRequest,
+    BrokenImage,
+    ConsoleError,
+}
+
+/// One line per kind of problem a page reported while rendering, likeliest culprit first:
+/// uncaught errors, failed requests, broken images, then console errors.
+pub fn page_problems(diagnostics: &PageDiagnostics) -> Vec<(PageProblem, String)> {
+    const LISTED: usize = 3;
+    let listed = |items: Vec<String>| {
+        let more = items.len().saturating_sub(LISTED);
+        let mut text = items
+            .into_iter()
+            .take(LISTED)
+            .collect::<Vec<_>>()
+            .join(", ");
+        if more > 0 {
+            text.push_str(&format!(" and {more} more"));
+        }
+        text
+    };
+    let first_of = |messages: &[String]| {
+        let first = messages[0].lines().next().unwrap_or_default();
+        let clipped: String = first.chars().take(160).collect();
+        if clipped.len() < first.len() {
+            format!("{clipped}…")
+        } else {
+            clipped
+        }
+    };
+
+    let mut problems = Vec::new();
+    let errors = &diagnostics.page_errors;
+    if !errors.is_empty() {
+        problems.push((
+            PageProblem::PageError,
+            format!(
+                "{}: {}",
+                counted(errors.len(), "uncaught JavaScript error"),
+                first_of(errors)
+            ),
+        ));
+    }
+    let requests = &diagnostics.failed_requests;
+    if !requests.is_empty() {
+        let items = requests
+            .iter()
+            .map(|request| match &request.resource_type {
+                Some(kind) => format!("{} {kind} {}", request.reason(), request.url),
+                None => format!("{} {}", request.reason(), request.url),
+            })
+            .collect();
+        problems.push((
+            PageProblem::FailedRequest,
+            format!(
+                "{}: {}",
+                counted(requests.len(), "failed request"),
+                listed(items)
+            ),
+        ));
+    }
+    let images = &diagnostics.broken_images;
+    if !images.is_empty() {
+        let items = images.iter().map(|image| image.src.clone()).collect();
+        problems.push((
+            PageProblem::BrokenImage,
+            format!(
+                "{}: {}",
+                counted(images.len(), "broken image"),
+                listed(items)
+            ),
+        ));
+    }
+    let console = &diagnostics.console_errors;
+    if !console.is_empty() {
+        problems.push((
+            PageProblem::ConsoleError,
+            format!(
+                "{}: {}",
+                counted(console.len(), "console error"),
+                first_of(console)
+            ),
+        ));
+    }
+    problems
+}
+
+fn counted(count: usize, noun: &str) -> String {
+    if count == 1 {
+        format!("1 {noun}")
+    } else {
+        format!("{count} {noun}s")
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use dpc_lib::types::{
-        BoundingBox, ColorDiff, ColorDiffKind, ColorMetric, DomSnapshot, LayoutDiffKind,
-        LayoutDiffRegion, LayoutMetric, MetricScores, ResourceKind, TypographyDiff,
+        BoundingBox, ColorDiff, ColorDiffKind, ColorMetric, DomSnapshot, FailedRequest,
+        LayoutDiffKind, LayoutDiffRegion, LayoutMetric, MetricScores, ResourceKind, TypographyDiff,
         TypographyIssue, TypographyMetric,
     };
     use std::collections::HashMap;
@@ -469,6 +766,8 @@ mod tests {
                 height: 1.0,
             },
             computed_style: None,
+            shadow_host: None,
+            frame: None,
         }
     }
 
@@ -485,15 +784,108 @@ mod tests {
             }),
             figma_tree: None,
             ocr_blocks: None,
+            diagnostics: None,
         }
     }
 
+    #[test]
+    fn browser_manager_applies_config_device_emulation() {
+        let mut config = Config::default();
+        config.device.preset = Some("iphone-14".to_string());
+        let manager = browser_manager(
+            30,
+            10,
+            45,
+            1,
+            &config,
+            &AuthArgs::default(),
+            false,
+            &HarArgs::default(),
+        )
+        .unwrap();
+        let device = &manager.options().device;
+        assert_eq!(device.device_scale_factor, 3.0);
+        assert!(device.is_mobile);
+    }
+
+    #[test]
+    fn browser_manager_applies_capture_settings() {
+        let mut config = Config::default();
+        config.capture.full_page = true;
+        config.capture.scroll_step = Some(400);
+        let off = browser_manager(
+            30,
+            10,
+            45,
+            1,
+            &config,
+            &AuthArgs::default(),
+            false,
+            &HarArgs::default(),
+        )
+        .unwrap();
+        assert!(off.options().full_page);
+        assert!(off.options().auto_scroll.is_none());
+
+        config.capture.auto_scroll = true;
+        let on = browser_manager(
+            30,
+            10,
+            45,
+            1,
+            &config,
+            &AuthArgs::default(),
+            false,
+            &HarArgs::default(),
+        )
+        .unwrap();
+        assert_eq!(
+            on.options().auto_scroll.map(|scroll| scroll.step),
+            Some(400)
+        );
+    }
+
     #[test]
     fn parse_ignore_selectors_normalizes_and_trims() {
         let parsed = parse_ignore_selectors(Some("  #Hero , .Ad ,p  ,, "));
         assert_eq!(parsed, vec!["#hero", ".ad", "p"]);
     }
 
+    #[test]
+    fn apply_dom_ignores_scopes_shadow_and_frame_content() {
+        let inside = |id: &str, class: &str, host: Option<&str>, frame: Option<&str>| DomNode {
+            shadow_host: host.map(str::to_string),
+            frame: frame.map(str::to_string),
+            ..make_node(id, "div", Some(class))
+        };
+        let nodes = vec![
+            make_node("widget", "my-widget", None),
+            inside("shadow-ad", "ad", Some("widget"), None),
+            make_node("light-ad", "div", Some("ad")),
+            make_node("embed", "iframe", None),
+            inside("frame-body", "page", None, Some("embed")),
+            inside("frame-widget", "card", None, Some("embed")),
+            inside("nested", "card", Some("frame-widget"), Some("embed")),
+        ];
+        let view = view_with_dom(nodes);
+        let kept = |selectors: &[&str]| -> Vec<String> {
+            let selectors: Vec<String> = selectors.iter().map(|s| s.to_string()).collect();
+            apply_dom_ignores(&view, &selectors)
+                .dom
+                .unwrap()
+                .nodes
+                .iter()
+                .map(|n| n.id.clone())
+                .collect()
+        };
+
+        assert_eq!(
+            kept(&[".ad:shadow", ":frame"]),
+            vec!["widget", "light-ad", "embed"]
+        );
+        assert_eq!(kept(&["iframe", "my-widget"]), vec!["light-ad"]);
+    }
+
     #[test]
     fn apply_dom_ignores_filters_on_id_class_and_tag() {
         let nodes = vec![
@@ -515,6 +907,34 @@ mod tests {
         assert!(kept.is_empty(), "all nodes should be ignored");
     }
 
+    #[test]
+    fn apply_ignore_regions_scales_css_pixels_to_the_screenshot() {
+        let tmp = tempfile::tempdir().expect("tempdir");
+        let shot = tmp.path().join("shot.png");
+        // A 2x render of a 10x10 CSS-pixel view.
+        RgbaImage::from_pixel(20, 20, image::Rgba([255, 255, 255, 255]))
+            .save(&shot)
+            .unwrap();
+        let view = NormalizedView {
+            screenshot_path: shot,
+            width: 10,
+            height: 10,
+            ..view_with_dom(Vec::new())
+        };
+        let region = IgnoreRegion {
+            x: 5.0,
+            y: 5.0,
+            width: 5.0,
+            height: 5.0,
+        };
+
+        let masked = apply_ignore_regions(&view, &[region], tmp.path(), "impl").unwrap();
+        let img = image::open(&masked.screenshot_path).unwrap().to_rgba8();
+        assert_eq!(img.get_pixel(9, 9)[3], 255);
+        assert_eq!(img.get_pixel(10, 10)[3], 0);
+        assert_eq!(img.get_pixel(19, 19)[3], 0);
+    }
+
     #[test]
     fn generate_diff_heatmap_creates_file() {
         let tmp = tempfile::tempdir().expect("tempdir");
@@ -559,7 +979,7 @@ mod tests {
             content: None,
         };
 
-        let summary = generate_summary(&scores, 0.4, 0.8);
+        let summary = generate_summary(&scores, 0.4, 0.8, &[]);
         assert!(
             summary
                 .top_issues
@@ -607,7 +1027,7 @@ mod tests {
             content: None,
         };
 
-        let summary = generate_summary(&scores, 0.0, 0.9);
+        let summary = generate_summary(&scores, 0.0, 0.9, &[]);
 
         assert!(
             summary
@@ -617,4 +1037,38 @@ mod tests {
             "layout issues should be surfaced even when implementation has no elements"
         );
     }
+
+    #[test]
+    fn summary_leads_with_page_problems() {
+        let diagnostics = PageDiagnostics {
+            console_errors: vec!["Warning: each child needs a key\n    at List".to_string()],
+            failed_requests: (0..5)
+                .map(|i| FailedRequest {
+                    url: format!("https://example.com/{i}.css"),
+                    resource_type: Some("stylesheet".to_string()),
+                    status: Some(404),
+                    error: None,
+                })
+                .collect(),
+            ..PageDiagnostics::default()
+        };
+        let scores = MetricScores {
+            pixel: None,
+            layout: None,
+            typography: None,
+            color: None,
+            content: None,
+        };
+
+        let summary = generate_summary(&scores, 0.5, 0.9, &[("Implementation", &diagnostics)]);
+        assert_eq!(
+            &summary.top_issues[1..],
+            [
+                "Implementation page: 5 failed requests: 404 stylesheet https://example.com/0.css, \
+                 404 stylesheet https://example.com/1.css, 404 stylesheet https://example.com/2.css \
+                 and 2 more",
+                "Implementation page: 1 console error: Warning: each child needs a key",
+            ]
+        );
+    }
 }
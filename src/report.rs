@@ -0,0 +1,790 @@
+//! Self-contained HTML reports (`--format html` and `dpc report`).
+//!
+//! Screenshots and the diff heatmap are inlined as data URIs so the file can be
+//! handed to designers without the artifacts directory.
+
+use std::fmt::Write as FmtWrite;
+use std::io::Cursor;
+use std::path::Path;
+
+use base64::Engine;
+use dpc_lib::types::{DiffSeverity, MetricScores};
+use dpc_lib::{read_view_bundle, CompareArtifacts, CompareOutput, DpcOutput, Viewport};
+use image::{DynamicImage, GenericImageView};
+use serde::Serialize;
+
+use crate::pipeline::build_diff_heatmap;
+
+const STYLE: &str = r#"
+body{font:14px/1.45 system-ui,-apple-system,Segoe UI,Roboto,sans-serif;margin:0;padding:24px;color:#1f2328;background:#f6f8fa}
+h1,h2,h3{margin:0 0 8px}
+section.compare{background:#fff;border:1px solid #d0d7de;border-radius:8px;padding:20px;margin:0 0 24px}
+.badge{display:inline-block;padding:2px 8px;border-radius:4px;color:#fff;font-weight:600;margin-right:8px}
+.pass{background:#1a7f37}.fail{background:#cf222e}.error{background:#9a6700}
+.meta{color:#57606a;margin:4px 0 12px}
+table{border-collapse:collapse;margin:8px 0 16px;width:100%}
+th,td{border:1px solid #d0d7de;padding:4px 8px;text-align:left;vertical-align:top}
+th{background:#f6f8fa}
+tr[data-region]{cursor:pointer}
+tr:target,tr.active{background:#fff8c5}
+.swatch{display:inline-block;width:14px;height:14px;border:1px solid #8c959f;vertical-align:middle;margin-right:4px}
+.toolbar{display:flex;flex-wrap:wrap;gap:8px;align-items:center;margin:8px 0}
+.toolbar button{border:1px solid #d0d7de;background:#f6f8fa;border-radius:6px;padding:4px 10px;cursor:pointer}
+.toolbar button.on{background:#0969da;color:#fff;border-color:#0969da}
+.view{display:none}
+.viewer[data-view=side] .v-side{display:grid;grid-template-columns:1fr 1fr;gap:16px}
+.viewer[data-view=swipe] .v-swipe,.viewer[data-view=onion] .v-onion,.viewer[data-view=heatmap] .v-heatmap{display:block}
+.frame{position:relative;width:100%;max-width:1200px}
+.frame img{display:block;width:100%}
+.frame img.top{position:absolute;left:0;top:0;width:100%;height:100%}
+.v-swipe img.top{clip-path:inset(0 0 0 var(--swipe,50%))}
+.v-onion img.top{opacity:var(--onion,0.5)}
+.v-heatmap img.top{opacity:.85}
+.region{position:absolute;box-sizing:border-box;border:2px solid;z-index:2}
+.region.pixel{border-color:#cf222e}.region.pixel.minor{border-color:#bf8700}
+.region.layout{border-color:#0969da;border-style:dashed}
+.region.semantic{border-color:#8250df}
+.region.active{outline:3px solid #ffd33d;z-index:3}
+.hide-pixel .region.pixel,.hide-layout .region.layout,.hide-semantic .region.semantic{display:none}
+figcaption{color:#57606a;margin-top:4px}
+pre{background:#f6f8fa;padding:12px;overflow:auto}
+"#;
+
+const SCRIPT: &str = r#"
+document.querySelectorAll('.viewer').forEach(function(v){
+  var buttons=v.querySelectorAll('[data-view-btn]');
+  buttons.forEach(function(b){b.addEventListener('click',function(){
+    v.dataset.view=b.dataset.viewBtn;
+    buttons.forEach(function(o){o.classList.toggle('on',o===b);});
+  });});
+  v.querySelectorAll('input[data-var]').forEach(function(r){
+    var apply=function(){v.style.setProperty(r.dataset.var,r.dataset.unit==='%'?r.value+'%':r.value/100);};
+    r.addEventListener('input',apply);apply();
+  });
+  v.querySelectorAll('input[data-toggle]').forEach(function(c){
+    c.addEventListener('change',function(){v.classList.toggle('hide-'+c.dataset.toggle,!c.checked);});
+  });
+});
+document.querySelectorAll('tr[data-region]').forEach(function(row){
+  row.addEventListener('click',function(){
+    document.querySelectorAll('.active').forEach(function(e){e.classList.remove('active');});
+    row.classList.add('active');
+    document.querySelectorAll('.region[data-region="'+row.dataset.region+'"]').forEach(function(b){
+      b.classList.add('active');b.scrollIntoView({block:'center',behavior:'smooth'});
+    });
+  });
+});
+"#;
+
+/// Render any output payload as a standalone HTML page.
+pub fn render_html_report(body: &DpcOutput) -> String {
+    let mut content = String::new();
+    let title = match body {
+        DpcOutput::Compare(out) => {
+            content.push_str(&compare_section(out, "cmp", None));
+            "Design parity report"
+        }
+        DpcOutput::ResponsiveCompare(out) => {
+            writeln!(
+                content,
+                "<h1>{} Responsive parity: {:.1}% combined (threshold {:.1}%)</h1>",
+                badge(out.passed),
+                out.similarity * 100.0,
+                out.threshold * 100.0
+            )
+            .ok();
+            for (idx, bp) in out.breakpoints.iter().enumerate() {
+                content.push_str(&compare_section(
+                    &bp.result,
+                    &format!("bp{idx}"),
+                    Some(&bp.name),
+                ));
+            }
+            "Responsive parity report"
+        }
+        DpcOutput::Batch(out) => {
+            writeln!(
+                content,
+                "<h1>{} Batch {}</h1><p class=\"meta\">{} entries: {} passed, {} failed, {} errors</p>",
+                badge(out.passed),
+                esc(&out.manifest.display().to_string()),
+                out.total,
+                out.passed_count,
+                out.failed_count,
+                out.error_count
+            )
+            .ok();
+            for (idx, entry) in out.entries.iter().enumerate() {
+                match (&entry.result, &entry.error) {
+                    (Some(result), _) => content.push_str(&compare_section(
+                        result,
+                        &format!("e{idx}"),
+                        Some(&entry.name),
+                    )),
+                    (None, Some(error)) => {
+                        writeln!(
+                            content,
+                            "<section class=\"compare\"><h2><span class=\"badge error\">ERROR</span>{}</h2><p>{}</p></section>",
+                            esc(&entry.name),
+                            esc(&error.message)
+                        )
+                        .ok();
+                    }
+                    (None, None) => {}
+                }
+            }
+            "Batch parity report"
+        }
+        DpcOutput::Error(out) => {
+            let message = out.message.as_deref().unwrap_or(out.error.message.as_str());
+            writeln!(
+                content,
+                "<section class=\"compare\"><h1><span class=\"badge error\">ERROR</span>{}</h1>",
+                esc(message)
+            )
+            .ok();
+            if let Some(remediation) = &out.error.remediation {
+                writeln!(content, "<p>Hint: {}</p>", esc(remediation)).ok();
+            }
+            content.push_str("</section>");
+            "DPC error"
+        }
+        other => {
+            let json = serde_json::to_string_pretty(other).unwrap_or_default();
+            writeln!(content, "<pre>{}</pre>", esc(&json)).ok();
+            "DPC report"
+        }
+    };
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n{content}\n<script>{SCRIPT}</script>\n</body>\n</html>\n"
+    )
+}
+
+/// One compare result: header, image viewer with region overlays, and diff tables.
+fn compare_section(out: &CompareOutput, id: &str, name: Option<&str>) -> String {
+    let mut buf = String::new();
+    writeln!(buf, "<section class=\"compare\" id=\"{id}\">").ok();
+    let heading = match name {
+        Some(name) => format!("{} ({})", esc(name), out.viewport),
+        None => "Design parity check".to_string(),
+    };
+    writeln!(
+        buf,
+        "<h2>{} {heading}: {:.1}% (threshold {:.1}%)</h2>",
+        badge(out.passed),
+        out.similarity * 100.0,
+        out.threshold * 100.0
+    )
+    .ok();
+    writeln!(
+        buf,
+        "<p class=\"meta\">ref: {} ({}) &middot; impl: {} ({}) &middot; viewport {}</p>",
+        esc(&out.ref_resource.value),
+        label(&out.ref_resource.kind),
+        esc(&out.impl_resource.value),
+        label(&out.impl_resource.kind),
+        out.viewport
+    )
+    .ok();
+
+    if let Some(summary) = &out.summary {
+        if !summary.top_issues.is_empty() {
+            buf.push_str("<ul>");
+            for issue in &summary.top_issues {
+                writeln!(buf, "<li>{}</li>", esc(issue)).ok();
+            }
+            buf.push_str("</ul>\n");
+        }
+    }
+    buf.push_str(&metric_scores_table(&out.metrics));
+
+    match out.artifacts.as_ref().and_then(|a| load_screens(a, out.viewport)) {
+        Some(screens) => buf.push_str(&viewer(out, id, &screens)),
+        None => buf.push_str(
+            "<p class=\"meta\">Screenshots unavailable (artifacts were not kept); showing tables only.</p>\n",
+        ),
+    }
+    buf.push_str(&diff_tables(&out.metrics, id));
+    buf.push_str("</section>\n");
+    buf
+}
+
+struct Screens {
+    ref_uri: String,
+    impl_uri: String,
+    heatmap_uri: Option<String>,
+    /// Reference view size in CSS pixels, the unit of layout regions.
+    css_size: (f32, f32),
+}
+
+fn load_screens(artifacts: &CompareArtifacts, viewport: Viewport) -> Option<Screens> {
+    let ref_path = artifacts.ref_screenshot.as_deref()?;
+    let impl_path = artifacts.impl_screenshot.as_deref()?;
+    let ref_img = image::open(ref_path).ok()?;
+    let (width, height) = ref_img.dimensions();
+    // High-DPI screenshots are larger than the view. The kept view knows its CSS size;
+    // without it, the viewport width gives the scale (full-page shots only grow taller).
+    let (scale_x, scale_y) = match artifacts
+        .ref_view
+        .as_deref()
+        .and_then(|path| read_view_bundle(path).ok())
+    {
+        Some(view) => view.pixel_scale(width, height),
+        None => {
+            let scale = (width as f32 / viewport.width.max(1) as f32).max(1.0);
+            (scale, scale)
+        }
+    };
+    let heatmap_uri = match artifacts.diff_image.as_deref().filter(|p| p.exists()) {
+        Some(path) => file_data_uri(path),
+        None => build_diff_heatmap(ref_path, impl_path)
+            .ok()
+            .and_then(|heat| png_data_uri(&DynamicImage::ImageRgba8(heat))),
+    };
+    Some(Screens {
+        ref_uri: png_data_uri(&ref_img)?,
+        impl_uri: file_data_uri(impl_path)?,
+        heatmap_uri,
+        css_size: (width as f32 / scale_x, height as f32 / scale_y),
+    })
+}
+
+fn png_data_uri(img: &DynamicImage) -> Option<String> {
+    let mut bytes = Vec::new();
+    img.write_to(&mut Cursor::new(&mut bytes), image::ImageOutputFormat::Png)
+        .ok()?;
+    Some(format!(
+        "data:image/png;base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+fn file_data_uri(path: &Path) -> Option<String> {
+    let bytes = std::fs::read(path).ok()?;
+    let mime = match path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .as_deref()
+    {
+        Some("jpg" | "jpeg") => "image/jpeg",
+        Some("webp") => "image/webp",
+        Some("gif") => "image/gif",
+        _ => "image/png",
+    };
+    Some(format!(
+        "data:{mime};base64,{}",
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    ))
+}
+
+fn viewer(out: &CompareOutput, id: &str, screens: &Screens) -> String {
+    let overlay = region_overlay(&out.metrics, id, screens.css_size);
+    let frame = |base: &str, top: Option<&str>| {
+        let top = top
+            .map(|uri| format!("<img class=\"top\" src=\"{uri}\" alt=\"\">"))
+            .unwrap_or_default();
+        format!("<div class=\"frame\"><img src=\"{base}\" alt=\"\">{top}{overlay}</div>")
+    };
+
+    let mut buf = String::new();
+    writeln!(buf, "<div class=\"viewer\" data-view=\"side\">").ok();
+    buf.push_str("<div class=\"toolbar\">");
+    let mut views = vec![
+        ("side", "Side by side"),
+        ("swipe", "Swipe"),
+        ("onion", "Onion skin"),
+    ];
+    if screens.heatmap_uri.is_some() {
+        views.push(("heatmap", "Heatmap"));
+    }
+    for (idx, (key, text)) in views.iter().enumerate() {
+        let on = if idx == 0 { " class=\"on\"" } else { "" };
+        write!(
+            buf,
+            "<button type=\"button\" data-view-btn=\"{key}\"{on}>{text}</button>"
+        )
+        .ok();
+    }
+    buf.push_str(
+        "<label>swipe <input type=\"range\" min=\"0\" max=\"100\" value=\"50\" data-var=\"--swipe\" data-unit=\"%\"></label>\
+         <label>onion <input type=\"range\" min=\"0\" max=\"100\" value=\"50\" data-var=\"--onion\"></label>\
+         <label><input type=\"checkbox\" checked data-toggle=\"pixel\"> pixel</label>\
+         <label><input type=\"checkbox\" checked data-toggle=\"layout\"> layout</label>\
+         <label><input type=\"checkbox\" checked data-toggle=\"semantic\"> semantic</label>",
+    );
+    buf.push_str("</div>\n");
+
+    writeln!(
+        buf,
+        "<div class=\"view v-side\"><figure>{}<figcaption>Reference</figcaption></figure><figure>{}<figcaption>Implementation</figcaption></figure></div>",
+        frame(&screens.ref_uri, None),
+        frame(&screens.impl_uri, None)
+    )
+    .ok();
+    writeln!(
+        buf,
+        "<div class=\"view v-swipe\">{}<p class=\"meta\">Left: reference, right: implementation.</p></div>",
+        frame(&screens.ref_uri, Some(&screens.impl_uri))
+    )
+    .ok();
+    writeln!(
+        buf,
+        "<div class=\"view v-onion\">{}<p class=\"meta\">Implementation over reference.</p></div>",
+        frame(&screens.ref_uri, Some(&screens.impl_uri))
+    )
+    .ok();
+    if let Some(heatmap) = &screens.heatmap_uri {
+        writeln!(
+            buf,
+            "<div class=\"view v-heatmap\">{}<p class=\"meta\">Green: minor, yellow: moderate, red: major.</p></div>",
+            frame(&screens.ref_uri, Some(heatmap))
+        )
+        .ok();
+    }
+    buf.push_str("</div>\n");
+    buf
+}
+
+/// Clickable boxes for every diff region, positioned in percent of the reference frame.
+///
+/// Pixel and semantic regions are normalized (0-1); layout regions are in CSS pixels of
+/// a `css_size` view.
+fn region_overlay(metrics: &MetricScores, id: &str, css_size: (f32, f32)) -> String {
+    let mut buf = String::new();
+    let mut push = |kind: &str, class: &str, idx: usize, rect: [f32; 4], title: String| {
+        let region = format!("{id}-{kind}-{idx}");
+        write!(
+            buf,
+            "<a class=\"region {kind} {class}\" data-region=\"{region}\" href=\"#{region}\" title=\"{}\" style=\"left:{:.3}%;top:{:.3}%;width:{:.3}%;height:{:.3}%\"></a>",
+            esc(&title),
+            rect[0] * 100.0,
+            rect[1] * 100.0,
+            rect[2] * 100.0,
+            rect[3] * 100.0
+        )
+        .ok();
+    };
+
+    if let Some(pixel) = &metrics.pixel {
+        for (idx, r) in pixel.diff_regions.iter().enumerate() {
+            push(
+                "pixel",
+                severity_class(r.severity),
+                idx,
+                [r.x, r.y, r.width, r.height],
+                format!("{} {}", label(&r.severity), label(&r.reason)),
+            );
+        }
+        for (idx, d) in pixel.semantic_diffs.iter().flatten().enumerate() {
+            push(
+                "semantic",
+                severity_class(d.severity),
+                idx,
+                [d.x, d.y, d.width, d.height],
+                format!("{}: {}", label(&d.diff_type), d.description),
+            );
+        }
+    }
+    if let Some(layout) = &metrics.layout {
+        let (w, h) = (css_size.0.max(1.0), css_size.1.max(1.0));
+        for (idx, r) in layout.diff_regions.iter().enumerate() {
+            push(
+                "layout",
+                "",
+                idx,
+                [r.x / w, r.y / h, r.width / w, r.height / h],
+                label(&r.kind),
+            );
+        }
+    }
+    buf
+}
+
+fn metric_scores_table(metrics: &MetricScores) -> String {
+    let scores = [
+        ("pixel", metrics.pixel.as_ref().map(|m| m.score)),
+        ("layout", metrics.layout.as_ref().map(|m| m.score)),
+        ("typography", metrics.typography.as_ref().map(|m| m.score)),
+        ("color", metrics.color.as_ref().map(|m| m.score)),
+        ("content", metrics.content.as_ref().map(|m| m.score)),
+    ];
+    let mut buf = String::from("<table><tr><th>Metric</th><th>Score</th></tr>");
+    for (name, score) in scores {
+        if let Some(score) = score {
+            write!(
+                buf,
+                "<tr><td>{name}</td><td>{:.1}%</td></tr>",
+                score * 100.0
+            )
+            .ok();
+        }
+    }
+    buf.push_str("</table>\n");
+    buf
+}
+
+fn diff_tables(metrics: &MetricScores, id: &str) -> String {
+    let mut buf = String::new();
+    let fmt_rect =
+        |x: f32, y: f32, w: f32, h: f32| format!("{x:.3}, {y:.3}, {w:.3} &times; {h:.3}");
+
+    if let Some(pixel) = metrics
+        .pixel
+        .as_ref()
+        .filter(|m| !m.diff_regions.is_empty())
+    {
+        buf.push_str("<h3>Pixel diff regions</h3><table><tr><th>#</th><th>Severity</th><th>Reason</th><th>Intensity</th><th>Region (x, y, w &times; h, normalized)</th></tr>");
+        for (idx, r) in pixel.diff_regions.iter().enumerate() {
+            write!(
+                buf,
+                "<tr id=\"{id}-pixel-{idx}\" data-region=\"{id}-pixel-{idx}\"><td>{idx}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                label(&r.severity),
+                label(&r.reason),
+                r.intensity.map(|v| format!("{v:.2}")).unwrap_or_default(),
+                fmt_rect(r.x, r.y, r.width, r.height)
+            )
+            .ok();
+        }
+        buf.push_str("</table>\n");
+    }
+
+    if let Some(diffs) = metrics
+        .pixel
+        .as_ref()
+        .and_then(|m| m.semantic_diffs.as_ref())
+        .filter(|d| !d.is_empty())
+    {
+        buf.push_str("<h3>Semantic diffs</h3><table><tr><th>#</th><th>Type</th><th>Severity</th><th>Description</th><th>Confidence</th></tr>");
+        for (idx, d) in diffs.iter().enumerate() {
+            write!(
+                buf,
+                "<tr id=\"{id}-semantic-{idx}\" data-region=\"{id}-semantic-{idx}\"><td>{idx}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                label(&d.diff_type),
+                label(&d.severity),
+                esc(&d.description),
+                d.confidence.map(|v| format!("{v:.2}")).unwrap_or_default()
+            )
+            .ok();
+        }
+        buf.push_str("</table>\n");
+    }
+
+    if let Some(layout) = metrics
+        .layout
+        .as_ref()
+        .filter(|m| !m.diff_regions.is_empty())
+    {
+        buf.push_str("<h3>Layout diffs</h3><table><tr><th>#</th><th>Kind</th><th>Element</th><th>Label</th><th>Region (x, y, w &times; h, px)</th></tr>");
+        for (idx, r) in layout.diff_regions.iter().enumerate() {
+            write!(
+                buf,
+                "<tr id=\"{id}-layout-{idx}\" data-region=\"{id}-layout-{idx}\"><td>{idx}</td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                label(&r.kind),
+                esc(r.element_type.as_deref().unwrap_or("")),
+                esc(r.label.as_deref().unwrap_or("")),
+                fmt_rect(r.x, r.y, r.width, r.height)
+            )
+            .ok();
+        }
+        buf.push_str("</table>\n");
+    }
+
+    if let Some(typography) = metrics.typography.as_ref().filter(|m| !m.diffs.is_empty()) {
+        buf.push_str("<h3>Typography diffs</h3><table><tr><th>Ref element</th><th>Impl element</th><th>Issues</th><th>Details</th></tr>");
+        for d in &typography.diffs {
+            let issues: Vec<String> = d.issues.iter().map(label).collect();
+            let details = d
+                .details
+                .as_ref()
+                .map(|v| v.to_string())
+                .unwrap_or_default();
+            write!(
+                buf,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td><code>{}</code></td></tr>",
+                esc(d.element_id_ref.as_deref().unwrap_or("")),
+                esc(d.element_id_impl.as_deref().unwrap_or("")),
+                issues.join(", "),
+                esc(&details)
+            )
+            .ok();
+        }
+        buf.push_str("</table>\n");
+    }
+
+    if let Some(color) = metrics.color.as_ref().filter(|m| !m.diffs.is_empty()) {
+        buf.push_str("<h3>Color diffs</h3><table><tr><th>Kind</th><th>Reference</th><th>Implementation</th><th>&Delta;E</th></tr>");
+        for d in &color.diffs {
+            write!(
+                buf,
+                "<tr><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>",
+                label(&d.kind),
+                swatch(&d.ref_color),
+                swatch(&d.impl_color),
+                d.delta_e.map(|v| format!("{v:.1}")).unwrap_or_default()
+            )
+            .ok();
+        }
+        buf.push_str("</table>\n");
+    }
+
+    if let Some(content) = &metrics.content {
+        if !content.missing_text.is_empty() || !content.extra_text.is_empty() {
+            buf.push_str("<h3>Content diffs</h3><table><tr><th>Missing in implementation</th><th>Extra in implementation</th></tr>");
+            let rows = content.missing_text.len().max(content.extra_text.len());
+            for idx in 0..rows {
+                write!(
+                    buf,
+                    "<tr><td>{}</td><td>{}</td></tr>",
+                    esc(content
+                        .missing_text
+                        .get(idx)
+                        .map(String::as_str)
+                        .unwrap_or("")),
+                    esc(content
+                        .extra_text
+                        .get(idx)
+                        .map(String::as_str)
+                        .unwrap_or(""))
+                )
+                .ok();
+            }
+            buf.push_str("</table>\n");
+        }
+    }
+
+    buf
+}
+
+fn swatch(hex: &str) -> String {
+    let hex = esc(hex);
+    format!("<span class=\"swatch\" style=\"background:{hex}\"></span>{hex}")
+}
+
+fn badge(passed: bool) -> &'static str {
+    if passed {
+        "<span class=\"badge pass\">PASS</span>"
+    } else {
+        "<span class=\"badge fail\">FAIL</span>"
+    }
+}
+
+fn severity_class(severity: DiffSeverity) -> &'static str {
+    match severity {
+        DiffSeverity::Minor => "minor",
+        DiffSeverity::Moderate => "moderate",
+        DiffSeverity::Major => "major",
+    }
+}
+
+/// Wire name of a serde enum (e.g. `position_shift`), matching the JSON output.
+pub(crate) fn label<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => String::new(),
+    }
+}
+
+/// Escape text for HTML/XML element content and attribute values.
+pub(crate) fn esc(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dpc_lib::output::DPC_OUTPUT_VERSION;
+    use dpc_lib::types::{
+        ColorDiff, ColorDiffKind, ColorMetric, LayoutDiffKind, LayoutDiffRegion, LayoutMetric,
+        PixelDiffReason, PixelDiffRegion, PixelMetric, ResourceKind,
+    };
+    use dpc_lib::{ResourceDescriptor, Viewport};
+    use image::RgbaImage;
+    use std::path::PathBuf;
+    use tempfile::TempDir;
+
+    fn compare_output(artifacts: Option<CompareArtifacts>) -> CompareOutput {
+        CompareOutput {
+            version: DPC_OUTPUT_VERSION.to_string(),
+            ref_resource: ResourceDescriptor {
+                kind: ResourceKind::Url,
+                value: "https://example.com/?a=<b>".to_string(),
+            },
+            impl_resource: ResourceDescriptor {
+                kind: ResourceKind::Image,
+                value: "impl.png".to_string(),
+            },
+            viewport: Viewport {
+                width: 20,
+                height: 10,
+            },
+            similarity: 0.8,
+            threshold: 0.95,
+            passed: false,
+            metrics: MetricScores {
+                pixel: Some(PixelMetric {
+                    score: 0.7,
+                    diff_regions: vec![PixelDiffRegion {
+                        x: 0.5,
+                        y: 0.0,
+                        width: 0.5,
+                        height: 1.0,
+                        severity: DiffSeverity::Major,
+                        reason: PixelDiffReason::PixelChange,
+                        intensity: Some(0.9),
+                    }],
+                    semantic_diffs: None,
+                }),
+                layout: Some(LayoutMetric {
+                    score: 0.9,
+                    diff_regions: vec![LayoutDiffRegion {
+                        x: 10.0,
+                        y: 5.0,
+                        width: 5.0,
+                        height: 5.0,
+                        kind: LayoutDiffKind::PositionShift,
+                        element_type: Some("div".to_string()),
+                        label: None,
+                    }],
+                }),
+                typography: None,
+                color: Some(ColorMetric {
+                    score: 0.8,
+                    diffs: vec![ColorDiff {
+                        kind: ColorDiffKind::PrimaryColorShift,
+                        ref_color: "#112233".to_string(),
+                        impl_color: "#445566".to_string(),
+                        delta_e: Some(12.5),
+                    }],
+                }),
+                content: None,
+            },
+            rendering: None,
+            summary: None,
+            artifacts,
+        }
+    }
+
+    #[test]
+    fn report_embeds_screenshots_and_clickable_regions() {
+        let dir = TempDir::new().expect("tempdir");
+        let ref_path = dir.path().join("ref_screenshot.png");
+        let impl_path = dir.path().join("impl_screenshot.png");
+        RgbaImage::from_pixel(20, 10, image::Rgba([0, 0, 0, 255]))
+            .save(&ref_path)
+            .unwrap();
+        RgbaImage::from_pixel(20, 10, image::Rgba([255, 255, 255, 255]))
+            .save(&impl_path)
+            .unwrap();
+        let artifacts = CompareArtifacts {
+            directory: dir.path().to_path_buf(),
+            kept: false,
+            ref_screenshot: Some(ref_path),
+            impl_screenshot: Some(impl_path),
+            diff_image: None,
+            ref_dom_snapshot: None,
+            impl_dom_snapshot: None,
+            ref_figma_snapshot: None,
+            impl_figma_snapshot: None,
+            ref_view: None,
+            impl_view: None,
+        };
+
+        let html = render_html_report(&DpcOutput::Compare(compare_output(Some(artifacts))));
+
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert_eq!(html.matches("data:image/png;base64,").count(), 8);
+        assert!(html.contains("data-view-btn=\"heatmap\""));
+        assert!(html.contains("href=\"#cmp-pixel-0\""));
+        assert!(html.contains("id=\"cmp-pixel-0\""));
+        // Layout px (10,5) on a 20x10 frame lands at 50%/50%.
+        assert!(html.contains("left:50.000%;top:50.000%"));
+        assert!(html.contains("primary_color_shift"));
+        assert!(html.contains("https://example.com/?a=&lt;b&gt;"));
+    }
+
+    #[test]
+    fn report_places_layout_regions_in_css_pixels_on_high_dpi_screenshots() {
+        let dir = TempDir::new().expect("tempdir");
+        let ref_path = dir.path().join("ref_screenshot.png");
+        let impl_path = dir.path().join("impl_screenshot.png");
+        // A 2x render of the 20x10 viewport, then a 2x full-page render 20x20 CSS tall.
+        for (height, view) in [(20, None), (40, Some(20))] {
+            for path in [&ref_path, &impl_path] {
+                RgbaImage::from_pixel(40, height, image::Rgba([0, 0, 0, 255]))
+                    .save(path)
+                    .unwrap();
+            }
+            let ref_view = view.map(|css_height| {
+                let path = dir.path().join("ref_view.json");
+                let view = dpc_lib::NormalizedView {
+                    kind: ResourceKind::Url,
+                    screenshot_path: ref_path.clone(),
+                    width: 20,
+                    height: css_height,
+                    dom: None,
+                    figma_tree: None,
+                    ocr_blocks: None,
+                    diagnostics: None,
+                };
+                dpc_lib::write_view_json(&path, &view).unwrap();
+                path
+            });
+            let artifacts = CompareArtifacts {
+                directory: dir.path().to_path_buf(),
+                kept: ref_view.is_some(),
+                ref_screenshot: Some(ref_path.clone()),
+                impl_screenshot: Some(impl_path.clone()),
+                diff_image: None,
+                ref_dom_snapshot: None,
+                impl_dom_snapshot: None,
+                ref_figma_snapshot: None,
+                impl_figma_snapshot: None,
+                ref_view,
+                impl_view: None,
+            };
+
+            let html = render_html_report(&DpcOutput::Compare(compare_output(Some(artifacts))));
+
+            let top = if view.is_some() { "25.000" } else { "50.000" };
+            assert!(html.contains(&format!("left:50.000%;top:{top}%")), "{top}");
+        }
+    }
+
+    #[test]
+    fn report_without_artifacts_still_lists_diffs() {
+        let mut out = compare_output(None);
+        out.artifacts = Some(CompareArtifacts {
+            directory: PathBuf::from("/nonexistent"),
+            kept: false,
+            ref_screenshot: Some(PathBuf::from("/nonexistent/ref.png")),
+            impl_screenshot: Some(PathBuf::from("/nonexistent/impl.png")),
+            diff_image: None,
+            ref_dom_snapshot: None,
+            impl_dom_snapshot: None,
+            ref_figma_snapshot: None,
+            impl_figma_snapshot: None,
+            ref_view: None,
+            impl_view: None,
+        });
+
+        let html = render_html_report(&DpcOutput::Compare(out));
+
+        assert!(html.contains("Screenshots unavailable"));
+        assert!(!html.contains("data:image"));
+        assert!(html.contains("Pixel diff regions"));
+        assert!(html.contains("position_shift"));
+    }
+}
@@ -3,7 +3,9 @@ use std::path::Path;
 use thiserror::Error;
 use url::Url;
 
+use crate::figma::is_figma_export;
 use crate::types::ResourceKind;
+use crate::view_bundle::is_view_bundle;
 
 #[derive(Debug, Clone)]
 pub struct ParsedResource {
@@ -26,14 +28,17 @@ pub enum ResourceParseError {
     FigmaMissingFileKey { url: String },
     #[error("Local file not found: {path}. Hint: check the path relative to the current working directory or use an absolute path.")]
     FileNotFound { path: String },
-    #[error("Unsupported file extension '{extension}'. Supported image extensions: {supported}.")]
+    #[error("Unsupported file extension '{extension}'. Supported extensions: {supported}, or a directory with index.html.")]
     UnsupportedExtension {
         extension: String,
         supported: String,
     },
+    #[error("Unrecognized JSON input {path}: it is neither a saved view nor a Figma nodes response. Hint: pass --ref-type/--impl-type view or figma-export to say which it is.")]
+    UnrecognizedJson { path: String },
 }
 
 const IMAGE_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "webp", "gif"];
+const HTML_EXTENSIONS: &[&str] = &["html", "htm"];
 
 pub fn parse_resource(
     value: &str,
@@ -109,23 +114,65 @@ fn parse_figma_url(value: &str) -> Result<FigmaInfo, ResourceParseError> {
 fn parse_local_resource(value: &str) -> Result<ParsedResource, ResourceParseError> {
     let path = Path::new(value);
 
+    if is_figma_export(path) {
+        return Ok(ParsedResource {
+            kind: ResourceKind::FigmaExport,
+            value: value.to_string(),
+            figma_info: None,
+        });
+    }
+
+    if is_view_bundle(path) {
+        return Ok(ParsedResource {
+            kind: ResourceKind::View,
+            value: value.to_string(),
+            figma_info: None,
+        });
+    }
+
+    // A static site: served from the directory, starting at its index page.
+    if path.is_dir() {
+        if !path.join("index.html").is_file() {
+            return Err(ResourceParseError::FileNotFound {
+                path: path.join("index.html").to_string_lossy().into_owned(),
+            });
+        }
+        return Ok(ParsedResource {
+            kind: ResourceKind::Html,
+            value: value.to_string(),
+            figma_info: None,
+        });
+    }
+
     let extension = path
         .extension()
         .and_then(|e| e.to_str())
         .map(|e| e.to_lowercase())
         .unwrap_or_default();
 
-    if !IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+    // JSON inputs are told apart by content above; anything else is an error.
+    if extension == "json" {
+        let display = path.to_string_lossy().into_owned();
+        return Err(if path.is_file() {
+            ResourceParseError::UnrecognizedJson { path: display }
+        } else {
+            ResourceParseError::FileNotFound { path: display }
+        });
+    }
+
+    let is_html = HTML_EXTENSIONS.contains(&extension.as_str());
+    if !is_html && !IMAGE_EXTENSIONS.contains(&extension.as_str()) {
+        let supported = [IMAGE_EXTENSIONS, HTML_EXTENSIONS].concat().join(", ");
         if extension.is_empty() {
             return Err(ResourceParseError::UnsupportedExtension {
                 extension: "no extension".to_string(),
-                supported: IMAGE_EXTENSIONS.join(", "),
+                supported,
             });
         }
 
         return Err(ResourceParseError::UnsupportedExtension {
             extension,
-            supported: IMAGE_EXTENSIONS.join(", "),
+            supported,
         });
     }
 
@@ -145,7 +192,11 @@ fn parse_local_resource(value: &str) -> Result<ParsedResource, ResourceParseErro
     }
 
     Ok(ParsedResource {
-        kind: ResourceKind::Image,
+        kind: if is_html {
+            ResourceKind::Html
+        } else {
+            ResourceKind::Image
+        },
         value: value.to_string(),
         figma_info: None,
     })
@@ -243,6 +294,70 @@ mod tests {
         assert!(matches!(res, Err(ResourceParseError::FileNotFound { .. })));
     }
 
+    #[test]
+    fn test_parse_view_bundles() {
+        let file = temp_file_with_extension("json");
+        fs::write(
+            file.path(),
+            r#"{"kind":"url","screenshotPath":"shot.png","width":10,"height":10}"#,
+        )
+        .unwrap();
+        let res = parse_resource(file.path().to_str().unwrap(), None).unwrap();
+        assert_eq!(res.kind, ResourceKind::View);
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        fs::write(dir.path().join("view.json"), "{}").unwrap();
+        let res = parse_resource(dir.path().to_str().unwrap(), None).unwrap();
+        assert_eq!(res.kind, ResourceKind::View);
+
+        let res = parse_resource("/tmp/does-not-exist_view.json", None);
+        assert!(matches!(res, Err(ResourceParseError::FileNotFound { .. })));
+    }
+
+    #[test]
+    fn test_parse_figma_export_directory() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        fs::write(dir.path().join("nodes.json"), "{}").unwrap();
+        let res = parse_resource(dir.path().to_str().unwrap(), None).unwrap();
+        assert_eq!(res.kind, ResourceKind::FigmaExport);
+
+        let file = temp_file_with_extension("json");
+        fs::write(file.path(), r#"{"name":"Design","nodes":{"1:2":{}}}"#).unwrap();
+        let res = parse_resource(file.path().to_str().unwrap(), None).unwrap();
+        assert_eq!(res.kind, ResourceKind::FigmaExport);
+    }
+
+    #[test]
+    fn test_other_json_is_not_taken_for_a_view() {
+        let file = temp_file_with_extension("json");
+        fs::write(file.path(), r#"{"name":"package","version":"1.0.0"}"#).unwrap();
+        let res = parse_resource(file.path().to_str().unwrap(), None);
+        assert!(matches!(
+            res,
+            Err(ResourceParseError::UnrecognizedJson { .. })
+        ));
+
+        let res = parse_resource(file.path().to_str().unwrap(), Some(ResourceKind::View)).unwrap();
+        assert_eq!(res.kind, ResourceKind::View);
+    }
+
+    #[test]
+    fn test_parse_local_html_file_and_directory() {
+        let file = temp_file_with_extension("html");
+        let res = parse_resource(file.path().to_str().unwrap(), None).unwrap();
+        assert_eq!(res.kind, ResourceKind::Html);
+
+        let dir = tempfile::tempdir().expect("tempdir");
+        let res = parse_resource(dir.path().to_str().unwrap(), None);
+        assert!(matches!(
+            res,
+            Err(ResourceParseError::FileNotFound { path }) if path.ends_with("index.html")
+        ));
+        fs::write(dir.path().join("index.html"), "<h1>hi</h1>").unwrap();
+        let res = parse_resource(dir.path().to_str().unwrap(), None).unwrap();
+        assert_eq!(res.kind, ResourceKind::Html);
+    }
+
     #[test]
     fn test_override_type() {
         let res = parse_resource("/some/path", Some(ResourceKind::Url)).unwrap();
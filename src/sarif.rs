@@ -0,0 +1,390 @@
+//! SARIF 2.1.0 output (`--format sarif`).
+//!
+//! Every pixel/layout/semantic region and typography/color/content diff becomes a
+//! result. SARIF regions are text-oriented, so the diff rectangle travels in the
+//! result's `properties.region` and the artifact location points at the impl resource.
+
+use dpc_lib::error::ErrorPayload;
+use dpc_lib::types::DiffSeverity;
+use dpc_lib::{CompareOutput, DpcOutput, FindingSeverity};
+use serde_json::{json, Map, Value};
+
+use crate::report::label;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Results plus the ids of the rules they reference, in first-seen order.
+#[derive(Default)]
+struct Run {
+    rules: Vec<String>,
+    results: Vec<Value>,
+}
+
+impl Run {
+    fn push(
+        &mut self,
+        rule_id: String,
+        level: &str,
+        message: String,
+        location: Value,
+        properties: Map<String, Value>,
+    ) {
+        if !self.rules.contains(&rule_id) {
+            self.rules.push(rule_id.clone());
+        }
+        let mut result = json!({
+            "ruleId": rule_id,
+            "level": level,
+            "message": { "text": message },
+            "locations": [location],
+        });
+        if !properties.is_empty() {
+            result["properties"] = Value::Object(properties);
+        }
+        self.results.push(result);
+    }
+}
+
+/// Render any output payload as a SARIF log with a single run.
+pub fn render_sarif(body: &DpcOutput) -> String {
+    let mut run = Run::default();
+    let mut successful = true;
+    match body {
+        DpcOutput::Compare(out) => compare_results(&mut run, out, Map::new()),
+        DpcOutput::ResponsiveCompare(out) => {
+            for bp in &out.breakpoints {
+                compare_results(&mut run, &bp.result, scope("breakpoint", &bp.name));
+            }
+        }
+        DpcOutput::Batch(out) => {
+            for entry in &out.entries {
+                match (&entry.result, &entry.error) {
+                    (Some(result), _) => {
+                        compare_results(&mut run, result, scope("entry", &entry.name))
+                    }
+                    (None, Some(error)) => {
+                        successful = false;
+                        error_result(&mut run, error, scope("entry", &entry.name));
+                    }
+                    (None, None) => {}
+                }
+            }
+        }
+        DpcOutput::Error(out) => {
+            successful = false;
+            error_result(&mut run, &out.error, Map::new());
+        }
+        DpcOutput::Quality(out) => {
+            for finding in &out.findings {
+                let level = match finding.severity {
+                    FindingSeverity::Error => "error",
+                    FindingSeverity::Warning => "warning",
+                    FindingSeverity::Info => "note",
+                };
+                run.push(
+                    format!("quality/{}", label(&finding.finding_type)),
+                    level,
+                    finding.message.clone(),
+                    artifact_location(&out.input.value, None),
+                    Map::new(),
+                );
+            }
+        }
+        DpcOutput::GenerateCode(_) | DpcOutput::Baseline(_) | DpcOutput::FigmaPull(_) => {}
+    }
+
+    let rules: Vec<Value> = run
+        .rules
+        .iter()
+        .map(|id| json!({ "id": id, "shortDescription": { "text": rule_description(id) } }))
+        .collect();
+    let log = json!({
+        "$schema": SARIF_SCHEMA,
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "dpc",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_REPOSITORY"),
+                    "rules": rules,
+                }
+            },
+            "invocations": [{ "executionSuccessful": successful }],
+            "results": run.results,
+        }]
+    });
+    serde_json::to_string_pretty(&log).unwrap_or_default()
+}
+
+fn compare_results(run: &mut Run, out: &CompareOutput, base: Map<String, Value>) {
+    let target = out.impl_resource.value.as_str();
+    let mut props = base;
+    props.insert("viewport".into(), json!(out.viewport.to_string()));
+    let with_region = |x: f32, y: f32, w: f32, h: f32, units: &str| {
+        let mut p = props.clone();
+        p.insert(
+            "region".into(),
+            json!({ "x": x, "y": y, "width": w, "height": h, "units": units }),
+        );
+        p
+    };
+
+    if !out.passed {
+        run.push(
+            "threshold".to_string(),
+            "error",
+            format!(
+                "Similarity {:.1}% is below threshold {:.1}% (ref: {})",
+                out.similarity * 100.0,
+                out.threshold * 100.0,
+                out.ref_resource.value
+            ),
+            artifact_location(target, None),
+            props.clone(),
+        );
+    }
+
+    if let Some(pixel) = &out.metrics.pixel {
+        for r in &pixel.diff_regions {
+            run.push(
+                format!("pixel/{}", label(&r.reason)),
+                severity_level(r.severity),
+                format!(
+                    "{} pixel difference at ({:.3}, {:.3}) size {:.3}x{:.3} (normalized)",
+                    label(&r.severity),
+                    r.x,
+                    r.y,
+                    r.width,
+                    r.height
+                ),
+                artifact_location(target, None),
+                with_region(r.x, r.y, r.width, r.height, "normalized"),
+            );
+        }
+        for d in pixel.semantic_diffs.iter().flatten() {
+            run.push(
+                format!("semantic/{}", label(&d.diff_type)),
+                severity_level(d.severity),
+                d.description.clone(),
+                artifact_location(target, None),
+                with_region(d.x, d.y, d.width, d.height, "normalized"),
+            );
+        }
+    }
+
+    if let Some(layout) = &out.metrics.layout {
+        for r in &layout.diff_regions {
+            let element = r.label.as_deref().or(r.element_type.as_deref());
+            run.push(
+                format!("layout/{}", label(&r.kind)),
+                "warning",
+                format!(
+                    "{} for {} at ({:.0}, {:.0}) size {:.0}x{:.0}px",
+                    label(&r.kind),
+                    element.unwrap_or("element"),
+                    r.x,
+                    r.y,
+                    r.width,
+                    r.height
+                ),
+                artifact_location(target, element),
+                with_region(r.x, r.y, r.width, r.height, "px"),
+            );
+        }
+    }
+
+    if let Some(typography) = &out.metrics.typography {
+        for d in &typography.diffs {
+            let issues: Vec<String> = d.issues.iter().map(label).collect();
+            let element = d.element_id_impl.as_deref().or(d.element_id_ref.as_deref());
+            let rule = issues.first().map(String::as_str).unwrap_or("mismatch");
+            run.push(
+                format!("typography/{rule}"),
+                "warning",
+                format!(
+                    "Typography mismatch ({}) on {}",
+                    issues.join(", "),
+                    element.unwrap_or("element")
+                ),
+                artifact_location(target, element),
+                props.clone(),
+            );
+        }
+    }
+
+    if let Some(color) = &out.metrics.color {
+        for d in &color.diffs {
+            run.push(
+                format!("color/{}", label(&d.kind)),
+                "warning",
+                format!(
+                    "Expected {}, got {}{}",
+                    d.ref_color,
+                    d.impl_color,
+                    d.delta_e
+                        .map(|v| format!(" (deltaE {v:.1})"))
+                        .unwrap_or_default()
+                ),
+                artifact_location(target, None),
+                props.clone(),
+            );
+        }
+    }
+
+    if let Some(content) = &out.metrics.content {
+        for text in &content.missing_text {
+            run.push(
+                "content/missing_text".to_string(),
+                "warning",
+                format!("Missing text: {text}"),
+                artifact_location(target, None),
+                props.clone(),
+            );
+        }
+        for text in &content.extra_text {
+            run.push(
+                "content/extra_text".to_string(),
+                "note",
+                format!("Unexpected text: {text}"),
+                artifact_location(target, None),
+                props.clone(),
+            );
+        }
+    }
+}
+
+fn error_result(run: &mut Run, error: &ErrorPayload, props: Map<String, Value>) {
+    let message = match &error.remediation {
+        Some(hint) => format!("{} (hint: {hint})", error.message),
+        None => error.message.clone(),
+    };
+    run.push(
+        format!("error/{}", label(&error.category)),
+        "error",
+        message,
+        json!({}),
+        props,
+    );
+}
+
+fn artifact_location(uri: &str, element: Option<&str>) -> Value {
+    let mut location = json!({ "physicalLocation": { "artifactLocation": { "uri": uri } } });
+    if let Some(name) = element {
+        location["logicalLocations"] = json!([{ "name": name, "kind": "element" }]);
+    }
+    location
+}
+
+fn scope(key: &str, name: &str) -> Map<String, Value> {
+    let mut props = Map::new();
+    props.insert(key.into(), json!(name));
+    props
+}
+
+fn severity_level(severity: DiffSeverity) -> &'static str {
+    match severity {
+        DiffSeverity::Major => "error",
+        DiffSeverity::Moderate => "warning",
+        DiffSeverity::Minor => "note",
+    }
+}
+
+fn rule_description(id: &str) -> &'static str {
+    match id.split('/').next().unwrap_or(id) {
+        "threshold" => "Combined similarity below threshold",
+        "pixel" => "Pixel difference region",
+        "semantic" => "Semantic visual difference",
+        "layout" => "Layout difference",
+        "typography" => "Typography mismatch",
+        "color" => "Color palette difference",
+        "content" => "Text content difference",
+        "quality" => "Design quality finding",
+        _ => "dpc run failed",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dpc_lib::output::DPC_OUTPUT_VERSION;
+    use dpc_lib::types::{
+        LayoutDiffKind, LayoutDiffRegion, LayoutMetric, MetricScores, PixelDiffReason,
+        PixelDiffRegion, PixelMetric, ResourceKind,
+    };
+    use dpc_lib::{ResourceDescriptor, Viewport};
+
+    #[test]
+    fn regions_become_results_with_locations() {
+        let out = CompareOutput {
+            version: DPC_OUTPUT_VERSION.to_string(),
+            ref_resource: ResourceDescriptor {
+                kind: ResourceKind::Url,
+                value: "https://design".to_string(),
+            },
+            impl_resource: ResourceDescriptor {
+                kind: ResourceKind::Url,
+                value: "https://build".to_string(),
+            },
+            viewport: Viewport::default(),
+            similarity: 0.5,
+            threshold: 0.9,
+            passed: false,
+            metrics: MetricScores {
+                pixel: Some(PixelMetric {
+                    score: 0.5,
+                    diff_regions: vec![PixelDiffRegion {
+                        x: 0.1,
+                        y: 0.2,
+                        width: 0.3,
+                        height: 0.4,
+                        severity: DiffSeverity::Moderate,
+                        reason: PixelDiffReason::PixelChange,
+                        intensity: None,
+                    }],
+                    semantic_diffs: None,
+                }),
+                layout: Some(LayoutMetric {
+                    score: 0.8,
+                    diff_regions: vec![LayoutDiffRegion {
+                        x: 10.0,
+                        y: 20.0,
+                        width: 30.0,
+                        height: 40.0,
+                        kind: LayoutDiffKind::MissingElement,
+                        element_type: Some("button".to_string()),
+                        label: Some("Buy".to_string()),
+                    }],
+                }),
+                typography: None,
+                color: None,
+                content: None,
+            },
+            rendering: None,
+            summary: None,
+            artifacts: None,
+        };
+
+        let log: Value = serde_json::from_str(&render_sarif(&DpcOutput::Compare(out))).unwrap();
+        let run = &log["runs"][0];
+        let results = run["results"].as_array().unwrap();
+
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0]["ruleId"], "threshold");
+        assert_eq!(results[1]["ruleId"], "pixel/pixel_change");
+        assert_eq!(results[1]["level"], "warning");
+        assert_eq!(results[1]["properties"]["region"]["units"], "normalized");
+        assert_eq!(
+            results[1]["locations"][0]["physicalLocation"]["artifactLocation"]["uri"],
+            "https://build"
+        );
+        assert_eq!(results[2]["ruleId"], "layout/missing_element");
+        assert_eq!(
+            results[2]["locations"][0]["logicalLocations"][0]["name"],
+            "Buy"
+        );
+        assert_eq!(run["tool"]["driver"]["rules"].as_array().unwrap().len(), 3);
+        assert_eq!(run["invocations"][0]["executionSuccessful"], true);
+    }
+}
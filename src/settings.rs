@@ -1,7 +1,19 @@
 use std::path::Path;
+use std::time::Duration;
 
+use clap::ValueEnum;
+use dpc_lib::browser::{ColorScheme, InteractiveState, ReducedMotion};
 use dpc_lib::types::Viewport;
-use dpc_lib::{Config, DpcError, ScoreWeights};
+use dpc_lib::{
+    AuthOptions, Breakpoint, BrowserEngine, Config, DpcError, FigmaCacheMode, HarMode,
+    HarUnmatched, ScoreWeights, StateTarget,
+};
+
+use crate::cli::{
+    AuthArgs, BrowserEngine as CliBrowserEngine, CaptureArgs, ColorScheme as CliColorScheme,
+    DeviceArgs, FigmaCacheMode as CliFigmaCacheMode, HarArgs, HarUnmatched as CliHarUnmatched,
+    InteractiveState as CliInteractiveState, ReducedMotion as CliReducedMotion,
+};
 
 /// Tracks which CLI flags were explicitly provided vs. defaulted.
 #[derive(Debug, Default)]
@@ -90,6 +102,236 @@ pub fn resolve_compare_settings(
     }
 }
 
+/// Pick the breakpoints for a compare run; an empty result means a single-viewport run.
+///
+/// `--viewports` wins (reusing names/node-ids from config breakpoints with the same viewport),
+/// an explicit `--viewport` forces a single run, and otherwise config `[[breakpoints]]` apply.
+/// `--viewports` entries that resolve to the same breakpoint label are rejected.
+pub fn resolve_breakpoints(
+    cli_viewports: Option<&[Viewport]>,
+    viewport_flag: bool,
+    config: &Config,
+) -> Result<Vec<Breakpoint>, DpcError> {
+    match cli_viewports {
+        Some(viewports) => {
+            let mut labels = std::collections::HashSet::new();
+            viewports
+                .iter()
+                .map(|vp| {
+                    let bp = config
+                        .breakpoints
+                        .iter()
+                        .find(|bp| bp.viewport == *vp)
+                        .cloned()
+                        .unwrap_or_else(|| Breakpoint::from_viewport(*vp));
+                    if !labels.insert(bp.label()) {
+                        return Err(DpcError::Config(format!(
+                            "--viewports lists breakpoint '{}' more than once",
+                            bp.label()
+                        )));
+                    }
+                    Ok(bp)
+                })
+                .collect()
+        }
+        None if viewport_flag => Ok(Vec::new()),
+        None => Ok(config.breakpoints.clone()),
+    }
+}
+
+/// Merge CLI auth flags over config `[auth]` and resolve files and `env:` secrets.
+///
+/// `--header` adds to (or replaces, by name) the configured headers; the other flags
+/// replace their config key.
+pub fn resolve_auth(config: &Config, args: &AuthArgs) -> Result<AuthOptions, DpcError> {
+    let mut auth = config.auth.clone();
+    if let Some(path) = &args.cookies {
+        auth.cookies = Some(path.clone());
+    }
+    if let Some(spec) = &args.basic_auth {
+        auth.basic_auth = Some(spec.clone());
+    }
+    if let Some(path) = &args.storage_state {
+        auth.storage_state = Some(path.clone());
+    }
+    let mut resolved = auth.resolve()?;
+    for spec in &args.headers {
+        let (name, value) = dpc_lib::browser::parse_header(spec)?;
+        resolved.headers.insert(name, value);
+    }
+    Ok(resolved)
+}
+
+/// HAR mode from `--har-record`/`--har-replay`, checked before any rendering.
+pub fn resolve_har(args: &HarArgs) -> Result<Option<HarMode>, DpcError> {
+    let mode = match (&args.har_record, &args.har_replay) {
+        (Some(path), _) => HarMode::Record(path.clone()),
+        (None, Some(path)) => HarMode::Replay {
+            path: path.clone(),
+            unmatched: match args.har_unmatched {
+                CliHarUnmatched::Fail => HarUnmatched::Fail,
+                CliHarUnmatched::Stub => HarUnmatched::Stub,
+            },
+        },
+        (None, None) => return Ok(None),
+    };
+    mode.check()?;
+    Ok(Some(mode))
+}
+
+/// Merge the CLI device flags over config `[device]`, validate the result, and let a
+/// device preset's viewport replace the configured one (`--viewport` still wins later).
+pub fn with_device_args(mut config: Config, args: &DeviceArgs) -> Result<Config, DpcError> {
+    let device = &mut config.device;
+    if let Some(name) = &args.device {
+        device.preset = Some(name.clone());
+    }
+    if let Some(scale) = args.device_scale_factor {
+        device.device_scale_factor = Some(scale);
+    }
+    if args.mobile {
+        device.is_mobile = Some(true);
+    }
+    if args.touch {
+        device.has_touch = Some(true);
+    }
+    if let Some(agent) = &args.user_agent {
+        device.user_agent = Some(agent.clone());
+    }
+    if let Some(scheme) = args.color_scheme {
+        device.color_scheme = Some(match scheme {
+            CliColorScheme::Light => ColorScheme::Light,
+            CliColorScheme::Dark => ColorScheme::Dark,
+            CliColorScheme::NoPreference => ColorScheme::NoPreference,
+        });
+    }
+    if let Some(motion) = args.reduced_motion {
+        device.reduced_motion = Some(match motion {
+            CliReducedMotion::Reduce => ReducedMotion::Reduce,
+            CliReducedMotion::NoPreference => ReducedMotion::NoPreference,
+        });
+    }
+    device
+        .validate()
+        .map_err(|err| DpcError::Config(format!("Invalid device settings: {err}")))?;
+    if let Some(preset) = config.device.preset() {
+        config.viewport = preset.viewport;
+    }
+    Ok(config)
+}
+
+/// Apply `--full-page` and the auto-scroll flags to `[capture]`; any `--scroll-*` flag
+/// turns auto-scroll on.
+pub fn with_capture_args(mut config: Config, args: &CaptureArgs) -> Config {
+    let capture = &mut config.capture;
+    capture.full_page |= args.full_page;
+    if let Some(step) = args.scroll_step {
+        capture.scroll_step = Some(step);
+    }
+    if let Some(height) = args.scroll_max_height {
+        capture.scroll_max_height = Some(height);
+    }
+    if let Some(ms) = args.scroll_settle_ms {
+        capture.scroll_settle = Some(Duration::from_millis(ms));
+    }
+    capture.auto_scroll |= args.auto_scroll
+        || args.scroll_step.is_some()
+        || args.scroll_max_height.is_some()
+        || args.scroll_settle_ms.is_some();
+    config
+}
+
+/// The library engine for a `--browser`/`--browsers` value.
+pub fn browser_engine(engine: CliBrowserEngine) -> BrowserEngine {
+    match engine {
+        CliBrowserEngine::Chromium => BrowserEngine::Chromium,
+        CliBrowserEngine::Firefox => BrowserEngine::Firefox,
+        CliBrowserEngine::Webkit => BrowserEngine::Webkit,
+    }
+}
+
+/// The library state for a `--states`/`--state-node` value.
+pub fn interactive_state(state: CliInteractiveState) -> InteractiveState {
+    match state {
+        CliInteractiveState::Rest => InteractiveState::Rest,
+        CliInteractiveState::Hover => InteractiveState::Hover,
+        CliInteractiveState::Focus => InteractiveState::Focus,
+        CliInteractiveState::Active => InteractiveState::Active,
+    }
+}
+
+/// Pick the elements and states for an interactive-state compare; empty means none.
+///
+/// `--states` replaces config `[[states]]` with a single target: the element the capture
+/// selectors point at, with each `--state-node STATE=NODE_ID` as a reference node-id.
+pub fn resolve_state_targets(
+    states: Option<&[CliInteractiveState]>,
+    state_nodes: &[String],
+    config: &Config,
+) -> Result<Vec<StateTarget>, DpcError> {
+    let Some(states) = states else {
+        return Ok(config.states.clone());
+    };
+    let mut resolved = Vec::with_capacity(states.len());
+    for state in states.iter().copied().map(interactive_state) {
+        if !resolved.contains(&state) {
+            resolved.push(state);
+        }
+    }
+    let mut target = StateTarget::from_states(resolved);
+    for entry in state_nodes {
+        let (state, node_id) = entry
+            .split_once('=')
+            .filter(|(_, node_id)| !node_id.trim().is_empty())
+            .ok_or_else(|| {
+                DpcError::Config(format!("--state-node expects STATE=NODE_ID, got '{entry}'"))
+            })?;
+        let state = CliInteractiveState::from_str(state.trim(), true)
+            .map(interactive_state)
+            .map_err(|_| {
+                DpcError::Config(format!(
+                    "--state-node: unknown state '{state}' (expected rest, hover, focus or active)"
+                ))
+            })?;
+        target
+            .ref_node_ids
+            .insert(state, node_id.trim().to_string());
+    }
+    Ok(vec![target])
+}
+
+/// Let `--browser` replace config `[browser] engine`.
+pub fn with_browser_arg(mut config: Config, browser: Option<CliBrowserEngine>) -> Config {
+    if let Some(engine) = browser {
+        config.browser.engine = browser_engine(engine);
+    }
+    config
+}
+
+/// Let `--figma-cache` replace config `[figma] cache`.
+pub fn with_figma_cache_arg(mut config: Config, mode: Option<CliFigmaCacheMode>) -> Config {
+    if let Some(mode) = mode {
+        config.figma.cache = match mode {
+            CliFigmaCacheMode::Off => FigmaCacheMode::Off,
+            CliFigmaCacheMode::Read => FigmaCacheMode::Read,
+            CliFigmaCacheMode::Refresh => FigmaCacheMode::Refresh,
+            CliFigmaCacheMode::Offline => FigmaCacheMode::Offline,
+        };
+    }
+    config
+}
+
+/// Engines for a cross-browser compare (`--browsers`), in order and without repeats.
+pub fn resolve_browsers(browsers: &[CliBrowserEngine]) -> Vec<BrowserEngine> {
+    let mut engines = Vec::with_capacity(browsers.len());
+    for engine in browsers.iter().copied().map(browser_engine) {
+        if !engines.contains(&engine) {
+            engines.push(engine);
+        }
+    }
+    engines
+}
+
 /// Load config from a TOML file, central config, or return defaults.
 /// Priority: explicit path > ~/.config/dpc/config.toml > defaults
 pub fn load_config(path: Option<&Path>) -> Result<Config, DpcError> {
@@ -173,7 +415,6 @@ pub fn format_effective_config(
 mod tests {
     use super::*;
     use dpc_lib::config::{MetricWeights, SemanticConfig, Timeouts};
-    use std::time::Duration;
 
     #[test]
     fn resolve_compare_settings_prefers_config_when_flags_absent() {
@@ -196,6 +437,14 @@ mod tests {
                 process: Duration::from_secs(7),
             },
             semantic: SemanticConfig::default(),
+            breakpoints: Vec::new(),
+            browser: Default::default(),
+            auth: Default::default(),
+            steps: Vec::new(),
+            device: Default::default(),
+            capture: Default::default(),
+            states: Vec::new(),
+            figma: Default::default(),
         };
         let flags = CompareFlagSources::default();
         let resolved = resolve_compare_settings(
@@ -252,6 +501,197 @@ mod tests {
         assert_eq!(resolved.process_timeout, 70);
     }
 
+    #[test]
+    fn resolve_breakpoints_prefers_cli_then_viewport_flag_then_config() {
+        let mobile = Breakpoint {
+            name: Some("mobile".to_string()),
+            ref_node_id: Some("1:2".to_string()),
+            ..Breakpoint::from_viewport(Viewport {
+                width: 375,
+                height: 812,
+            })
+        };
+        let cfg = Config {
+            breakpoints: vec![mobile.clone()],
+            ..Config::default()
+        };
+
+        let cli = [
+            Viewport {
+                width: 375,
+                height: 812,
+            },
+            Viewport {
+                width: 1024,
+                height: 768,
+            },
+        ];
+        let resolved = resolve_breakpoints(Some(&cli), false, &cfg).unwrap();
+        assert_eq!(
+            resolved,
+            vec![mobile.clone(), Breakpoint::from_viewport(cli[1])]
+        );
+
+        assert!(resolve_breakpoints(None, true, &cfg).unwrap().is_empty());
+        assert_eq!(
+            resolve_breakpoints(None, false, &cfg).unwrap(),
+            vec![mobile]
+        );
+
+        let err = resolve_breakpoints(Some(&[cli[1], cli[1]]), false, &cfg).unwrap_err();
+        assert!(matches!(err, DpcError::Config(msg) if msg.contains("'1024x768'")));
+    }
+
+    #[test]
+    fn resolve_auth_merges_cli_flags_over_config() {
+        let mut cfg = Config::default();
+        cfg.auth
+            .headers
+            .insert("X-Env".to_string(), "staging".to_string());
+        cfg.auth.basic_auth = Some("config:pw".to_string());
+        let args = AuthArgs {
+            headers: vec!["X-Env: prod".to_string(), "X-Trace: 1".to_string()],
+            basic_auth: Some("cli:pw2".to_string()),
+            ..AuthArgs::default()
+        };
+
+        let auth = resolve_auth(&cfg, &args).unwrap();
+        assert_eq!(auth.headers["X-Env"], "prod");
+        assert_eq!(auth.headers["X-Trace"], "1");
+        assert_eq!(auth.basic_auth.unwrap().username, "cli");
+
+        let missing = AuthArgs {
+            cookies: Some("does-not-exist.txt".into()),
+            ..AuthArgs::default()
+        };
+        assert!(resolve_auth(&cfg, &missing).is_err());
+    }
+
+    #[test]
+    fn state_flags_replace_config_targets() {
+        let configured = StateTarget {
+            selector: Some(".card".to_string()),
+            ..StateTarget::from_states(vec![InteractiveState::Hover])
+        };
+        let cfg = Config {
+            states: vec![configured.clone()],
+            ..Config::default()
+        };
+        assert_eq!(
+            resolve_state_targets(None, &[], &cfg).unwrap(),
+            vec![configured]
+        );
+
+        let cli = [
+            CliInteractiveState::Hover,
+            CliInteractiveState::Active,
+            CliInteractiveState::Hover,
+        ];
+        let nodes = ["Hover=12-40".to_string()];
+        let targets = resolve_state_targets(Some(&cli), &nodes, &cfg).unwrap();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(
+            targets[0].states,
+            vec![InteractiveState::Hover, InteractiveState::Active]
+        );
+        assert_eq!(targets[0].selector, None);
+        assert_eq!(targets[0].ref_node_ids[&InteractiveState::Hover], "12-40");
+
+        for bad in ["hover", "hover=", "pressed=1-2"] {
+            assert!(resolve_state_targets(Some(&cli), &[bad.to_string()], &cfg).is_err());
+        }
+    }
+
+    #[test]
+    fn figma_cache_flag_overrides_config_mode() {
+        let mut cfg = Config::default();
+        cfg.figma.cache = FigmaCacheMode::Off;
+        assert_eq!(
+            with_figma_cache_arg(cfg.clone(), None).figma.cache,
+            FigmaCacheMode::Off
+        );
+        assert_eq!(
+            with_figma_cache_arg(cfg, Some(CliFigmaCacheMode::Offline))
+                .figma
+                .cache,
+            FigmaCacheMode::Offline
+        );
+    }
+
+    #[test]
+    fn browser_flags_override_config_engine_and_dedupe() {
+        let mut cfg = Config::default();
+        cfg.browser.engine = BrowserEngine::Webkit;
+        assert_eq!(
+            with_browser_arg(cfg.clone(), None).browser.engine,
+            BrowserEngine::Webkit
+        );
+        assert_eq!(
+            with_browser_arg(cfg, Some(CliBrowserEngine::Firefox))
+                .browser
+                .engine,
+            BrowserEngine::Firefox
+        );
+        assert_eq!(
+            resolve_browsers(&[
+                CliBrowserEngine::Webkit,
+                CliBrowserEngine::Chromium,
+                CliBrowserEngine::Webkit,
+            ]),
+            vec![BrowserEngine::Webkit, BrowserEngine::Chromium]
+        );
+    }
+
+    #[test]
+    fn device_args_override_config_and_set_the_preset_viewport() {
+        let mut cfg = Config::default();
+        cfg.device.preset = Some("iphone-14".to_string());
+        cfg.device.color_scheme = Some(ColorScheme::Light);
+        let args = DeviceArgs {
+            device: Some("pixel-7".to_string()),
+            color_scheme: Some(CliColorScheme::Dark),
+            ..DeviceArgs::default()
+        };
+
+        let cfg = with_device_args(cfg, &args).unwrap();
+        assert_eq!(
+            cfg.viewport,
+            Viewport {
+                width: 412,
+                height: 915
+            }
+        );
+        let emulation = cfg.device.emulation();
+        assert_eq!(emulation.device_scale_factor, 2.625);
+        assert_eq!(emulation.color_scheme, Some(ColorScheme::Dark));
+
+        let unknown = DeviceArgs {
+            device: Some("nokia-3310".to_string()),
+            ..DeviceArgs::default()
+        };
+        let err = with_device_args(Config::default(), &unknown).unwrap_err();
+        assert!(err.to_string().contains("unknown device preset"));
+    }
+
+    #[test]
+    fn scroll_flags_imply_auto_scroll() {
+        let mut cfg = Config::default();
+        cfg.capture.full_page = true;
+        let cfg = with_capture_args(
+            cfg,
+            &CaptureArgs {
+                scroll_settle_ms: Some(50),
+                ..CaptureArgs::default()
+            },
+        );
+        assert!(cfg.capture.full_page);
+        let scroll = cfg.capture.auto_scroll().expect("auto-scroll on");
+        assert_eq!(scroll.settle, Duration::from_millis(50));
+
+        let plain = with_capture_args(Config::default(), &CaptureArgs::default());
+        assert!(plain.capture.auto_scroll().is_none());
+    }
+
     #[test]
     fn format_effective_config_includes_all_fields() {
         let summary = format_effective_config(
@@ -15,7 +15,10 @@ pub mod metric_results;
 pub use core::{BoundingBox, NormalizedView, OcrBlock, ResourceKind, TypographyStyle, Viewport};
 
 // Re-export DOM types
-pub use dom::{ComputedStyle, DomNode, DomSnapshot};
+pub use dom::{
+    BoxCorners, BoxSides, BrokenImage, ComputedStyle, DomNode, DomSnapshot, FailedRequest,
+    PageDiagnostics, PseudoElement,
+};
 
 // Re-export Figma types
 pub use figma::{FigmaNode, FigmaPaint, FigmaPaintKind, FigmaSnapshot};
@@ -12,7 +12,7 @@ use std::path::PathBuf;
 
 pub use crate::viewport::Viewport;
 
-use super::dom::DomSnapshot;
+use super::dom::{DomSnapshot, PageDiagnostics};
 use super::figma::FigmaSnapshot;
 
 /// Classification of input resource type.
@@ -25,6 +25,13 @@ pub enum ResourceKind {
     Image,
     /// Figma design reference
     Figma,
+    /// Serialized `NormalizedView` bundle replayed without rendering
+    View,
+    /// Local HTML file or directory, served on loopback and rendered like a URL
+    Html,
+    /// Saved Figma nodes JSON plus its PNG export, converted without the API
+    #[serde(rename = "figma-export")]
+    FigmaExport,
 }
 
 /// A normalized representation of a design view.
@@ -51,6 +58,25 @@ pub struct NormalizedView {
     /// OCR-extracted text blocks (for image inputs without DOM/Figma)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ocr_blocks: Option<Vec<OcrBlock>>,
+    /// Console errors, failed requests and broken images seen while rendering (URL inputs)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<PageDiagnostics>,
+}
+
+impl NormalizedView {
+    /// Screenshot pixels per view pixel along x and y, given the screenshot's size.
+    ///
+    /// `width`/`height` (and DOM bounding boxes) are CSS pixels, so this is the device scale
+    /// factor for a high-DPI URL render and 1.0 for everything else.
+    pub fn pixel_scale(&self, image_width: u32, image_height: u32) -> (f32, f32) {
+        if self.width == 0 || self.height == 0 {
+            return (1.0, 1.0);
+        }
+        (
+            image_width as f32 / self.width as f32,
+            image_height as f32 / self.height as f32,
+        )
+    }
 }
 
 /// Rectangle bounds for an element.
@@ -44,6 +44,12 @@ pub struct DomNode {
     /// CSS computed styles
     #[serde(skip_serializing_if = "Option::is_none")]
     pub computed_style: Option<ComputedStyle>,
+    /// ID of the nearest shadow host when this node lives in an (open) shadow root
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub shadow_host: Option<String>,
+    /// ID of the `<iframe>` node when this node lives in a same-origin frame's document
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub frame: Option<String>,
 }
 
 /// Computed CSS styles for a DOM element.
@@ -59,4 +65,141 @@ pub struct ComputedStyle {
     pub display: Option<String>,
     pub visibility: Option<String>,
     pub opacity: Option<f32>,
+    /// Padding per side, in CSS pixels
+    pub padding: Option<BoxSides>,
+    /// Margin per side, in CSS pixels (negative margins are kept)
+    pub margin: Option<BoxSides>,
+    /// Border width per side, in CSS pixels
+    pub border_width: Option<BoxSides>,
+    /// Border style shorthand (e.g. "solid", "solid none")
+    pub border_style: Option<String>,
+    /// Border color shorthand
+    pub border_color: Option<String>,
+    /// Border radius per corner, in CSS pixels (percentages resolved against the box)
+    pub border_radius: Option<BoxCorners>,
+    /// `box-shadow`; `None` when the element has no shadow
+    pub box_shadow: Option<String>,
+    /// Letter spacing in CSS pixels (`normal` is 0)
+    pub letter_spacing: Option<f32>,
+    pub text_align: Option<String>,
+    pub text_transform: Option<String>,
+    /// Rendered `::before` pseudo-element, if any
+    pub before: Option<PseudoElement>,
+    /// Rendered `::after` pseudo-element, if any
+    pub after: Option<PseudoElement>,
+}
+
+/// Per-side lengths (padding, margin, border width).
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub struct BoxSides {
+    pub top: f32,
+    pub right: f32,
+    pub bottom: f32,
+    pub left: f32,
+}
+
+impl BoxSides {
+    /// Sides in top, right, bottom, left order.
+    pub fn values(&self) -> [f32; 4] {
+        [self.top, self.right, self.bottom, self.left]
+    }
+}
+
+/// Per-corner border radii.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct BoxCorners {
+    pub top_left: f32,
+    pub top_right: f32,
+    pub bottom_right: f32,
+    pub bottom_left: f32,
+}
+
+impl BoxCorners {
+    /// Corners clockwise from top-left.
+    pub fn values(&self) -> [f32; 4] {
+        [
+            self.top_left,
+            self.top_right,
+            self.bottom_right,
+            self.bottom_left,
+        ]
+    }
+}
+
+/// A `::before` or `::after` pseudo-element with generated content.
+///
+/// Pseudo-elements have no client rect of their own, so only the computed size is known;
+/// it is `None` for inline pseudo-elements whose width or height is `auto`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PseudoElement {
+    /// The `content` value, unquoted when it is a plain string
+    pub content: String,
+    pub width: Option<f32>,
+    pub height: Option<f32>,
+}
+
+/// Problems the browser reported while rendering a page; empty lists are omitted.
+///
+/// These are often the real cause of a visual mismatch: a stylesheet or font that 404'd,
+/// or a script that threw before rendering its part of the page.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageDiagnostics {
+    /// Messages logged with `console.error`, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub console_errors: Vec<String>,
+    /// Uncaught exceptions and unhandled promise rejections
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub page_errors: Vec<String>,
+    /// Requests that failed outright or got a 4xx/5xx response
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub failed_requests: Vec<FailedRequest>,
+    /// `<img>` elements that finished loading without image data (`naturalWidth == 0`)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub broken_images: Vec<BrokenImage>,
+}
+
+impl PageDiagnostics {
+    pub fn is_empty(&self) -> bool {
+        self.console_errors.is_empty()
+            && self.page_errors.is_empty()
+            && self.failed_requests.is_empty()
+            && self.broken_images.is_empty()
+    }
+}
+
+/// A network request that did not succeed.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FailedRequest {
+    pub url: String,
+    /// What requested it, e.g. `stylesheet`, `font`, `script`, `image`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+    /// HTTP status, for requests that got a response
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    /// Network error, e.g. `net::ERR_NAME_NOT_RESOLVED`, for requests that got none
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl FailedRequest {
+    /// `404`, the network error, or `failed`.
+    pub fn reason(&self) -> String {
+        match (self.status, &self.error) {
+            (Some(status), _) => status.to_string(),
+            (None, Some(error)) => error.clone(),
+            (None, None) => "failed".to_string(),
+        }
+    }
+}
+
+/// An image element that loaded no image data.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BrokenImage {
+    pub src: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub alt: Option<String>,
 }
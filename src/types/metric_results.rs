@@ -203,6 +203,9 @@ pub enum TypographyIssue {
     FontSizeDiff,
     FontWeightDiff,
     LineHeightDiff,
+    LetterSpacingDiff,
+    TextTransformDiff,
+    TextAlignDiff,
 }
 
 // ============================================================================
@@ -0,0 +1,177 @@
+//! Serialized [`NormalizedView`] bundles for offline replay.
+//!
+//! A bundle is either a directory holding `view.json` plus its screenshot, or a
+//! standalone view JSON file (e.g. `ref_view.json` in a kept artifacts directory).
+//! Relative screenshot paths resolve against the directory containing the JSON, so
+//! bundles stay valid when copied or committed as a corpus.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{DpcError, Result};
+use crate::types::NormalizedView;
+
+/// View JSON file name inside a bundle directory.
+pub const VIEW_FILE: &str = "view.json";
+/// Screenshot file name inside a bundle directory.
+pub const SCREENSHOT_FILE: &str = "screenshot.png";
+
+/// Top-level fields every serialized view has; a JSON file is only taken for a view when
+/// it holds all of them.
+const VIEW_FIELDS: [&str; 4] = ["kind", "screenshotPath", "width", "height"];
+
+/// Write a self-contained bundle into `dir`, copying the screenshot and replacing any contents.
+pub fn write_view_bundle(dir: &Path, view: &NormalizedView) -> Result<()> {
+    if dir.exists() {
+        fs::remove_dir_all(dir)?;
+    }
+    fs::create_dir_all(dir)?;
+    fs::copy(&view.screenshot_path, dir.join(SCREENSHOT_FILE))?;
+
+    let mut stored = view.clone();
+    stored.screenshot_path = PathBuf::from(SCREENSHOT_FILE);
+    fs::write(dir.join(VIEW_FILE), serde_json::to_vec_pretty(&stored)?)?;
+    Ok(())
+}
+
+/// Write `view` as JSON at `path` without copying the screenshot.
+///
+/// The screenshot path is stored relative to `path`'s directory when it lives there.
+pub fn write_view_json(path: &Path, view: &NormalizedView) -> Result<()> {
+    let mut stored = view.clone();
+    if let Some(relative) = path
+        .parent()
+        .and_then(|base| view.screenshot_path.strip_prefix(base).ok())
+    {
+        stored.screenshot_path = relative.to_path_buf();
+    }
+    fs::write(path, serde_json::to_vec_pretty(&stored)?)?;
+    Ok(())
+}
+
+/// Load a bundle directory or view JSON file, resolving and checking the screenshot path.
+pub fn read_view_bundle(path: &Path) -> Result<NormalizedView> {
+    let json_path = if path.is_dir() {
+        path.join(VIEW_FILE)
+    } else {
+        path.to_path_buf()
+    };
+    let data = fs::read(&json_path).map_err(|err| {
+        DpcError::Config(format!(
+            "Failed to read view bundle {}: {err}",
+            json_path.display()
+        ))
+    })?;
+    let mut view: NormalizedView = serde_json::from_slice(&data).map_err(|err| {
+        DpcError::Config(format!(
+            "Invalid view bundle {} (expected a serialized NormalizedView): {err}",
+            json_path.display()
+        ))
+    })?;
+
+    if view.screenshot_path.is_relative() {
+        if let Some(base) = json_path.parent() {
+            view.screenshot_path = base.join(&view.screenshot_path);
+        }
+    }
+    if !view.screenshot_path.is_file() {
+        return Err(DpcError::Config(format!(
+            "View bundle {} is missing its screenshot ({})",
+            json_path.display(),
+            view.screenshot_path.display()
+        )));
+    }
+    Ok(view)
+}
+
+/// True when `path` is a view bundle: a directory with `view.json`, or a `.json` file whose
+/// top-level object has a serialized view's fields.
+pub fn is_view_bundle(path: &Path) -> bool {
+    if path.is_dir() {
+        return path.join(VIEW_FILE).is_file();
+    }
+    let is_json = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("json"));
+    is_json
+        && fs::read(path)
+            .ok()
+            .and_then(|data| serde_json::from_slice::<serde_json::Value>(&data).ok())
+            .and_then(|value| value.as_object().cloned())
+            .is_some_and(|object| VIEW_FIELDS.iter().all(|field| object.contains_key(*field)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{DomSnapshot, ResourceKind};
+    use image::RgbaImage;
+    use tempfile::TempDir;
+
+    fn view_at(path: PathBuf) -> NormalizedView {
+        RgbaImage::from_pixel(3, 2, image::Rgba([9, 9, 9, 255]))
+            .save(&path)
+            .expect("write screenshot");
+        NormalizedView {
+            kind: ResourceKind::Url,
+            screenshot_path: path,
+            width: 3,
+            height: 2,
+            dom: Some(DomSnapshot {
+                url: Some("https://example.com".to_string()),
+                title: None,
+                nodes: vec![],
+            }),
+            figma_tree: None,
+            ocr_blocks: None,
+            diagnostics: None,
+        }
+    }
+
+    #[test]
+    fn view_json_round_trips_with_relative_screenshot() {
+        let dir = TempDir::new().expect("tempdir");
+        let view = view_at(dir.path().join("ref_screenshot.png"));
+        let json_path = dir.path().join("ref_view.json");
+
+        write_view_json(&json_path, &view).expect("write");
+        let raw = fs::read_to_string(&json_path).unwrap();
+        assert!(raw.contains("\"screenshotPath\": \"ref_screenshot.png\""));
+
+        let loaded = read_view_bundle(&json_path).expect("read");
+        assert_eq!(
+            loaded.screenshot_path,
+            dir.path().join("ref_screenshot.png")
+        );
+        assert_eq!(loaded.kind, ResourceKind::Url);
+        assert!(loaded.dom.is_some());
+    }
+
+    #[test]
+    fn bundle_directory_copies_screenshot() {
+        let dir = TempDir::new().expect("tempdir");
+        let view = view_at(dir.path().join("shot.png"));
+        let bundle = dir.path().join("bundle");
+
+        write_view_bundle(&bundle, &view).expect("write bundle");
+        fs::remove_file(dir.path().join("shot.png")).unwrap();
+
+        assert!(is_view_bundle(&bundle));
+        let loaded = read_view_bundle(&bundle).expect("read bundle");
+        assert_eq!(loaded.screenshot_path, bundle.join(SCREENSHOT_FILE));
+        assert_eq!((loaded.width, loaded.height), (3, 2));
+    }
+
+    #[test]
+    fn read_reports_missing_screenshot() {
+        let dir = TempDir::new().expect("tempdir");
+        let view = view_at(dir.path().join("gone.png"));
+        let json_path = dir.path().join("view.json");
+        write_view_json(&json_path, &view).expect("write");
+        fs::remove_file(dir.path().join("gone.png")).unwrap();
+
+        let err = read_view_bundle(&json_path).expect_err("missing screenshot");
+        assert!(err.to_string().contains("missing its screenshot"));
+    }
+}
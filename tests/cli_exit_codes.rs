@@ -452,6 +452,37 @@ fn compare_figma_without_token_reports_config_error_and_remediation() {
     );
 }
 
+#[test]
+fn figma_pull_without_token_or_node_id_exits_two() {
+    let dir = TempDir::new().expect("tempdir");
+    let pull = |url: &str| {
+        Command::new(env!("CARGO_BIN_EXE_dpc"))
+            .current_dir(dir.path())
+            .args(["figma", "pull", url])
+            .env_remove("FIGMA_TOKEN")
+            .env_remove("FIGMA_OAUTH_TOKEN")
+            .output()
+            .expect("run dpc figma pull")
+    };
+
+    let output = pull("https://www.figma.com/file/FILE123/Mock?node-id=1-2");
+    assert_eq!(output.status.code(), Some(2));
+    let err: serde_json::Value = serde_json::from_slice(&output.stdout).expect("error JSON");
+    assert!(err["error"]["message"]
+        .as_str()
+        .unwrap_or_default()
+        .contains("FIGMA_TOKEN"));
+    assert!(!dir.path().join(".dpc").exists());
+
+    let output = pull("https://www.figma.com/file/FILE123/Mock");
+    assert_eq!(output.status.code(), Some(2));
+    let err: serde_json::Value = serde_json::from_slice(&output.stdout).expect("error JSON");
+    assert!(err["error"]["message"]
+        .as_str()
+        .unwrap_or_default()
+        .contains("node-id"));
+}
+
 #[test]
 fn compare_figma_without_node_id_reports_config_error_and_hint() {
     let output = Command::new(env!("CARGO_BIN_EXE_dpc"))
@@ -722,3 +753,208 @@ fn compare_pretty_exit_code_returns_fatal_for_invalid_input() {
         .expect("run dpc");
     assert_eq!(status.code(), Some(2));
 }
+
+#[test]
+fn batch_aggregates_entries_and_fails_when_any_entry_fails() {
+    let dir = TempDir::new().expect("tempdir");
+    write_image(&dir.path().join("black.png"), [0, 0, 0, 255]);
+    write_image(&dir.path().join("white.png"), [255, 255, 255, 255]);
+    let manifest = dir.path().join("batch.yaml");
+    std::fs::write(
+        &manifest,
+        r#"
+concurrency: 2
+entries:
+  - name: same
+    ref: black.png
+    impl: black.png
+  - name: different
+    ref: black.png
+    impl: white.png
+    viewport: 320x240
+"#,
+    )
+    .expect("write manifest");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dpc"))
+        .args(["batch", "--manifest", manifest.to_str().unwrap()])
+        .output()
+        .expect("run dpc");
+
+    assert_eq!(output.status.code(), Some(1));
+    let body: DpcOutput =
+        serde_json::from_slice(&output.stdout).expect("batch output should be JSON");
+    match body {
+        DpcOutput::Batch(out) => {
+            assert!(!out.passed);
+            assert_eq!(out.total, 2);
+            assert_eq!(out.passed_count, 1);
+            assert_eq!(out.failed_count, 1);
+            assert_eq!(out.entries[0].name, "same");
+            assert!(out.entries[0].passed);
+            let different = out.entries[1].result.as_ref().expect("compare result");
+            assert_eq!(different.viewport.width, 320);
+            assert!(!different.passed);
+        }
+        other => panic!("expected batch output, got {:?}", other),
+    }
+}
+
+#[test]
+fn batch_exit_code_returns_fatal_when_an_entry_errors() {
+    let dir = TempDir::new().expect("tempdir");
+    write_image(&dir.path().join("ref.png"), [0, 0, 0, 255]);
+    let manifest = dir.path().join("batch.toml");
+    std::fs::write(
+        &manifest,
+        r#"
+[[entries]]
+name = "ok"
+ref = "ref.png"
+impl = "ref.png"
+
+[[entries]]
+name = "broken"
+ref = "ref.png"
+impl = "http://127.0.0.1:1/unreachable"
+"#,
+    )
+    .expect("write manifest");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dpc"))
+        .args(["batch", "--manifest", manifest.to_str().unwrap()])
+        .env("PATH", "")
+        .output()
+        .expect("run dpc");
+
+    assert_eq!(output.status.code(), Some(2));
+    let body: DpcOutput =
+        serde_json::from_slice(&output.stdout).expect("batch output should be JSON");
+    match body {
+        DpcOutput::Batch(out) => {
+            assert_eq!(out.error_count, 1);
+            assert!(out.entries[0].passed);
+            assert!(out.entries[1].error.is_some());
+        }
+        other => panic!("expected batch output, got {:?}", other),
+    }
+}
+
+#[test]
+fn compare_reports_unset_auth_secret_without_rendering() {
+    let dir = TempDir::new().expect("tempdir");
+    let ref_path = dir.path().join("ref.png");
+    write_image(&ref_path, [10, 10, 10, 255]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dpc"))
+        .args([
+            "compare",
+            "--ref",
+            ref_path.to_str().unwrap(),
+            "--impl",
+            ref_path.to_str().unwrap(),
+            "--header",
+            "Authorization: env:DPC_TEST_UNSET_AUTH_TOKEN",
+        ])
+        .env_remove("DPC_TEST_UNSET_AUTH_TOKEN")
+        .output()
+        .expect("run dpc");
+
+    assert_eq!(output.status.code(), Some(2));
+    let err: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout JSON");
+    assert_eq!(err["error"]["category"], "config");
+    let msg = err["error"]["message"].as_str().unwrap_or_default();
+    assert!(
+        msg.contains("DPC_TEST_UNSET_AUTH_TOKEN"),
+        "expected env var name in error, got {msg}"
+    );
+}
+
+#[test]
+fn compare_rejects_missing_har_replay_file() {
+    let dir = TempDir::new().expect("tempdir");
+    let ref_path = dir.path().join("ref.png");
+    write_image(&ref_path, [10, 10, 10, 255]);
+    let har_path = dir.path().join("missing.har");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dpc"))
+        .args([
+            "compare",
+            "--ref",
+            ref_path.to_str().unwrap(),
+            "--impl",
+            ref_path.to_str().unwrap(),
+            "--har-replay",
+            har_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("run dpc");
+
+    assert_eq!(output.status.code(), Some(2));
+    let err: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout JSON");
+    assert_eq!(err["error"]["category"], "config");
+    let msg = err["error"]["message"].as_str().unwrap_or_default();
+    assert!(
+        msg.contains("Failed to read HAR"),
+        "expected HAR error, got {msg}"
+    );
+}
+
+#[test]
+fn compare_rejects_invalid_steps_file() {
+    let dir = TempDir::new().expect("tempdir");
+    let ref_path = dir.path().join("ref.png");
+    write_image(&ref_path, [10, 10, 10, 255]);
+    let steps_path = dir.path().join("steps.yaml");
+    std::fs::write(&steps_path, "- tap: \"#menu\"\n").expect("write steps");
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dpc"))
+        .args([
+            "compare",
+            "--ref",
+            ref_path.to_str().unwrap(),
+            "--impl",
+            ref_path.to_str().unwrap(),
+            "--steps",
+            steps_path.to_str().unwrap(),
+        ])
+        .output()
+        .expect("run dpc");
+
+    assert_eq!(output.status.code(), Some(2));
+    let err: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout JSON");
+    assert_eq!(err["error"]["category"], "config");
+    let msg = err["error"]["message"].as_str().unwrap_or_default();
+    assert!(
+        msg.contains("Invalid steps file"),
+        "expected steps file error, got {msg}"
+    );
+}
+
+#[test]
+fn compare_rejects_selector_for_non_url_input() {
+    let dir = TempDir::new().expect("tempdir");
+    let ref_path = dir.path().join("ref.png");
+    write_image(&ref_path, [10, 10, 10, 255]);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_dpc"))
+        .args([
+            "compare",
+            "--ref",
+            ref_path.to_str().unwrap(),
+            "--impl",
+            ref_path.to_str().unwrap(),
+            "--impl-selector",
+            ".card",
+        ])
+        .output()
+        .expect("run dpc");
+
+    assert_eq!(output.status.code(), Some(2));
+    let err: serde_json::Value = serde_json::from_slice(&output.stdout).expect("stdout JSON");
+    let msg = err["error"]["message"].as_str().unwrap_or_default();
+    assert!(
+        msg.contains("--impl-selector only applies to URL and HTML inputs"),
+        "expected selector error, got {msg}"
+    );
+}
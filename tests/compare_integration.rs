@@ -2,7 +2,7 @@ use std::path::{Path, PathBuf};
 use std::process::{Command, Output};
 
 use dpc_lib::error::ErrorCategory;
-use dpc_lib::{DpcOutput, ResourceKind};
+use dpc_lib::{BrowserEngine, DpcOutput, InteractiveState, ResourceKind};
 use image::{ImageBuffer, Rgba};
 use serde_json::Value;
 use tempfile::tempdir;
@@ -309,7 +309,7 @@ fn image_inputs_emit_json_and_pass() {
             assert!(
                 out.summary
                     .as_ref()
-                    .map_or(false, |s| !s.top_issues.is_empty()),
+                    .is_some_and(|s| !s.top_issues.is_empty()),
                 "summary should include top issues"
             );
         }
@@ -399,6 +399,50 @@ fn url_inputs_use_mock_renderer() {
     }
 }
 
+#[test]
+fn html_inputs_use_mock_renderer() {
+    let site = tempdir().expect("tempdir");
+    std::fs::write(site.path().join("index.html"), "<h1>Home</h1>").unwrap();
+    let page = site.path().join("page.html");
+    std::fs::write(&page, "<h1>Page</h1>").unwrap();
+
+    let output = run_compare(
+        &[
+            "compare",
+            "--ref",
+            site.path().to_str().unwrap(),
+            "--impl",
+            page.to_str().unwrap(),
+            "--format",
+            "json",
+            "--threshold",
+            "0.90",
+        ],
+        &[
+            ("DPC_MOCK_RENDER_REF", asset("ref.png").to_str().unwrap()),
+            (
+                "DPC_MOCK_RENDER_IMPL",
+                asset("impl_identical.png").to_str().unwrap(),
+            ),
+        ],
+    );
+
+    assert!(
+        output.status.success(),
+        "html mock run should pass: {:?}",
+        output.status.code()
+    );
+
+    match parse_output(&output.stdout) {
+        DpcOutput::Compare(out) => {
+            assert!(matches!(out.ref_resource.kind, ResourceKind::Html));
+            assert!(matches!(out.impl_resource.kind, ResourceKind::Html));
+            assert!(out.passed);
+        }
+        other => panic!("expected compare output, got {:?}", other),
+    }
+}
+
 #[test]
 fn figma_inputs_use_mock_renderer() {
     let output = run_compare(
@@ -442,6 +486,59 @@ fn figma_inputs_use_mock_renderer() {
     }
 }
 
+#[test]
+fn figma_exports_compare_without_a_token() {
+    let dir = tempdir().expect("tempdir");
+    let export = dir.path().join("home");
+    std::fs::create_dir(&export).unwrap();
+    std::fs::copy(asset("ref.png"), export.join("frame.png")).unwrap();
+    let nodes = serde_json::json!({
+        "nodes": {
+            "1:2": {
+                "document": {
+                    "id": "1:2",
+                    "name": "Home",
+                    "type": "FRAME",
+                    "absoluteBoundingBox": { "x": 0.0, "y": 0.0, "width": 100.0, "height": 100.0 }
+                }
+            }
+        }
+    });
+    std::fs::write(
+        export.join("nodes.json"),
+        serde_json::to_vec(&nodes).unwrap(),
+    )
+    .unwrap();
+
+    let output = run_compare(
+        &[
+            "compare",
+            "--ref",
+            export.to_str().unwrap(),
+            "--impl",
+            asset("ref.png").to_str().unwrap(),
+            "--viewport",
+            "400x300",
+            "--format",
+            "json",
+        ],
+        &[("FIGMA_TOKEN", "")],
+    );
+
+    assert!(
+        output.status.success(),
+        "figma export compare should pass: {}",
+        String::from_utf8_lossy(&output.stdout)
+    );
+    match parse_output(&output.stdout) {
+        DpcOutput::Compare(out) => {
+            assert_eq!(out.ref_resource.kind, ResourceKind::FigmaExport);
+            assert!(out.passed);
+        }
+        other => panic!("expected compare output, got {:?}", other),
+    }
+}
+
 #[test]
 fn invalid_input_exits_with_fatal_code() {
     let missing = asset("missing.png");
@@ -636,3 +733,601 @@ fn invalid_ignore_regions_file_exits_with_error() {
         "expected message to describe expected shape {{x,y,width,height}}, got: {message}"
     );
 }
+
+#[test]
+fn viewports_flag_reports_each_breakpoint() {
+    let dir = tempdir().expect("tempdir");
+    let artifacts_dir = dir.path().join("artifacts");
+    let output = run_compare(
+        &[
+            "compare",
+            "--ref",
+            asset("ref.png").to_str().unwrap(),
+            "--impl",
+            asset("impl_identical.png").to_str().unwrap(),
+            "--viewports",
+            "375x812,1440x900",
+            "--threshold",
+            "0.9",
+            "--artifacts-dir",
+            artifacts_dir.to_str().unwrap(),
+            "--format",
+            "json",
+        ],
+        &[],
+    );
+
+    assert_eq!(output.status.code(), Some(0));
+    match parse_output(&output.stdout) {
+        DpcOutput::ResponsiveCompare(out) => {
+            assert!(out.passed);
+            let names: Vec<&str> = out.breakpoints.iter().map(|b| b.name.as_str()).collect();
+            assert_eq!(names, vec!["375x812", "1440x900"]);
+            assert_eq!(out.breakpoints[0].result.viewport.width, 375);
+            assert!(artifacts_dir
+                .join("375x812")
+                .join("ref_screenshot.png")
+                .exists());
+        }
+        other => panic!("expected responsive compare output, got {:?}", other),
+    }
+}
+
+#[test]
+fn browsers_flag_reports_each_engine() {
+    let dir = tempdir().expect("tempdir");
+    let artifacts_dir = dir.path().join("artifacts");
+    let output = run_compare(
+        &[
+            "compare",
+            "--ref",
+            asset("ref.png").to_str().unwrap(),
+            "--impl",
+            asset("impl_identical.png").to_str().unwrap(),
+            "--browsers",
+            "firefox,webkit,firefox",
+            "--threshold",
+            "0.9",
+            "--artifacts-dir",
+            artifacts_dir.to_str().unwrap(),
+            "--format",
+            "json",
+        ],
+        &[],
+    );
+
+    assert_eq!(output.status.code(), Some(0));
+    match parse_output(&output.stdout) {
+        DpcOutput::ResponsiveCompare(out) => {
+            assert!(out.passed);
+            let names: Vec<&str> = out.breakpoints.iter().map(|b| b.name.as_str()).collect();
+            assert_eq!(names, vec!["firefox", "webkit"]);
+            assert_eq!(out.breakpoints[1].browser, Some(BrowserEngine::Webkit));
+            assert!(artifacts_dir
+                .join("webkit")
+                .join("ref_screenshot.png")
+                .exists());
+        }
+        other => panic!("expected responsive compare output, got {:?}", other),
+    }
+}
+
+#[test]
+fn states_flag_compares_each_state() {
+    let dir = tempdir().expect("tempdir");
+    let artifacts_dir = dir.path().join("artifacts");
+    let (ref_png, impl_png) = (asset("ref.png"), asset("impl_identical.png"));
+    let mock_env = [
+        ("DPC_MOCK_RENDER_REF", ref_png.to_str().unwrap()),
+        ("DPC_MOCK_RENDER_IMPL", impl_png.to_str().unwrap()),
+        ("FIGMA_TOKEN", "dummy-token"),
+    ];
+    let output = run_compare(
+        &[
+            "compare",
+            "--ref",
+            "https://www.figma.com/file/FILE123/Mock?node-id=1-2",
+            "--impl",
+            "http://localhost:3000",
+            "--impl-selector",
+            ".btn-primary",
+            "--states",
+            "rest,hover",
+            "--state-node",
+            "hover=1-3",
+            "--threshold",
+            "0.9",
+            "--artifacts-dir",
+            artifacts_dir.to_str().unwrap(),
+            "--format",
+            "json",
+        ],
+        &mock_env,
+    );
+
+    assert_eq!(output.status.code(), Some(0));
+    match parse_output(&output.stdout) {
+        DpcOutput::ResponsiveCompare(out) => {
+            let names: Vec<&str> = out.breakpoints.iter().map(|b| b.name.as_str()).collect();
+            assert_eq!(names, vec!["rest", "hover"]);
+            assert_eq!(out.breakpoints[1].state, Some(InteractiveState::Hover));
+            assert!(artifacts_dir
+                .join("hover")
+                .join("impl_screenshot.png")
+                .exists());
+        }
+        other => panic!("expected responsive compare output, got {:?}", other),
+    }
+
+    let missing_selector = run_compare(
+        &[
+            "compare",
+            "--ref",
+            "https://www.figma.com/file/FILE123/Mock?node-id=1-2",
+            "--impl",
+            "http://localhost:3000",
+            "--states",
+            "hover",
+        ],
+        &mock_env,
+    );
+    assert_eq!(missing_selector.status.code(), Some(2));
+    match parse_error(&missing_selector.stdout) {
+        DpcOutput::Error(err) => assert!(err.error.message.contains("--impl-selector")),
+        other => panic!("expected error output, got {:?}", other),
+    }
+}
+
+#[test]
+fn config_breakpoints_map_figma_node_ids() {
+    let dir = tempdir().expect("tempdir");
+    let cfg_path = dir.path().join("dpc.toml");
+    std::fs::write(
+        &cfg_path,
+        r#"
+[[breakpoints]]
+name = "mobile"
+viewport = "375x812"
+ref_node_id = "10-1"
+
+[[breakpoints]]
+name = "desktop"
+viewport = "1440x900"
+ref_node_id = "10-2"
+"#,
+    )
+    .expect("write config");
+
+    let output = run_compare(
+        &[
+            "compare",
+            "--config",
+            cfg_path.to_str().unwrap(),
+            "--ref",
+            "https://www.figma.com/file/FILE123/Mock?node-id=1-2",
+            "--impl",
+            asset("impl_identical.png").to_str().unwrap(),
+            "--threshold",
+            "0.9",
+            "--format",
+            "json",
+        ],
+        &[
+            ("DPC_MOCK_RENDER_REF", asset("ref.png").to_str().unwrap()),
+            ("FIGMA_TOKEN", "dummy-token"),
+        ],
+    );
+
+    assert_eq!(output.status.code(), Some(0));
+    match parse_output(&output.stdout) {
+        DpcOutput::ResponsiveCompare(out) => {
+            let names: Vec<&str> = out.breakpoints.iter().map(|b| b.name.as_str()).collect();
+            assert_eq!(names, vec!["mobile", "desktop"]);
+            assert!(matches!(out.ref_resource.kind, ResourceKind::Figma));
+        }
+        other => panic!("expected responsive compare output, got {:?}", other),
+    }
+}
+
+#[test]
+fn baseline_record_compare_approve_round_trip() {
+    let dir = tempdir().expect("tempdir");
+    let store = dir.path().join("baselines");
+    let store_arg = store.to_str().unwrap();
+    let baseline_args = |extra: &[&str]| -> Vec<String> {
+        let mut args: Vec<String> = vec!["baseline".into()];
+        args.extend(extra.iter().map(|s| s.to_string()));
+        args.extend([
+            "--scenario".into(),
+            "home".into(),
+            "--baseline-dir".into(),
+            store_arg.to_string(),
+            "--viewport".into(),
+            "64x64".into(),
+        ]);
+        args
+    };
+    let run = |args: Vec<String>| {
+        let refs: Vec<&str> = args.iter().map(String::as_str).collect();
+        run_compare(&refs, &[])
+    };
+
+    let record = run(baseline_args(&[
+        "record",
+        "--input",
+        asset("ref.png").to_str().unwrap(),
+    ]));
+    assert_eq!(record.status.code(), Some(0));
+    match parse_output(&record.stdout) {
+        DpcOutput::Baseline(out) => {
+            assert_eq!(out.entries.len(), 1);
+            assert!(out.entries[0].path.join("view.json").exists());
+        }
+        other => panic!("expected baseline output, got {:?}", other),
+    }
+
+    let changed = asset("impl_different.png");
+    let compare_changed = || {
+        run(baseline_args(&[
+            "compare",
+            "--impl",
+            changed.to_str().unwrap(),
+            "--threshold",
+            "0.99",
+        ]))
+    };
+    let first = compare_changed();
+    assert_eq!(first.status.code(), Some(1));
+    match parse_output(&first.stdout) {
+        DpcOutput::Compare(out) => assert!(!out.passed),
+        other => panic!("expected compare output, got {:?}", other),
+    }
+    assert!(store.join("home").join("64x64.pending").exists());
+
+    let approve = Command::new(bin_path())
+        .args([
+            "baseline",
+            "approve",
+            "--scenario",
+            "home",
+            "--baseline-dir",
+        ])
+        .arg(&store)
+        .output()
+        .expect("run approve");
+    assert_eq!(approve.status.code(), Some(0));
+    assert!(!store.join("home").join("64x64.pending").exists());
+
+    let second = compare_changed();
+    assert_eq!(second.status.code(), Some(0));
+}
+
+#[test]
+fn baseline_compare_without_record_reports_config_error() {
+    let dir = tempdir().expect("tempdir");
+    let output = run_compare(
+        &[
+            "baseline",
+            "compare",
+            "--scenario",
+            "missing",
+            "--impl",
+            asset("impl_identical.png").to_str().unwrap(),
+            "--baseline-dir",
+            dir.path().to_str().unwrap(),
+        ],
+        &[],
+    );
+
+    assert_eq!(output.status.code(), Some(2));
+    match parse_error(&output.stdout) {
+        DpcOutput::Error(err) => {
+            assert_eq!(err.error.category, ErrorCategory::Config);
+            assert!(err.error.message.contains("dpc baseline record"));
+        }
+        other => panic!("expected error output, got {:?}", other),
+    }
+}
+
+#[test]
+fn compare_replays_saved_views_without_rendering() {
+    let dir = tempdir().expect("tempdir");
+    let artifacts_dir = dir.path().join("run1");
+
+    let first = run_compare(
+        &[
+            "compare",
+            "--ref",
+            "https://example.com/design",
+            "--impl",
+            "https://example.com/build",
+            "--viewport",
+            "64x64",
+            "--threshold",
+            "0.5",
+            "--artifacts-dir",
+            artifacts_dir.to_str().unwrap(),
+        ],
+        &[
+            ("DPC_MOCK_RENDER_REF", asset("ref.png").to_str().unwrap()),
+            (
+                "DPC_MOCK_RENDER_IMPL",
+                asset("impl_different.png").to_str().unwrap(),
+            ),
+        ],
+    );
+    assert!(first.status.code().is_some_and(|c| c < 2));
+    let original = match parse_output(&first.stdout) {
+        DpcOutput::Compare(out) => out,
+        other => panic!("expected compare output, got {:?}", other),
+    };
+    let ref_view = artifacts_dir.join("ref_view.json");
+    let impl_view = artifacts_dir.join("impl_view.json");
+    assert!(ref_view.exists() && impl_view.exists());
+
+    // No mock env vars: a render attempt would fail, so a result proves the replay.
+    let replay = run_compare(
+        &[
+            "compare",
+            "--ref",
+            ref_view.to_str().unwrap(),
+            "--impl",
+            impl_view.to_str().unwrap(),
+            "--threshold",
+            "0.01",
+        ],
+        &[("PATH", "")],
+    );
+    assert_eq!(replay.status.code(), Some(0));
+    match parse_output(&replay.stdout) {
+        DpcOutput::Compare(out) => {
+            assert!(matches!(out.ref_resource.kind, ResourceKind::View));
+            assert!(matches!(out.impl_resource.kind, ResourceKind::View));
+            assert!((out.similarity - original.similarity).abs() < 1e-6);
+            assert!(out.passed);
+        }
+        other => panic!("expected compare output, got {:?}", other),
+    }
+}
+
+#[test]
+fn page_diagnostics_lead_the_summary() {
+    let dir = tempdir().expect("tempdir");
+    let artifacts_dir = dir.path().join("run");
+    let first = run_compare(
+        &[
+            "compare",
+            "--ref",
+            "https://example.com/design",
+            "--impl",
+            "https://example.com/build",
+            "--viewport",
+            "64x64",
+            "--artifacts-dir",
+            artifacts_dir.to_str().unwrap(),
+        ],
+        &[
+            ("DPC_MOCK_RENDER_REF", asset("ref.png").to_str().unwrap()),
+            ("DPC_MOCK_RENDER_IMPL", asset("ref.png").to_str().unwrap()),
+        ],
+    );
+    assert!(first.status.code().is_some_and(|c| c < 2));
+
+    let impl_view = artifacts_dir.join("impl_view.json");
+    let mut view: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&impl_view).unwrap()).unwrap();
+    view["diagnostics"] = serde_json::json!({
+        "pageErrors": ["ReferenceError: theme is not defined"],
+        "failedRequests": [
+            { "url": "https://example.com/site.css", "resourceType": "stylesheet", "status": 404 }
+        ]
+    });
+    std::fs::write(&impl_view, serde_json::to_vec(&view).unwrap()).unwrap();
+
+    let replay = run_compare(
+        &[
+            "compare",
+            "--ref",
+            artifacts_dir.join("ref_view.json").to_str().unwrap(),
+            "--impl",
+            impl_view.to_str().unwrap(),
+        ],
+        &[],
+    );
+    let out = match parse_output(&replay.stdout) {
+        DpcOutput::Compare(out) => out,
+        other => panic!("expected compare output, got {:?}", other),
+    };
+    let issues = out.summary.expect("summary").top_issues;
+    assert_eq!(
+        issues[1],
+        "Implementation page: 1 uncaught JavaScript error: ReferenceError: theme is not defined"
+    );
+    assert_eq!(
+        issues[2],
+        "Implementation page: 1 failed request: 404 stylesheet https://example.com/site.css"
+    );
+}
+
+#[test]
+fn html_format_writes_self_contained_report() {
+    let dir = tempdir().expect("tempdir");
+    let report = dir.path().join("report.html");
+
+    let output = run_compare(
+        &[
+            "compare",
+            "--ref",
+            asset("ref.png").to_str().unwrap(),
+            "--impl",
+            asset("impl_different.png").to_str().unwrap(),
+            "--threshold",
+            "0.99",
+            "--format",
+            "html",
+            "--output",
+            report.to_str().unwrap(),
+        ],
+        &[],
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    let html = std::fs::read_to_string(&report).expect("report written");
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("data:image/png;base64,"));
+    assert!(html.contains("data-view-btn=\"swipe\""));
+    assert!(html.contains("class=\"region pixel"));
+}
+
+#[test]
+fn report_command_renders_saved_json() {
+    let dir = tempdir().expect("tempdir");
+    let artifacts_dir = dir.path().join("artifacts");
+    let result = dir.path().join("result.json");
+    let report = dir.path().join("report.html");
+
+    let compare = run_compare(
+        &[
+            "compare",
+            "--ref",
+            asset("ref.png").to_str().unwrap(),
+            "--impl",
+            asset("impl_different.png").to_str().unwrap(),
+            "--threshold",
+            "0.5",
+            "--artifacts-dir",
+            artifacts_dir.to_str().unwrap(),
+            "--output",
+            result.to_str().unwrap(),
+        ],
+        &[],
+    );
+    assert!(compare.status.code().is_some_and(|c| c < 2));
+
+    let output = run_compare(
+        &[
+            "report",
+            result.to_str().unwrap(),
+            "--output",
+            report.to_str().unwrap(),
+        ],
+        &[],
+    );
+    assert_eq!(output.status.code(), Some(0));
+    let html = std::fs::read_to_string(&report).expect("report written");
+    assert!(html.contains("data:image/png;base64,"));
+
+    let missing = run_compare(
+        &["report", dir.path().join("nope.json").to_str().unwrap()],
+        &[],
+    );
+    assert_eq!(missing.status.code(), Some(2));
+    match parse_error(&missing.stdout) {
+        DpcOutput::Error(err) => assert!(err.error.message.contains("nope.json")),
+        other => panic!("expected error output, got {:?}", other),
+    }
+}
+
+#[test]
+fn junit_and_sarif_formats_keep_exit_codes() {
+    let args = |format: &'static str| {
+        vec![
+            "compare".to_string(),
+            "--ref".to_string(),
+            asset("ref.png").to_str().unwrap().to_string(),
+            "--impl".to_string(),
+            asset("impl_different.png").to_str().unwrap().to_string(),
+            "--threshold".to_string(),
+            "0.99".to_string(),
+            "--format".to_string(),
+            format.to_string(),
+        ]
+    };
+
+    let junit_args = args("junit");
+    let junit = run_compare(
+        &junit_args.iter().map(String::as_str).collect::<Vec<_>>(),
+        &[],
+    );
+    assert_eq!(junit.status.code(), Some(1));
+    let xml = String::from_utf8(junit.stdout).unwrap();
+    assert!(xml.starts_with("<?xml"));
+    assert!(xml.contains("<testcase classname=\"dpc.compare\" name=\"similarity\">"));
+    assert!(xml.contains("<failure"));
+
+    let sarif_args = args("sarif");
+    let sarif = run_compare(
+        &sarif_args.iter().map(String::as_str).collect::<Vec<_>>(),
+        &[],
+    );
+    assert_eq!(sarif.status.code(), Some(1));
+    let log: Value = serde_json::from_slice(&sarif.stdout).expect("sarif is JSON");
+    assert_eq!(log["version"], "2.1.0");
+    let results = log["runs"][0]["results"].as_array().unwrap();
+    assert!(results.iter().any(|r| r["ruleId"] == "threshold"));
+    assert!(results
+        .iter()
+        .any(|r| r["ruleId"].as_str().unwrap().starts_with("pixel/")));
+
+    let error = run_compare(
+        &[
+            "compare",
+            "--ref",
+            "missing.png",
+            "--impl",
+            "missing.png",
+            "--format",
+            "junit",
+        ],
+        &[],
+    );
+    assert_eq!(error.status.code(), Some(2));
+    assert!(String::from_utf8(error.stdout).unwrap().contains("<error "));
+}
+
+#[test]
+fn markdown_format_links_kept_artifacts_relative_to_output() {
+    let dir = tempdir().expect("tempdir");
+    let artifacts_dir = dir.path().join("artifacts");
+    let summary = dir.path().join("summary.md");
+
+    let output = run_compare(
+        &[
+            "compare",
+            "--ref",
+            asset("ref.png").to_str().unwrap(),
+            "--impl",
+            asset("impl_different.png").to_str().unwrap(),
+            "--threshold",
+            "0.99",
+            "--format",
+            "markdown",
+            "--output",
+            summary.to_str().unwrap(),
+            "--artifacts-dir",
+            artifacts_dir.to_str().unwrap(),
+        ],
+        &[],
+    );
+
+    assert_eq!(output.status.code(), Some(1));
+    let md = std::fs::read_to_string(&summary).expect("summary written");
+    assert!(md.contains("❌ **FAIL**"));
+    assert!(md.contains("| Metric | Score |"));
+    assert!(md.contains("](artifacts/ref_screenshot.png)"));
+
+    let quality = run_compare(
+        &[
+            "quality",
+            "--input",
+            asset("ref.png").to_str().unwrap(),
+            "--format",
+            "markdown",
+        ],
+        &[],
+    );
+    assert_eq!(quality.status.code(), Some(0));
+    assert!(String::from_utf8(quality.stdout)
+        .unwrap()
+        .starts_with("### Quality score"));
+}